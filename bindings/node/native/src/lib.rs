@@ -211,6 +211,10 @@ fn validate_bot(mut cx: FunctionContext) -> JsResult<JsObject> {
     }
 }
 
+/*
+* Run a request on a bot, given either as a whole `bot`, or by its `bot_id` and optionally
+* its `version_id`, the `label` pointing to the version to run, or a traffic `split`
+*/
 fn run_bot(mut cx: FunctionContext) -> JsResult<JsValue> {
     let raw_run_request = cx.argument::<JsValue>(0)?;
 
@@ -626,6 +630,178 @@ fn get_bot_versions_limit(mut cx: FunctionContext) -> JsResult<JsValue> {
     }
 }
 
+/*
+* List the labels of a bot
+*
+* [{"label": String, "version_id": String, "history": [String], "updated_at": String}]
+*/
+fn get_bot_labels(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+
+    match csml_engine::get_bot_labels(&bot_id) {
+        Ok(value) => {
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
+/*
+* Get a label of a bot
+*/
+fn get_bot_label(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+    let label = cx.argument::<JsString>(1)?.value();
+
+    match csml_engine::get_bot_label(&bot_id, &label) {
+        Ok(label) => {
+            let value = match label {
+                Some(label) => serde_json::json!(label),
+                None => {
+                    serde_json::json!({
+                        "error": "Not found"
+                    })
+                }
+            };
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
+/*
+* Make a label of a bot point to a given version, creating the label if needed
+*/
+fn promote_bot_version(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+    let label = cx.argument::<JsString>(1)?.value();
+    let version_id = cx.argument::<JsString>(2)?.value();
+
+    match csml_engine::promote_bot_version(&bot_id, &label, &version_id) {
+        Ok(value) => {
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
+/*
+* Make a label of a bot point back to its previous version, or to the version given as third argument
+*/
+fn rollback_bot_label(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+    let label = cx.argument::<JsString>(1)?.value();
+    let version_id = match cx.argument_opt(2) {
+        Some(arg) => arg.downcast::<JsString>().ok().map(|version_id| version_id.value()),
+        None => None,
+    };
+
+    match csml_engine::rollback_bot_label(&bot_id, &label, version_id.as_deref()) {
+        Ok(label) => {
+            let value = match label {
+                Some(label) => serde_json::json!(label),
+                None => {
+                    serde_json::json!({
+                        "error": "Not found"
+                    })
+                }
+            };
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
+/*
+* Delete a label of a bot
+*/
+fn delete_bot_label(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+    let label = cx.argument::<JsString>(1)?.value();
+
+    match csml_engine::delete_bot_label(&bot_id, &label) {
+        Ok(value) => {
+            let value= serde_json::json!(
+                value
+            );
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
+/*
+* Compare the flows of two versions of a bot
+*
+* {
+*   "from_version_id": String,
+*   "to_version_id": String,
+*   "default_flow": Option<{"from": String, "to": String}>,
+*   "flows": [{"name": String, "status": String, "steps": {"added": [String], "removed": [String]}, "commands": {..}, "diff": String}],
+*   "unchanged_flows": [String],
+* }
+*/
+fn diff_bot_versions(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let bot_id = cx.argument::<JsString>(0)?.value();
+    let from_version_id = cx.argument::<JsString>(1)?.value();
+    let to_version_id = cx.argument::<JsString>(2)?.value();
+
+    match csml_engine::diff_bot_versions(&bot_id, &from_version_id, &to_version_id) {
+        Ok(diff) => {
+            let value = match diff {
+                Some(diff) => diff,
+                None => {
+                    serde_json::json!({
+                        "error": "Not found"
+                    })
+                }
+            };
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+        Err(err) => {
+            let value = serde_json::json!({
+                "error": format!("{:?}", err),
+            });
+
+            Ok(neon_serde::to_value(&mut cx, &value)?)
+        },
+    }
+}
+
 register_module!(mut cx, {
     cx.export_function("validateBot", validate_bot)?;
 
@@ -635,6 +811,13 @@ register_module!(mut cx, {
     cx.export_function("getBotVersionsLimit", get_bot_versions_limit)?;
     cx.export_function("deleteBotVersion", delete_bot_version)?;
     cx.export_function("deleteBotVersions", delete_bot_versions)?;
+    cx.export_function("diffBotVersions", diff_bot_versions)?;
+
+    cx.export_function("getBotLabels", get_bot_labels)?;
+    cx.export_function("getBotLabel", get_bot_label)?;
+    cx.export_function("promoteBotVersion", promote_bot_version)?;
+    cx.export_function("rollbackBotLabel", rollback_bot_label)?;
+    cx.export_function("deleteBotLabel", delete_bot_label)?;
 
     cx.export_function("createClientMemory ", create_client_memory)?;
     cx.export_function("getClientMemories", get_client_memories)?;
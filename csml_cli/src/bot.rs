@@ -0,0 +1,178 @@
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow};
+use csml_interpreter::{load_components, validate_bot};
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Optional bot.json at the root of the bot directory
+ *
+ * {
+ *     "name": "pizza bot",
+ *     "default_flow": "welcome",
+ *     "commands": {"order": ["order", "pizza"]},
+ *     "env": {"api_url": "https://api.example.com"}
+ * }
+ */
+#[derive(Debug, Default, Deserialize)]
+struct BotConfig {
+    name: Option<String>,
+    default_flow: Option<String>,
+    #[serde(default)]
+    commands: HashMap<String, Vec<String>>,
+    env: Option<Value>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let content =
+        fs::read_to_string(path).map_err(|err| format!("can not read {}: {}", path.display(), err))?;
+
+    serde_json::from_str(&content).map_err(|err| format!("invalid JSON in {}: {}", path.display(), err))
+}
+
+/**
+ * Files of the directory with the extension, sorted by name
+ */
+fn get_files(dir: &Path, extension: &str) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|err| format!("can not read {}: {}", dir.display(), err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && path.extension().map_or(false, |ext| ext == extension))
+        .collect();
+    files.sort();
+
+    Ok(files)
+}
+
+fn get_name(path: &Path) -> Result<String, String> {
+    match path.file_stem().and_then(|name| name.to_str()) {
+        Some(name) => Ok(name.to_owned()),
+        None => Err(format!("invalid file name {}", path.display())),
+    }
+}
+
+fn get_flows(dir: &Path, config: &BotConfig) -> Result<Vec<CsmlFlow>, String> {
+    let mut flows = vec![];
+
+    for path in get_files(dir, "csml")? {
+        // test files of the bot are run by `csml test`
+        if path.to_string_lossy().ends_with(".test.csml") {
+            continue;
+        }
+
+        let name = get_name(&path)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|err| format!("can not read flow {}: {}", path.display(), err))?;
+        let commands = config.commands.get(&name).cloned().unwrap_or_default();
+
+        flows.push(CsmlFlow::new(&name, &name, &content, commands));
+    }
+
+    Ok(flows)
+}
+
+/**
+ * Custom components of the bot, one components/NAME.json file per component
+ */
+fn get_custom_components(dir: &Path) -> Result<Option<Value>, String> {
+    let dir = dir.join("components");
+    if !dir.is_dir() {
+        return Ok(None);
+    }
+
+    let mut components = Map::new();
+    for path in get_files(&dir, "json")? {
+        components.insert(get_name(&path)?, read_json(&path)?);
+    }
+
+    Ok(Some(Value::Object(components)))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Bot made of the .csml files of the directory, one flow per file named after the file.
+ *
+ * The default flow is the given one, else the one of bot.json, else the flow named
+ * "default", else the first flow by name.
+ */
+pub fn load_bot(dir: &Path, default_flow: Option<&str>) -> Result<CsmlBot, String> {
+    let config_path = dir.join("bot.json");
+    let config: BotConfig = match config_path.is_file() {
+        true => read_json(&config_path)?,
+        false => BotConfig::default(),
+    };
+
+    let flows = get_flows(dir, &config)?;
+    if flows.is_empty() {
+        return Err(format!("no .csml flow in {}", dir.display()));
+    }
+
+    let default_flow = match default_flow.map(|flow| flow.to_owned()).or_else(|| config.default_flow.clone()) {
+        Some(flow) => flow,
+        None if flows.iter().any(|flow| flow.name == "default") => "default".to_owned(),
+        None => flows[0].name.to_owned(),
+    };
+    if !flows.iter().any(|flow| flow.name == default_flow) {
+        return Err(format!("default flow '{}' does not exist in this bot", default_flow));
+    }
+
+    let name = match &config.name {
+        Some(name) => name.to_owned(),
+        None => get_name(dir).unwrap_or_else(|_| "bot".to_owned()),
+    };
+    let native_components = load_components().map_err(|err| err.format_error())?;
+
+    let bot = CsmlBot::new(
+        &name,
+        &name,
+        None,
+        flows,
+        Some(native_components),
+        get_custom_components(dir)?,
+        &default_flow,
+        None,
+        config.env.clone(),
+    );
+
+    match validate_bot(&bot).errors {
+        Some(errors) if !errors.is_empty() => {
+            let errors: Vec<String> = errors.iter().map(|err| err.format_error()).collect();
+            Err(format!("{}: invalid bot\n{}", dir.display(), errors.join("\n")))
+        }
+        _ => Ok(bot),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_load_bot() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/bot");
+        let bot = load_bot(&dir, None).unwrap();
+
+        let flows: Vec<&str> = bot.flows.iter().map(|flow| flow.name.as_str()).collect();
+        assert_eq!(flows, vec!["default", "order"]);
+        assert_eq!(bot.default_flow, "default");
+        assert_eq!(bot.flows[1].commands, vec!["order", "pizza"]);
+        assert!(bot.custom_components.unwrap().get("Quote").is_some());
+
+        assert_eq!(load_bot(&dir, Some("order")).unwrap().default_flow, "order");
+        assert!(load_bot(&dir, Some("unknown")).is_err());
+    }
+}
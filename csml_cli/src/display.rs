@@ -0,0 +1,154 @@
+use csml_interpreter::data::Message;
+use serde_json::Value;
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_str<'a>(content: &'a Value, key: &str) -> Option<&'a str> {
+    content.get(key).and_then(|value| value.as_str())
+}
+
+/**
+ * Content of a component that can be given either alone or as {"content_type", "content"},
+ * like the buttons of a question or the cards of a carousel
+ */
+fn get_content(value: &Value) -> &Value {
+    match value.get("content_type") {
+        Some(_) => value.get("content").unwrap_or(value),
+        None => value,
+    }
+}
+
+/**
+ * Numbered button, the number can be typed to send its payload
+ */
+fn format_button(button: &Value, lines: &mut Vec<String>, payloads: &mut Vec<String>) {
+    let button = get_content(button);
+    let title = get_str(button, "title").unwrap_or_default();
+    let payload = get_str(button, "payload").unwrap_or(title);
+
+    payloads.push(payload.to_owned());
+    lines.push(format!("     [{}] {}", payloads.len(), title));
+}
+
+fn format_buttons(content: &Value, lines: &mut Vec<String>, payloads: &mut Vec<String>) {
+    if let Some(buttons) = content.get("buttons").and_then(|buttons| buttons.as_array()) {
+        for button in buttons.iter() {
+            format_button(button, lines, payloads);
+        }
+    }
+}
+
+fn format_card(card: &Value, lines: &mut Vec<String>, payloads: &mut Vec<String>) {
+    let card = get_content(card);
+
+    lines.push(format!("bot: [card] {}", get_str(card, "title").unwrap_or_default()));
+    if let Some(subtitle) = get_str(card, "subtitle") {
+        lines.push(format!("     {}", subtitle));
+    }
+    if let Some(image_url) = get_str(card, "image_url") {
+        lines.push(format!("     {}", image_url));
+    }
+    format_buttons(card, lines, payloads);
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Lines displaying the messages of the bot in a terminal, and the payloads of their
+ * buttons in the order they are numbered
+ */
+pub fn format_messages(messages: &[Message]) -> (Vec<String>, Vec<String>) {
+    let mut lines = vec![];
+    let mut payloads = vec![];
+
+    for message in messages.iter() {
+        let content = &message.content;
+
+        match message.content_type.as_str() {
+            "text" => lines.push(format!("bot: {}", get_str(content, "text").unwrap_or_default())),
+            "question" => {
+                lines.push(format!("bot: {}", get_str(content, "title").unwrap_or_default()));
+                format_buttons(content, &mut lines, &mut payloads);
+            }
+            "button" => format_button(content, &mut lines, &mut payloads),
+            "card" => format_card(content, &mut lines, &mut payloads),
+            "carousel" => {
+                let cards = content.get("cards").and_then(|cards| cards.as_array());
+                for card in cards.into_iter().flatten() {
+                    format_card(card, &mut lines, &mut payloads);
+                }
+            }
+            "image" | "video" | "audio" | "file" | "url" => {
+                let url = get_str(content, "url").unwrap_or_default();
+                match get_str(content, "text").or_else(|| get_str(content, "title")) {
+                    Some(text) => lines.push(format!("bot: [{}] {} {}", message.content_type, text, url)),
+                    None => lines.push(format!("bot: [{}] {}", message.content_type, url)),
+                }
+            }
+            "typing" | "wait" => lines.push(format!(
+                "bot: [{} {}ms]",
+                message.content_type,
+                content.get("duration").unwrap_or(&Value::Null)
+            )),
+            "error" => lines.push(format!("bot: [error] {}", get_str(content, "error").unwrap_or_default())),
+            content_type => lines.push(format!("bot: [{}] {}", content_type, content)),
+        }
+    }
+
+    (lines, payloads)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn message(content_type: &str, content: Value) -> Message {
+        Message {
+            content_type: content_type.to_owned(),
+            content,
+        }
+    }
+
+    #[test]
+    fn ok_format_messages() {
+        let button = |title: &str, payload: &str| {
+            json!({"content_type": "button", "content": {"title": title, "payload": payload}})
+        };
+        let messages = vec![
+            message("text", json!({"text": "Hello"})),
+            message(
+                "question",
+                json!({"title": "Pizza?", "buttons": [button("Yes", "yes"), button("No", "no")]}),
+            ),
+            message(
+                "carousel",
+                json!({"cards": [{"content_type": "card", "content": {"title": "Margherita", "buttons": [button("Order", "order")]}}]}),
+            ),
+            message("image", json!({"url": "https://example.com/pizza.png"})),
+            message("typing", json!({"duration": 500})),
+            message("Component.quote", json!({"text": "hot"})),
+        ];
+
+        let (lines, payloads) = format_messages(&messages);
+        assert_eq!(
+            lines,
+            vec![
+                "bot: Hello",
+                "bot: Pizza?",
+                "     [1] Yes",
+                "     [2] No",
+                "bot: [card] Margherita",
+                "     [3] Order",
+                "bot: [image] https://example.com/pizza.png",
+                "bot: [typing 500ms]",
+                "bot: [Component.quote] {\"text\":\"hot\"}",
+            ]
+        );
+        assert_eq!(payloads, vec!["yes", "no", "order"]);
+    }
+}
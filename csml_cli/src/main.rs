@@ -0,0 +1,232 @@
+mod bot;
+mod display;
+mod repl;
+mod transcript;
+
+use bot::load_bot;
+use repl::Repl;
+use transcript::{parse_now, Transcript};
+
+use csml_interpreter::data::{CsmlBot, MockTransport};
+use csml_interpreter::test_runner::{run_tests, Conversation};
+
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Arc;
+
+const USAGE: &str = "\
+usage:
+  csml run BOT_DIR [options]            talk to the bot in the terminal
+  csml replay BOT_DIR TRANSCRIPT...     replay recorded conversations, exit 1 on mismatch
+  csml test [PATH]...                   run the *.test.csml files
+
+options:
+  --flow NAME         default flow of the bot
+  --metadata JSON     metadata of the user
+  --fixtures FILE     answer the HTTP requests with the fixtures of the JSON file
+  --now DATE          RFC 3339 date returned by Time()
+  --memory            keep the memories when a conversation ends
+  --record FILE       record the conversation to a YAML transcript";
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Default)]
+struct Options {
+    paths: Vec<PathBuf>,
+    flow: Option<String>,
+    metadata: Option<Value>,
+    fixtures: Option<PathBuf>,
+    now: Option<String>,
+    memory: bool,
+    record: Option<PathBuf>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| match args.next() {
+            Some(value) => Ok(value.to_owned()),
+            None => Err(format!("missing value of {}", name)),
+        };
+
+        match arg.as_str() {
+            "--flow" => options.flow = Some(value(arg)?),
+            "--metadata" => {
+                let metadata = value(arg)?;
+                options.metadata = Some(
+                    serde_json::from_str(&metadata)
+                        .map_err(|err| format!("invalid --metadata '{}': {}", metadata, err))?,
+                );
+            }
+            "--fixtures" => options.fixtures = Some(PathBuf::from(value(arg)?)),
+            "--now" => {
+                let now = value(arg)?;
+                parse_now(&now)?;
+                options.now = Some(now);
+            }
+            "--memory" => options.memory = true,
+            "--record" => options.record = Some(PathBuf::from(value(arg)?)),
+            arg if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            path => options.paths.push(PathBuf::from(path)),
+        }
+    }
+
+    Ok(options)
+}
+
+fn get_fixtures(options: &Options) -> Result<Option<Arc<MockTransport>>, String> {
+    match &options.fixtures {
+        Some(path) => {
+            let fixtures = fs::read_to_string(path)
+                .map_err(|err| format!("can not read fixtures {}: {}", path.display(), err))?;
+
+            Ok(Some(Arc::new(MockTransport::from_json(&fixtures)?)))
+        }
+        None => Ok(None),
+    }
+}
+
+fn get_bot(options: &Options) -> Result<CsmlBot, String> {
+    match options.paths.first() {
+        Some(dir) => load_bot(dir, options.flow.as_deref()),
+        None => Err("missing BOT_DIR".to_owned()),
+    }
+}
+
+fn run(options: &Options) -> Result<i32, String> {
+    let bot = get_bot(options)?;
+    let metadata = options.metadata.clone().unwrap_or(Value::Null);
+
+    let mut conversation = Conversation::new(&bot, metadata.to_owned());
+    conversation.keep_memories = options.memory;
+    conversation.now = options.now.as_deref().map(parse_now).transpose()?;
+    if let Some(fixtures) = get_fixtures(options)? {
+        conversation.http_transport = Some(fixtures);
+    }
+
+    let record = options
+        .record
+        .as_ref()
+        .map(|path| (path.to_owned(), Transcript::new(metadata, options.now.clone(), options.memory)));
+
+    let stdin = io::stdin();
+    Repl::new(conversation, record)
+        .run(&mut stdin.lock(), &mut io::stdout())
+        .map_err(|err| err.to_string())?;
+
+    Ok(0)
+}
+
+fn replay(options: &Options) -> Result<i32, String> {
+    let bot = get_bot(options)?;
+    if options.paths.len() < 2 {
+        return Err("missing TRANSCRIPT".to_owned());
+    }
+
+    let fixtures = get_fixtures(options)?;
+    let mut failed = 0;
+    for path in options.paths[1..].iter() {
+        let transcript = Transcript::read(path)?;
+        let mut conversation = Conversation::new(&bot, Value::Null);
+
+        match transcript.replay(&mut conversation, fixtures.clone()) {
+            Ok(()) => println!("ok      {}", path.display()),
+            Err(mismatch) => {
+                println!("FAILED  {} (turn {})", path.display(), mismatch.turn);
+                for line in mismatch.diff.lines() {
+                    println!("    {}", line);
+                }
+                failed += 1;
+            }
+        }
+    }
+
+    println!("\n{} passed, {} failed", options.paths.len() - 1 - failed, failed);
+
+    Ok(if failed > 0 { 1 } else { 0 })
+}
+
+fn test(options: &Options) -> Result<i32, String> {
+    let paths = match options.paths.is_empty() {
+        true => vec![PathBuf::from(".")],
+        false => options.paths.to_owned(),
+    };
+
+    let summary = run_tests(&paths, &mut io::stdout()).map_err(|err| err.to_string())?;
+
+    Ok(summary.exit_code())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.split_first() {
+        Some((command, args)) => get_options(args).and_then(|options| match command.as_str() {
+            "run" => run(&options),
+            "replay" => replay(&options),
+            "test" => test(&options),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                Ok(0)
+            }
+            command => Err(format!("unknown command '{}'\n\n{}", command, USAGE)),
+        }),
+        None => Err(USAGE.to_owned()),
+    };
+
+    match result {
+        Ok(code) => process::exit(code),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn ok_get_options() {
+        let options = get_options(&args(&[
+            "bot",
+            "--flow",
+            "order",
+            "--metadata",
+            "{\"firstname\": \"Tom\"}",
+            "--memory",
+            "--now",
+            "2021-06-01T10:00:00Z",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.paths, vec![PathBuf::from("bot")]);
+        assert_eq!(options.flow.as_deref(), Some("order"));
+        assert_eq!(options.metadata, Some(serde_json::json!({"firstname": "Tom"})));
+        assert!(options.memory);
+
+        assert!(get_options(&args(&["bot", "--flow"])).is_err());
+        assert!(get_options(&args(&["bot", "--now", "tomorrow"])).is_err());
+        assert!(get_options(&args(&["bot", "--verbose"])).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::display::format_messages;
+use crate::transcript::{messages_to_json, Transcript, Turn};
+
+use csml_interpreter::test_runner::{get_json_event, Conversation};
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+const HELP: &str = "\
+Type a message to send it to the bot, or:
+  NUMBER        click the button with this number
+  {...}         send a JSON event {\"content_type\": ..., \"content\": {...}}
+  :flow NAME    start a flow
+  :memories     show the memories of the user
+  :reset        start a new conversation, forgetting the memories
+  :help         show this help
+  :quit         leave (or Ctrl-D)";
+
+/**
+ * Interactive conversation with a bot, recorded to a transcript file if there is one
+ */
+pub struct Repl<'a> {
+    pub conversation: Conversation<'a>,
+    pub record: Option<(PathBuf, Transcript)>,
+    // payloads of the buttons of the last messages, by number
+    buttons: Vec<String>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Repl<'a> {
+    /**
+     * The user event matching the input line, None if the line is empty or a command
+     */
+    fn get_user_event(&mut self, line: &str, output: &mut dyn Write) -> io::Result<Option<Value>> {
+        let (command, args) = match line.find(char::is_whitespace) {
+            Some(index) => (&line[..index], line[index..].trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "" => Ok(None),
+            ":help" => {
+                writeln!(output, "{}", HELP)?;
+                Ok(None)
+            }
+            ":memories" => {
+                let memories = Value::Object(self.conversation.memories.to_owned());
+                writeln!(output, "{}", serde_json::to_string_pretty(&memories).unwrap_or_default())?;
+                Ok(None)
+            }
+            ":reset" => {
+                self.conversation.reset();
+                self.buttons.clear();
+                if let Some((_, transcript)) = &mut self.record {
+                    transcript.turns.clear();
+                    writeln!(output, "(new conversation, the recording starts again)")?;
+                }
+                Ok(None)
+            }
+            ":flow" if !args.is_empty() => Ok(Some(json!({
+                "content_type": "flow_trigger",
+                "content": {"flow_id": args},
+            }))),
+            command if command.starts_with(':') => {
+                writeln!(output, "unknown command '{}', type :help", line)?;
+                Ok(None)
+            }
+            _ if line.starts_with('{') => match serde_json::from_str(line) {
+                Ok(event) => Ok(Some(event)),
+                Err(err) => {
+                    writeln!(output, "invalid JSON event: {}", err)?;
+                    Ok(None)
+                }
+            },
+            _ => match line.parse::<usize>() {
+                Ok(number) if number > 0 && number <= self.buttons.len() => Ok(Some(json!({
+                    "content_type": "payload",
+                    "content": {"payload": self.buttons[number - 1]},
+                }))),
+                _ => Ok(Some(Value::String(line.to_owned()))),
+            },
+        }
+    }
+
+    fn send(&mut self, user: Value, output: &mut dyn Write) -> io::Result<()> {
+        let event = match get_json_event(&user) {
+            Ok(event) => event,
+            Err(err) => return writeln!(output, "invalid event: {}", err),
+        };
+
+        let messages = self.conversation.send_event(event);
+        let (lines, buttons) = format_messages(&messages);
+        for line in lines.iter() {
+            writeln!(output, "{}", line)?;
+        }
+        self.buttons = buttons;
+
+        if let Some((path, transcript)) = &mut self.record {
+            transcript.turns.push(Turn {
+                user,
+                bot: messages_to_json(&messages),
+            });
+            // written after each turn, so that nothing is lost if the CLI is killed
+            if let Err(err) = transcript.write(path) {
+                writeln!(output, "error: {}", err)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a> Repl<'a> {
+    pub fn new(conversation: Conversation<'a>, record: Option<(PathBuf, Transcript)>) -> Self {
+        Self {
+            conversation,
+            record,
+            buttons: vec![],
+        }
+    }
+
+    /**
+     * Read the user input line by line until :quit or the end of the input
+     */
+    pub fn run(&mut self, input: &mut dyn BufRead, output: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            output,
+            "Talking to {} (flow {}), type :help for help",
+            self.conversation.bot.name, self.conversation.flow
+        )?;
+
+        loop {
+            write!(output, "> ")?;
+            output.flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                return Ok(());
+            }
+
+            let line = line.trim();
+            if line == ":quit" || line == ":exit" {
+                return Ok(());
+            }
+
+            if let Some(user) = self.get_user_event(line, output)? {
+                self.send(user, output)?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::load_bot;
+    use std::path::Path;
+
+    #[test]
+    fn ok_run() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/bot");
+        let bot = load_bot(&dir, None).unwrap();
+        let mut repl = Repl::new(Conversation::new(&bot, Value::Null), None);
+
+        let mut input = "hi\n1\n2\n:memories\n:quit\nnot sent\n".as_bytes();
+        let mut output = vec![];
+        repl.run(&mut input, &mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("bot: What do you want to do?\n     [1] Order a pizza\n     [2] Read a quote"));
+        assert!(output.contains("bot: Which size?\n     [1] Small\n     [2] Large"));
+        assert!(output.contains("bot: One Large pizza, coming right up!"));
+        assert!(output.contains("\"size\": \"Large\""));
+        assert!(!output.contains("not sent"));
+    }
+}
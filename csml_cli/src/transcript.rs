@@ -0,0 +1,165 @@
+use csml_interpreter::data::{Message, MockTransport};
+use csml_interpreter::test_runner::{get_diff, get_json_event, Conversation};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * An event of the user and the messages the bot answered
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Turn {
+    // "hello" for a text, otherwise {"content_type": String, "content": Object}
+    pub user: Value,
+    #[serde(default)]
+    pub bot: Vec<Value>,
+}
+
+/**
+ * A conversation recorded with `csml run --record`, replayed by `csml replay`
+ *
+ * metadata:
+ *   firstname: Tom
+ * now: "2021-06-01T10:00:00Z"
+ * memory: false
+ * turns:
+ *   - user: hello
+ *     bot: [{content_type: text, content: {text: Hi Tom!}}]
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transcript {
+    #[serde(default)]
+    pub metadata: Value,
+    // RFC 3339 date returned by Time(), the current date if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub now: Option<String>,
+    // keep the memories when a conversation ends
+    #[serde(default)]
+    pub memory: bool,
+    #[serde(default)]
+    pub turns: Vec<Turn>,
+}
+
+/**
+ * First turn of a transcript where the bot did not answer the recorded messages
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    // index of the turn, from 1
+    pub turn: usize,
+    pub diff: String,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn parse_now(now: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(now)
+        .map(|now| now.with_timezone(&Utc))
+        .map_err(|_| format!("'{}' is not a valid RFC 3339 date", now))
+}
+
+pub fn messages_to_json(messages: &[Message]) -> Vec<Value> {
+    messages
+        .iter()
+        .map(|message| message.to_owned().message_to_json())
+        .collect()
+}
+
+impl Transcript {
+    pub fn new(metadata: Value, now: Option<String>, memory: bool) -> Self {
+        Self {
+            metadata,
+            now,
+            memory,
+            turns: vec![],
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|err| format!("can not read {}: {}", path.display(), err))?;
+
+        serde_yaml::from_str(&content).map_err(|err| format!("invalid transcript {}: {}", path.display(), err))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let content = serde_yaml::to_string(self).map_err(|err| err.to_string())?;
+
+        fs::write(path, content).map_err(|err| format!("can not write {}: {}", path.display(), err))
+    }
+
+    /**
+     * Send the events of the user to the bot in a new conversation, and compare its
+     * messages with the recorded ones. HTTP requests are answered by the fixtures.
+     */
+    pub fn replay(&self, conversation: &mut Conversation, fixtures: Option<Arc<MockTransport>>) -> Result<(), Mismatch> {
+        conversation.metadata = self.metadata.to_owned();
+        conversation.keep_memories = self.memory;
+        conversation.now = match &self.now {
+            Some(now) => Some(parse_now(now).map_err(|diff| Mismatch { turn: 0, diff })?),
+            None => None,
+        };
+        if let Some(fixtures) = fixtures {
+            conversation.http_transport = Some(fixtures);
+        }
+        conversation.reset();
+
+        for (index, turn) in self.turns.iter().enumerate() {
+            let event = get_json_event(&turn.user).map_err(|diff| Mismatch { turn: index + 1, diff })?;
+            let actual = Value::Array(messages_to_json(&conversation.send_event(event)));
+            let expected = Value::Array(turn.bot.to_owned());
+
+            if actual != expected {
+                return Err(Mismatch {
+                    turn: index + 1,
+                    diff: get_diff(&expected, &actual),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::load_bot;
+
+    #[test]
+    fn ok_replay() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/bot");
+        let bot = load_bot(&dir, None).unwrap();
+        let mut transcript = Transcript::read(&dir.join("order.yaml")).unwrap();
+
+        let mut conversation = Conversation::new(&bot, Value::Null);
+        assert_eq!(transcript.replay(&mut conversation, None), Ok(()));
+
+        transcript.turns[1].bot[1]["content"]["text"] = Value::String("Two small pizzas".to_owned());
+        let mismatch = transcript.replay(&mut conversation, None).unwrap_err();
+        assert_eq!(mismatch.turn, 2);
+        assert!(mismatch.diff.contains("-       \"text\": \"Two small pizzas\""));
+    }
+
+    #[test]
+    fn ok_yaml() {
+        let mut transcript = Transcript::new(serde_json::json!({"firstname": "Tom"}), None, false);
+        transcript.turns.push(Turn {
+            user: Value::String("hello".to_owned()),
+            bot: vec![serde_json::json!({"content_type": "text", "content": {"text": "Hi Tom!"}})],
+        });
+
+        let yaml = serde_yaml::to_string(&transcript).unwrap();
+        assert_eq!(serde_yaml::from_str::<Transcript>(&yaml).unwrap(), transcript);
+    }
+}
@@ -0,0 +1,355 @@
+use crate::data::{Database, EngineError};
+use crate::db_connectors::{
+    conversations, interactions, nodes, DbConversation, DbInteraction, DbNode,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// every conversation, interaction and node of the time range is loaded in memory
+const MAX_ANALYTICS_DAYS: i64 = 90;
+
+/**
+ * The time range of the analytics: `to` defaults to now and `from` to 90 days before `to`.
+ * A time range longer than 90 days is rejected.
+ */
+pub fn get_date_range(
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), EngineError> {
+    let max_range = Duration::days(MAX_ANALYTICS_DAYS);
+    let to = to.unwrap_or(now);
+    let from = from.unwrap_or(to - max_range);
+
+    if from > to {
+        return Err(EngineError::Format("'from' must be before 'to'".to_owned()));
+    }
+    if to - from > max_range {
+        return Err(EngineError::Format(format!(
+            "the time range can not be longer than {} days",
+            MAX_ANALYTICS_DAYS
+        )));
+    }
+
+    Ok((from, to))
+}
+
+/**
+ * Aggregate the conversations created for a bot in a given time range,
+ * with their paths (nodes), and the interactions received in the same time range.
+ */
+pub fn get_bot_analytics(
+    bot_id: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    db: &mut Database,
+) -> Result<Value, EngineError> {
+    let conversations = conversations::get_bot_conversations(bot_id, Some(from), Some(to), db)?;
+    let interactions = interactions::get_bot_interactions(bot_id, Some(from), Some(to), db)?;
+
+    // no node of these conversations is older than them
+    let mut nodes = nodes::get_bot_nodes(bot_id, &conversations, Some(from), db)?;
+
+    let paths: Vec<Vec<(String, String)>> = conversations
+        .iter()
+        .map(|conversation| {
+            let nodes = nodes.remove(&conversation.id).unwrap_or_default();
+            get_conversation_path(conversation, &nodes)
+        })
+        .collect();
+
+    Ok(compute_analytics(&conversations, &paths, &interactions))
+}
+
+/**
+ * List every (flow, step) a conversation went through, starting with the step
+ * where the conversation started. Reaching the end of the conversation is the `end` step.
+ */
+fn get_conversation_path(conversation: &DbConversation, nodes: &[DbNode]) -> Vec<(String, String)> {
+    let start = match nodes.first() {
+        Some(node) => (node.flow_id.to_owned(), node.step_id.to_owned()),
+        None => (conversation.flow_id.to_owned(), conversation.step_id.to_owned()),
+    };
+
+    let mut path = vec![start];
+    for node in nodes.iter() {
+        let flow = node.next_flow.as_ref().unwrap_or(&node.flow_id);
+
+        if let Some(step) = &node.next_step {
+            path.push((flow.to_owned(), step.to_owned()));
+        }
+    }
+
+    path
+}
+
+/**
+ * - most visited steps: number of times each step was reached
+ * - drop-off points: last step of the conversations that never reached `end`
+ *   (open conversations are counted where they currently stand)
+ * - flows: a flow is completed when the conversation reaches `end` in this flow
+ *   or moves on to another flow
+ * - average length: number of steps and duration of a conversation
+ * - error rate: share of interactions that did not succeed
+ * - versions: interactions and error rate of each version picked by a traffic split
+ */
+fn compute_analytics(
+    conversations: &[DbConversation],
+    paths: &[Vec<(String, String)>],
+    interactions: &[DbInteraction],
+) -> Value {
+    let mut visits: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut drop_offs: HashMap<(&str, &str), usize> = HashMap::new();
+    let mut flows: HashMap<&str, (usize, usize)> = HashMap::new();
+    let mut total_steps = 0;
+
+    for path in paths.iter() {
+        for (index, (flow, step)) in path.iter().enumerate() {
+            if step != "end" {
+                *visits.entry((flow, step)).or_insert(0) += 1;
+                total_steps += 1;
+            }
+
+            let entered = index == 0 || path[index - 1].0 != *flow;
+            let left = match path.get(index + 1) {
+                Some((next_flow, _)) => next_flow != flow,
+                None => step == "end",
+            };
+
+            let (started, completed) = flows.entry(flow).or_insert((0, 0));
+            if entered {
+                *started += 1;
+            }
+            if left {
+                *completed += 1;
+            }
+        }
+
+        if let Some((flow, step)) = path.last() {
+            if step != "end" {
+                *drop_offs.entry((flow, step)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let total_duration: usize = conversations
+        .iter()
+        .filter_map(|conversation| {
+            let created_at = DateTime::parse_from_rfc3339(&conversation.created_at).ok()?;
+            let last_interaction_at =
+                DateTime::parse_from_rfc3339(&conversation.last_interaction_at).ok()?;
+
+            Some((last_interaction_at - created_at).num_milliseconds().max(0) as usize)
+        })
+        .sum();
+
+    let failed_interactions = interactions
+        .iter()
+        .filter(|interaction| !interaction.success)
+        .count();
+
+    json!({
+        "conversations": conversations.len(),
+        "interactions": interactions.len(),
+        "failed_interactions": failed_interactions,
+        "error_rate": ratio(failed_interactions, interactions.len()),
+        "average_conversation_length": {
+            "steps": ratio(total_steps, conversations.len()),
+            "duration_seconds": ratio(total_duration, conversations.len()) / 1000.0,
+        },
+        "most_visited_steps": sort_by_count(visits, "visits"),
+        "drop_off_points": sort_by_count(drop_offs, "conversations"),
+        "flows": get_flows_completion(flows),
+        "versions": get_versions_outcome(interactions),
+    })
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    match total {
+        0 => 0.0,
+        total => count as f64 / total as f64,
+    }
+}
+
+fn sort_by_count(counts: HashMap<(&str, &str), usize>, name: &str) -> Vec<Value> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a_key, a_count), (b_key, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+    });
+
+    counts
+        .into_iter()
+        .map(|((flow, step), count)| json!({"flow": flow, "step": step, name: count}))
+        .collect()
+}
+
+fn get_flows_completion(flows: HashMap<&str, (usize, usize)>) -> Vec<Value> {
+    let mut flows: Vec<_> = flows.into_iter().collect();
+    flows.sort_by(|(a_flow, (a_started, _)), (b_flow, (b_started, _))| {
+        b_started.cmp(a_started).then_with(|| a_flow.cmp(b_flow))
+    });
+
+    flows
+        .into_iter()
+        .map(|(flow, (started, completed))| {
+            json!({
+                "flow": flow,
+                "started": started,
+                "completed": completed,
+                "completion_rate": ratio(completed, started),
+            })
+        })
+        .collect()
+}
+
+fn get_versions_outcome(interactions: &[DbInteraction]) -> Vec<Value> {
+    let mut versions: HashMap<&str, (usize, usize)> = HashMap::new();
+    for interaction in interactions.iter() {
+        if let Some(version_id) = &interaction.version_id {
+            let (total, failed) = versions.entry(version_id).or_insert((0, 0));
+            *total += 1;
+            if !interaction.success {
+                *failed += 1;
+            }
+        }
+    }
+
+    let mut versions: Vec<_> = versions.into_iter().collect();
+    versions.sort();
+
+    versions
+        .into_iter()
+        .map(|(version_id, (total, failed))| {
+            json!({
+                "version_id": version_id,
+                "interactions": total,
+                "failed_interactions": failed,
+                "error_rate": ratio(failed, total),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+
+    fn conversation(id: &str, flow: &str, step: &str, duration: u32) -> DbConversation {
+        DbConversation {
+            id: id.to_owned(),
+            client: Client {
+                bot_id: "botid".to_owned(),
+                channel_id: "channelid".to_owned(),
+                user_id: id.to_owned(),
+            },
+            flow_id: flow.to_owned(),
+            step_id: step.to_owned(),
+            status: "CLOSED".to_owned(),
+            last_interaction_at: format!("2021-01-01T00:00:{:02}.000Z", duration),
+            updated_at: "2021-01-01T00:00:00.000Z".to_owned(),
+            created_at: "2021-01-01T00:00:00.000Z".to_owned(),
+        }
+    }
+
+    fn interaction(version_id: Option<&str>, success: bool) -> DbInteraction {
+        DbInteraction {
+            id: "id".to_owned(),
+            client: Client {
+                bot_id: "botid".to_owned(),
+                channel_id: "channelid".to_owned(),
+                user_id: "userid".to_owned(),
+            },
+            success,
+            event: json!({}),
+            version_id: version_id.map(|id| id.to_owned()),
+            updated_at: "2021-01-01T00:00:00.000Z".to_owned(),
+            created_at: "2021-01-01T00:00:00.000Z".to_owned(),
+        }
+    }
+
+    fn step(flow: &str, step: &str) -> (String, String) {
+        (flow.to_owned(), step.to_owned())
+    }
+
+    #[test]
+    fn ok_analytics() {
+        let conversations = vec![
+            conversation("1", "Default", "end", 10),
+            conversation("2", "Other", "question", 20),
+        ];
+        let paths = vec![
+            vec![step("Default", "start"), step("Other", "start"), step("Other", "end")],
+            vec![step("Default", "start"), step("Other", "start"), step("Other", "question")],
+        ];
+
+        let analytics = compute_analytics(&conversations, &paths, &[]);
+
+        assert_eq!(analytics["conversations"], 2);
+        assert_eq!(analytics["error_rate"], 0.0);
+        assert_eq!(analytics["average_conversation_length"]["steps"], 2.5);
+        assert_eq!(analytics["average_conversation_length"]["duration_seconds"], 15.0);
+        assert_eq!(
+            analytics["most_visited_steps"][0],
+            json!({"flow": "Default", "step": "start", "visits": 2})
+        );
+        assert_eq!(
+            analytics["drop_off_points"],
+            json!([{"flow": "Other", "step": "question", "conversations": 1}])
+        );
+        assert_eq!(
+            analytics["flows"],
+            json!([
+                {"flow": "Default", "started": 2, "completed": 2, "completion_rate": 1.0},
+                {"flow": "Other", "started": 2, "completed": 1, "completion_rate": 0.5},
+            ])
+        );
+    }
+
+    fn date(date: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn ok_date_range() {
+        let now = date("2021-06-01T00:00:00Z");
+
+        assert_eq!(
+            get_date_range(None, None, now).unwrap(),
+            (date("2021-03-03T00:00:00Z"), now)
+        );
+        assert_eq!(
+            get_date_range(None, Some(date("2021-04-01T00:00:00Z")), now).unwrap(),
+            (date("2021-01-01T00:00:00Z"), date("2021-04-01T00:00:00Z"))
+        );
+        assert_eq!(
+            get_date_range(Some(date("2021-05-01T00:00:00Z")), None, now).unwrap(),
+            (date("2021-05-01T00:00:00Z"), now)
+        );
+        assert!(get_date_range(Some(date("2021-01-01T00:00:00Z")), None, now).is_err());
+        assert!(get_date_range(Some(now), Some(date("2021-05-01T00:00:00Z")), now).is_err());
+    }
+
+    #[test]
+    fn ok_analytics_versions() {
+        let interactions = vec![
+            interaction(Some("b"), true),
+            interaction(Some("a"), false),
+            interaction(Some("a"), true),
+            interaction(None, false),
+        ];
+
+        let analytics = compute_analytics(&[], &[], &interactions);
+
+        assert_eq!(analytics["error_rate"], 0.5);
+        assert_eq!(
+            analytics["versions"],
+            json!([
+                {"version_id": "a", "interactions": 2, "failed_interactions": 1, "error_rate": 0.5},
+                {"version_id": "b", "interactions": 1, "failed_interactions": 0, "error_rate": 0.0},
+            ])
+        );
+    }
+}
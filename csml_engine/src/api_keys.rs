@@ -0,0 +1,75 @@
+/**
+ * API keys are used by CSML servers to authenticate incoming requests.
+ * Only a SHA-256 hash of each key is stored: the key itself is returned once, on creation.
+ *
+ * Each key has a list of scopes:
+ * - run: send events to bots
+ * - read: read conversations, memories, messages and bot versions
+ * - admin: every other action, including managing API keys. Grants all the other scopes.
+ *
+ * A key can be restricted to a list of bot_ids. Keys without bot_ids can access every bot.
+ */
+use crate::data::{Database, EngineError};
+use crate::db_connectors::{api_keys, ApiKeyCreated, DbApiKey};
+
+use openssl::{rand::rand_bytes, sha::sha256};
+
+pub const SCOPES: [&str; 3] = ["run", "read", "admin"];
+
+/**
+ * The identifier of a key is the hex-encoded SHA-256 hash of the key
+ */
+pub fn get_api_key_id(key: &str) -> String {
+    hex::encode(sha256(key.as_bytes()))
+}
+
+/**
+ * An API key needs at least one scope, and all its scopes must be known
+ */
+pub fn validate_scopes(scopes: &[String]) -> Result<(), EngineError> {
+    if scopes.is_empty() {
+        return Err(EngineError::Format("an API key needs at least one scope".to_owned()));
+    }
+
+    if let Some(scope) = scopes.iter().find(|scope| !SCOPES.contains(&scope.as_str())) {
+        return Err(EngineError::Format(format!(
+            "'{}' is not a valid scope, expected one of {:?}",
+            scope, SCOPES
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn create_api_key(
+    scopes: Vec<String>,
+    bot_ids: Option<Vec<String>>,
+    db: &mut Database,
+) -> Result<ApiKeyCreated, EngineError> {
+    let mut bytes = [0; 32];
+    rand_bytes(&mut bytes)?;
+    let key = hex::encode(bytes);
+
+    let api_key = DbApiKey {
+        id: get_api_key_id(&key),
+        scopes,
+        bot_ids,
+        created_at: String::new(),
+    };
+
+    api_keys::create_api_key(&api_key, db)?;
+
+    Ok(ApiKeyCreated {
+        id: api_key.id,
+        key,
+        scopes: api_key.scopes,
+        bot_ids: api_key.bot_ids,
+    })
+}
+
+/**
+ * Find the stored API key matching a key sent by a client
+ */
+pub fn get_api_key(key: &str, db: &mut Database) -> Result<Option<DbApiKey>, EngineError> {
+    api_keys::get_api_key(&get_api_key_id(key), db)
+}
@@ -0,0 +1,169 @@
+use crate::data::{
+    CsmlRequest, Database, EngineError, DEBOUNCE_WINDOW, REQUEST_LOCK_TIMEOUT, REQUEST_LOCK_TTL,
+};
+use crate::db_connectors::state::{
+    acquire_state_lease, delete_state_key, get_state_key, release_state_lease, set_state_items,
+};
+use csml_interpreter::data::Client;
+
+use chrono::{prelude::Utc, SecondsFormat};
+use serde_json::{json, map::Map, Value};
+use std::{env, thread, time::Duration, time::Instant};
+use uuid::Uuid;
+
+const DEFAULT_LOCK_TTL: u64 = 30_000;
+const DEFAULT_LOCK_TIMEOUT: u64 = 30_000;
+const LOCK_POLL_INTERVAL: u64 = 50;
+
+fn get_env_millis(var: &str, default: u64) -> u64 {
+    match env::var(var) {
+        Ok(value) => value.parse::<u64>().unwrap_or(default),
+        Err(_) => default,
+    }
+}
+
+/**
+ * Requests of a same client must be handled one at a time, otherwise they would race
+ * on the same conversation and hold state.
+ * The lock is a lease stored in the client's state: if the engine instance holding it
+ * crashes, the lease simply expires after REQUEST_LOCK_TTL_MS (default 30s).
+ * We wait at most REQUEST_LOCK_TIMEOUT_MS (default 30s) for the lock to be released.
+ *
+ * Each lock gets its own owner, to be given back to unlock_client: request_ids may be
+ * empty or repeated by retries, so they can not tell two locks apart.
+ */
+pub fn lock_client(client: &Client, db: &mut Database) -> Result<String, EngineError> {
+    let owner = Uuid::new_v4().to_string();
+    let ttl = get_env_millis(REQUEST_LOCK_TTL, DEFAULT_LOCK_TTL);
+    let timeout = Duration::from_millis(get_env_millis(REQUEST_LOCK_TIMEOUT, DEFAULT_LOCK_TIMEOUT));
+    let now = Instant::now();
+
+    loop {
+        if acquire_state_lease(client, "lock", "request", &owner, ttl as i64, db)? {
+            return Ok(owner);
+        }
+
+        if now.elapsed() >= timeout {
+            return Err(EngineError::Manager(format!(
+                "timeout while waiting for the previous request of user {} to end",
+                client.user_id
+            )));
+        }
+
+        thread::sleep(Duration::from_millis(LOCK_POLL_INTERVAL));
+    }
+}
+
+pub fn unlock_client(client: &Client, owner: &str, db: &mut Database) -> Result<(), EngineError> {
+    release_state_lease(client, "lock", "request", owner, db)
+}
+
+/**
+ * When DEBOUNCE_WINDOW_MS is set, text events are not interpreted right away.
+ * Each one is pushed to a buffer in the client's state, then we wait for the debounce window:
+ * if no other text event was received in the meantime, all the buffered texts are merged
+ * into the current request's event, which is then interpreted as a single event.
+ *
+ * Returns false if the request was merged into a more recent one and must not be interpreted.
+ */
+pub fn debounce_event(request: &mut CsmlRequest, db: &mut Database) -> Result<bool, EngineError> {
+    let window = get_env_millis(DEBOUNCE_WINDOW, 0);
+    if window == 0 || request.payload["content_type"] != "text" {
+        return Ok(true);
+    }
+
+    let text = match request.payload["content"]["text"].as_str() {
+        Some(text) => text.to_owned(),
+        None => return Ok(true),
+    };
+
+    let client = request.client.to_owned();
+    // request_ids may be empty or repeated, each buffered event gets its own id
+    let event_id = Uuid::new_v4().to_string();
+
+    let owner = lock_client(&client, db)?;
+    let mut buffer = get_debounce_buffer(&client, db)?;
+    buffer.push(json!({"id": event_id, "request_id": request.request_id, "text": text}));
+    delete_state_key(&client, "debounce", "buffer", db)?;
+    set_state_items(&client, "debounce", vec![("buffer", &json!(buffer))], db)?;
+    unlock_client(&client, &owner, db)?;
+
+    thread::sleep(Duration::from_millis(window));
+
+    let owner = lock_client(&client, db)?;
+    let buffer = get_debounce_buffer(&client, db)?;
+    let merged_text = merge_debounce_buffer(&buffer, &event_id);
+
+    if let Some(text) = &merged_text {
+        delete_state_key(&client, "debounce", "buffer", db)?;
+        request.payload["content"]["text"] = json!(text);
+    }
+    unlock_client(&client, &owner, db)?;
+
+    Ok(merged_text.is_some())
+}
+
+/**
+ * All the buffered texts joined by spaces, if the given event is the last one of the buffer.
+ * Otherwise a more recent event will be interpreted instead: None is returned.
+ */
+fn merge_debounce_buffer(buffer: &[Value], event_id: &str) -> Option<String> {
+    match buffer.last() {
+        Some(last) if last["id"] == json!(event_id) => {
+            let texts: Vec<&str> = buffer.iter().filter_map(|event| event["text"].as_str()).collect();
+            Some(texts.join(" "))
+        }
+        _ => None,
+    }
+}
+
+fn get_debounce_buffer(client: &Client, db: &mut Database) -> Result<Vec<Value>, EngineError> {
+    match get_state_key(client, "debounce", "buffer", db)? {
+        Some(Value::Array(buffer)) => Ok(buffer),
+        _ => Ok(vec![]),
+    }
+}
+
+/**
+ * Response returned for requests that have been merged into a more recent request:
+ * the messages will be returned by the request that actually gets interpreted.
+ */
+pub fn debounced_response(request: &CsmlRequest) -> Map<String, Value> {
+    let mut map: Map<String, Value> = Map::new();
+
+    map.insert("messages".to_owned(), json!([]));
+    map.insert("conversation_end".to_owned(), Value::Bool(false));
+    map.insert("debounced".to_owned(), Value::Bool(true));
+    map.insert("request_id".to_owned(), json!(request.request_id));
+    map.insert(
+        "received_at".to_owned(),
+        json!(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)),
+    );
+
+    let mut map_client: Map<String, Value> = Map::new();
+    map_client.insert("bot_id".to_owned(), json!(request.client.bot_id));
+    map_client.insert("user_id".to_owned(), json!(request.client.user_id));
+    map_client.insert("channel_id".to_owned(), json!(request.client.channel_id));
+    map.insert("client".to_owned(), Value::Object(map_client));
+
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_merge_debounce_buffer() {
+        let buffer = vec![
+            json!({"id": "1", "request_id": "", "text": "hello"}),
+            json!({"id": "2", "request_id": "", "text": "how are"}),
+            json!({"id": "3", "request_id": "", "text": "you?"}),
+        ];
+
+        assert_eq!(merge_debounce_buffer(&buffer, "3"), Some("hello how are you?".to_owned()));
+        assert_eq!(merge_debounce_buffer(&buffer, "1"), None);
+        assert_eq!(merge_debounce_buffer(&buffer, "2"), None);
+        assert_eq!(merge_debounce_buffer(&[], "1"), None);
+    }
+}
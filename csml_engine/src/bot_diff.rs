@@ -0,0 +1,251 @@
+use crate::data::{Database, EngineError};
+use crate::db_connectors::bot;
+
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow};
+use csml_interpreter::data::diff::{diff_lines, DiffLine};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+// number of unchanged lines shown around each change
+const DIFF_CONTEXT: usize = 3;
+
+/**
+ * Format a diff as a unified diff, with DIFF_CONTEXT unchanged lines around each change
+ */
+fn format_unified_diff(lines: &[DiffLine]) -> String {
+    // number of old and new lines before each line
+    let mut positions = Vec::with_capacity(lines.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in lines.iter() {
+        positions.push((old_line, new_line));
+        match line {
+            DiffLine::Same(_) => {
+                old_line += 1;
+                new_line += 1;
+            }
+            DiffLine::Removed(_) => old_line += 1,
+            DiffLine::Added(_) => new_line += 1,
+        }
+    }
+
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        if let DiffLine::Same(_) = line {
+            continue;
+        }
+
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + DIFF_CONTEXT + 1).min(lines.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut diff = String::new();
+    for (start, end) in hunks {
+        let hunk = &lines[start..end];
+        let old_count = hunk.iter().filter(|line| !matches!(line, DiffLine::Added(_))).count();
+        let new_count = hunk.iter().filter(|line| !matches!(line, DiffLine::Removed(_))).count();
+        let (old_start, new_start) = positions[start];
+
+        // an empty range starts at the line before it, as in `diff -u`
+        diff.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            if old_count > 0 { old_start + 1 } else { old_start },
+            old_count,
+            if new_count > 0 { new_start + 1 } else { new_start },
+            new_count,
+        ));
+
+        for line in hunk {
+            let (prefix, text) = match line {
+                DiffLine::Same(text) => (' ', text),
+                DiffLine::Removed(text) => ('-', text),
+                DiffLine::Added(text) => ('+', text),
+            };
+            diff.push(prefix);
+            diff.push_str(text);
+            diff.push('\n');
+        }
+    }
+
+    diff
+}
+
+fn get_added_and_removed(from: &[String], to: &[String]) -> Value {
+    let mut added: Vec<&String> = to.iter().filter(|item| !from.contains(item)).collect();
+    let mut removed: Vec<&String> = from.iter().filter(|item| !to.contains(item)).collect();
+    added.sort();
+    removed.sort();
+
+    json!({"added": added, "removed": removed})
+}
+
+fn diff_flow(name: &str, from: Option<&CsmlFlow>, to: Option<&CsmlFlow>, steps: (&[String], &[String])) -> Value {
+    let status = match (from, to) {
+        (None, _) => "added",
+        (_, None) => "removed",
+        _ => "modified",
+    };
+    let from_lines: Vec<&str> = from.map(|flow| flow.content.lines().collect()).unwrap_or_default();
+    let to_lines: Vec<&str> = to.map(|flow| flow.content.lines().collect()).unwrap_or_default();
+    let no_commands = vec![];
+
+    json!({
+        "name": name,
+        "status": status,
+        "steps": get_added_and_removed(steps.0, steps.1),
+        "commands": get_added_and_removed(
+            from.map(|flow| &flow.commands).unwrap_or(&no_commands),
+            to.map(|flow| &flow.commands).unwrap_or(&no_commands),
+        ),
+        "diff": format_unified_diff(&diff_lines(&from_lines, &to_lines)),
+    })
+}
+
+/**
+ * Compare the flows of two bots, matched by name. Each flow that was added, removed
+ * or modified comes with the steps and commands it gained or lost, and a unified diff
+ * of its content.
+ */
+pub fn diff_bots(from: &CsmlBot, to: &CsmlBot) -> Value {
+    let mut flows: BTreeMap<&str, (Option<&CsmlFlow>, Option<&CsmlFlow>)> = BTreeMap::new();
+    for flow in from.flows.iter() {
+        flows.entry(&flow.name).or_default().0 = Some(flow);
+    }
+    for flow in to.flows.iter() {
+        flows.entry(&flow.name).or_default().1 = Some(flow);
+    }
+
+    let from_steps = csml_interpreter::get_steps_from_flow(from.to_owned());
+    let to_steps = csml_interpreter::get_steps_from_flow(to.to_owned());
+    let no_steps = vec![];
+
+    let mut changed_flows = vec![];
+    let mut unchanged_flows = vec![];
+    for (name, (from_flow, to_flow)) in flows {
+        if let (Some(from_flow), Some(to_flow)) = (from_flow, to_flow) {
+            if from_flow.content == to_flow.content && from_flow.commands == to_flow.commands {
+                unchanged_flows.push(name);
+                continue;
+            }
+        }
+
+        let steps = (
+            from_steps.get(name).unwrap_or(&no_steps).as_slice(),
+            to_steps.get(name).unwrap_or(&no_steps).as_slice(),
+        );
+        changed_flows.push(diff_flow(name, from_flow, to_flow, steps));
+    }
+
+    let default_flow = match from.default_flow == to.default_flow {
+        true => Value::Null,
+        false => json!({"from": from.default_flow, "to": to.default_flow}),
+    };
+
+    json!({
+        "default_flow": default_flow,
+        "flows": changed_flows,
+        "unchanged_flows": unchanged_flows,
+    })
+}
+
+/**
+ * Compare the flows of two versions of a bot.
+ * Return None if one of the versions does not exist.
+ */
+pub fn diff_bot_versions(
+    bot_id: &str,
+    from_version_id: &str,
+    to_version_id: &str,
+    db: &mut Database,
+) -> Result<Option<Value>, EngineError> {
+    let from = match bot::get_by_version_id(from_version_id, bot_id, db)? {
+        Some(version) if version.bot.id == bot_id => version,
+        _ => return Ok(None),
+    };
+    let to = match bot::get_by_version_id(to_version_id, bot_id, db)? {
+        Some(version) if version.bot.id == bot_id => version,
+        _ => return Ok(None),
+    };
+
+    let mut diff = diff_bots(&from.bot, &to.bot);
+    diff["from_version_id"] = json!(from.version_id);
+    diff["to_version_id"] = json!(to.version_id);
+
+    Ok(Some(diff))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(name: &str, content: &str) -> CsmlFlow {
+        CsmlFlow {
+            id: name.to_owned(),
+            name: name.to_owned(),
+            content: content.to_owned(),
+            commands: vec![],
+        }
+    }
+
+    fn bot(flows: Vec<CsmlFlow>) -> CsmlBot {
+        CsmlBot {
+            id: "botid".to_owned(),
+            name: "bot".to_owned(),
+            fn_endpoint: None,
+            flows,
+            native_components: None,
+            custom_components: None,
+            default_flow: "Default".to_owned(),
+            bot_ast: None,
+            env: None,
+        }
+    }
+
+    #[test]
+    fn ok_unified_diff() {
+        let from: Vec<String> = (1..=10).map(|i| i.to_string()).collect();
+        let mut to = from.to_owned();
+        to[4] = "five".to_owned();
+
+        let from: Vec<&str> = from.iter().map(|line| line.as_str()).collect();
+        let to: Vec<&str> = to.iter().map(|line| line.as_str()).collect();
+
+        assert_eq!(
+            format_unified_diff(&diff_lines(&from, &to)),
+            "@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn ok_diff_bots() {
+        let from = bot(vec![
+            flow("Default", "start:\n  say \"hello\"\n  goto end"),
+            flow("Removed", "start:\n  goto end"),
+            flow("Same", "start:\n  goto end"),
+        ]);
+        let to = bot(vec![
+            flow("Default", "start:\n  say \"hi\"\n  goto next\n\nnext:\n  goto end"),
+            flow("Added", "start:\n  goto end"),
+            flow("Same", "start:\n  goto end"),
+        ]);
+
+        let diff = diff_bots(&from, &to);
+
+        assert_eq!(diff["default_flow"], Value::Null);
+        assert_eq!(diff["unchanged_flows"], json!(["Same"]));
+
+        let flows = diff["flows"].as_array().unwrap();
+        assert_eq!(flows.len(), 3);
+        assert_eq!(flows[0]["name"], "Added");
+        assert_eq!(flows[0]["status"], "added");
+        assert_eq!(flows[1]["name"], "Default");
+        assert_eq!(flows[1]["status"], "modified");
+        assert_eq!(flows[1]["steps"], json!({"added": ["next"], "removed": []}));
+        assert_eq!(flows[2]["name"], "Removed");
+        assert_eq!(flows[2]["status"], "removed");
+        assert_eq!(flows[2]["diff"], "@@ -1,2 +0,0 @@\n-start:\n-  goto end\n");
+    }
+}
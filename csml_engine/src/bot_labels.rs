@@ -0,0 +1,213 @@
+/**
+ * Labels (e.g. staging, production) point to a version of a bot, so that a bot can be run
+ * by label instead of by version_id. Promoting a label makes it point to another version;
+ * rolling it back makes it point to a version it pointed to before.
+ */
+use crate::data::{BotLabel, Database, EngineError};
+use crate::db_connectors::{bot, bot_labels};
+
+use chrono::{SecondsFormat, Utc};
+
+// number of previous versions kept for each label
+const MAX_LABEL_HISTORY: usize = 20;
+
+pub fn validate_label(label: &str) -> Result<(), EngineError> {
+    let is_valid_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+
+    if label.is_empty() || label.len() > 64 || !label.chars().all(is_valid_char) {
+        return Err(EngineError::Format(format!(
+            "invalid label '{}': labels are made of 1 to 64 letters, digits, '-', '_' or '.'",
+            label
+        )));
+    }
+
+    Ok(())
+}
+
+fn check_bot_version(bot_id: &str, version_id: &str, db: &mut Database) -> Result<(), EngineError> {
+    match bot::get_by_version_id(version_id, bot_id, db)? {
+        Some(version) if version.bot.id == bot_id => Ok(()),
+        _ => Err(EngineError::Format(format!(
+            "bot version '{}' not found",
+            version_id
+        ))),
+    }
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn promote(previous: Option<BotLabel>, label: &str, version_id: &str) -> BotLabel {
+    let mut history = match previous {
+        Some(previous) if previous.version_id != version_id => {
+            let mut history = previous.history;
+            history.push(previous.version_id);
+            history
+        }
+        Some(previous) => previous.history,
+        None => vec![],
+    };
+
+    if history.len() > MAX_LABEL_HISTORY {
+        history.drain(..history.len() - MAX_LABEL_HISTORY);
+    }
+
+    BotLabel {
+        label: label.to_owned(),
+        version_id: version_id.to_owned(),
+        history,
+        updated_at: now(),
+    }
+}
+
+/**
+ * Without a version_id, go back to the previous version of the label.
+ * With a version_id found in the history, the versions promoted after it are dropped
+ * from the history; otherwise the rollback is the same as a promotion.
+ */
+fn rollback(current: BotLabel, version_id: Option<&str>) -> Result<BotLabel, EngineError> {
+    let mut history = current.history.to_owned();
+
+    let version_id = match version_id {
+        Some(version_id) => match history.iter().rposition(|previous| previous == version_id) {
+            Some(index) => {
+                history.truncate(index);
+                version_id.to_owned()
+            }
+            None => {
+                let label = current.label.to_owned();
+                return Ok(promote(Some(current), &label, version_id));
+            }
+        },
+        None => match history.pop() {
+            Some(version_id) => version_id,
+            None => {
+                return Err(EngineError::Format(format!(
+                    "label '{}' has no previous version to roll back to",
+                    current.label
+                )))
+            }
+        },
+    };
+
+    Ok(BotLabel {
+        label: current.label,
+        version_id,
+        history,
+        updated_at: now(),
+    })
+}
+
+/**
+ * Make a label point to a given version of a bot, creating the label if needed
+ */
+pub fn promote_bot_version(
+    bot_id: &str,
+    label: &str,
+    version_id: &str,
+    db: &mut Database,
+) -> Result<BotLabel, EngineError> {
+    validate_label(label)?;
+    check_bot_version(bot_id, version_id, db)?;
+
+    let previous = bot_labels::get_bot_label(bot_id, label, db)?;
+    let bot_label = promote(previous, label, version_id);
+    bot_labels::set_bot_label(bot_id, &bot_label, db)?;
+
+    Ok(bot_label)
+}
+
+/**
+ * Make a label point to the version it pointed to before, or to a given version.
+ * Return None if the label does not exist.
+ */
+pub fn rollback_bot_label(
+    bot_id: &str,
+    label: &str,
+    version_id: Option<&str>,
+    db: &mut Database,
+) -> Result<Option<BotLabel>, EngineError> {
+    let current = match bot_labels::get_bot_label(bot_id, label, db)? {
+        Some(current) => current,
+        None => return Ok(None),
+    };
+
+    if let Some(version_id) = version_id {
+        check_bot_version(bot_id, version_id, db)?;
+    }
+
+    let bot_label = rollback(current, version_id)?;
+    bot_labels::set_bot_label(bot_id, &bot_label, db)?;
+
+    Ok(Some(bot_label))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(version_id: &str, history: &[&str]) -> BotLabel {
+        BotLabel {
+            label: "production".to_owned(),
+            version_id: version_id.to_owned(),
+            history: history.iter().map(|version| version.to_string()).collect(),
+            updated_at: "2021-01-01T00:00:00.000Z".to_owned(),
+        }
+    }
+
+    #[test]
+    fn ok_validate_label() {
+        assert!(validate_label("production").is_ok());
+        assert!(validate_label("release-1.2_rc").is_ok());
+        assert!(validate_label("").is_err());
+        assert!(validate_label("pro duction").is_err());
+        assert!(validate_label(&"a".repeat(65)).is_err());
+    }
+
+    #[test]
+    fn ok_promote() {
+        let promoted = promote(None, "production", "v1");
+        assert_eq!(promoted.version_id, "v1");
+        assert!(promoted.history.is_empty());
+
+        let promoted = promote(Some(label("v2", &["v1"])), "production", "v3");
+        assert_eq!(promoted.version_id, "v3");
+        assert_eq!(promoted.history, vec!["v1", "v2"]);
+
+        // promoting the current version again does not change the history
+        let promoted = promote(Some(label("v2", &["v1"])), "production", "v2");
+        assert_eq!(promoted.history, vec!["v1"]);
+    }
+
+    #[test]
+    fn ok_promote_history_limit() {
+        let history: Vec<String> = (0..MAX_LABEL_HISTORY).map(|i| format!("v{}", i)).collect();
+        let history: Vec<&str> = history.iter().map(|version| version.as_str()).collect();
+
+        let promoted = promote(Some(label("current", &history)), "production", "next");
+        assert_eq!(promoted.history.len(), MAX_LABEL_HISTORY);
+        assert_eq!(promoted.history.first().unwrap(), "v1");
+        assert_eq!(promoted.history.last().unwrap(), "current");
+    }
+
+    #[test]
+    fn ok_rollback() {
+        let rolled_back = rollback(label("v3", &["v1", "v2"]), None).unwrap();
+        assert_eq!(rolled_back.version_id, "v2");
+        assert_eq!(rolled_back.history, vec!["v1"]);
+
+        let rolled_back = rollback(label("v3", &["v1", "v2"]), Some("v1")).unwrap();
+        assert_eq!(rolled_back.version_id, "v1");
+        assert!(rolled_back.history.is_empty());
+
+        let rolled_back = rollback(label("v3", &["v1", "v2"]), Some("v0")).unwrap();
+        assert_eq!(rolled_back.version_id, "v0");
+        assert_eq!(rolled_back.history, vec!["v1", "v2", "v3"]);
+    }
+
+    #[test]
+    fn ko_rollback_without_history() {
+        assert!(rollback(label("v1", &[]), None).is_err());
+    }
+}
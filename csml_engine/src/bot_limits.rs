@@ -0,0 +1,43 @@
+/**
+ * Rate limits and daily quotas of a bot. The limits themselves are enforced by the server
+ * receiving the requests: the engine only stores them, along with the number of interactions
+ * of each bot per day (UTC) so that quotas are shared by every server instance.
+ */
+use crate::data::{BotLimits, Database, EngineError, RateLimit};
+use crate::db_connectors::bot_limits;
+
+use chrono::Utc;
+
+fn validate_rate_limit(name: &str, limit: &Option<RateLimit>) -> Result<(), EngineError> {
+    match limit {
+        Some(RateLimit { burst, per_second }) if *burst < 1.0 || *per_second <= 0.0 => {
+            Err(EngineError::Format(format!(
+                "invalid '{}' rate limit: burst must be at least 1 and per_second greater than 0",
+                name
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn validate_bot_limits(limits: &BotLimits) -> Result<(), EngineError> {
+    validate_rate_limit("bot", &limits.bot)?;
+    validate_rate_limit("channel", &limits.channel)?;
+    validate_rate_limit("user", &limits.user)?;
+
+    Ok(())
+}
+
+/**
+ * Count a new interaction for today, and return the number of interactions of the day,
+ * or None without counting it if there already are max interactions today
+ */
+pub fn increment_daily_interactions(
+    bot_id: &str,
+    max: u64,
+    db: &mut Database,
+) -> Result<Option<u64>, EngineError> {
+    let day = Utc::now().format("%Y-%m-%d").to_string();
+
+    bot_limits::increment_bot_usage(bot_id, &day, max, db)
+}
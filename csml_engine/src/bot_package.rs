@@ -0,0 +1,490 @@
+/**
+ * A bot package is a portable version of a bot, made to be kept in git and deployed
+ * from one environment to another. It is a .tar.gz archive with:
+ *
+ * - manifest.json: the bot's information and the list of the other files
+ * - flows/<name>.csml: the content of each flow
+ * - components/<name>.json: each custom component
+ * - env.schema.json: the JSON schema of the bot's env. The values of the env are never
+ *   exported, as they usually hold secrets: they are given again when the package is imported.
+ *
+ * The archive of a given bot version is always the same, byte for byte.
+ */
+use crate::data::EngineError;
+use crate::db_connectors::BotVersion;
+use csml_interpreter::data::{csml_bot::CsmlBot, csml_flow::CsmlFlow};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Component, Path};
+
+pub const PACKAGE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const ENV_SCHEMA_FILE: &str = "env.schema.json";
+// maximum size of the files of a package once uncompressed
+const MAX_PACKAGE_SIZE: u64 = 8_388_608; // 8MB
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PackageFile {
+    pub name: String,
+    pub file: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PackageFlow {
+    pub id: String,
+    pub name: String,
+    pub file: String,
+    pub commands: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PackageManifest {
+    pub format_version: u32,
+    pub id: String,
+    pub name: String,
+    pub default_flow: String,
+    pub fn_endpoint: Option<String>,
+    pub version_id: Option<String>,
+    pub engine_version: Option<String>,
+    pub flows: Vec<PackageFlow>,
+    pub components: Vec<PackageFile>,
+    pub env_schema: Option<String>,
+}
+
+fn package_error(message: &str) -> EngineError {
+    EngineError::Format(format!("invalid bot package: {}", message))
+}
+
+/**
+ * Names are used as file names: anything else than letters, digits, '-', '_' and '.'
+ * is replaced, and a suffix is added to the names that end up with the same file name
+ */
+fn get_file_path(dir: &str, name: &str, extension: &str, used: &mut HashSet<String>) -> String {
+    let mut stem: String = name
+        .chars()
+        .map(|c| match c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' {
+            true => c,
+            false => '_',
+        })
+        .collect();
+    if stem.is_empty() || stem.starts_with('.') {
+        stem.insert(0, '_');
+    }
+
+    let mut path = format!("{}/{}.{}", dir, stem, extension);
+    let mut index = 1;
+    while used.contains(&path.to_lowercase()) {
+        index += 1;
+        path = format!("{}/{}-{}.{}", dir, stem, index, extension);
+    }
+    used.insert(path.to_lowercase());
+
+    path
+}
+
+fn get_json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/**
+ * The schema of an env lists its keys and the type of their values, all keys being required
+ */
+pub fn get_env_schema(env: &Map<String, Value>) -> Value {
+    let properties: Map<String, Value> = env
+        .iter()
+        .map(|(key, value)| (key.to_owned(), json!({"type": get_json_type(value)})))
+        .collect();
+    let required: Vec<&String> = env.keys().collect();
+
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/**
+ * Check an env against the schema of a package: every required key must be set
+ * and every key of the schema must have a value of the given type
+ * (an integer is also a valid number)
+ */
+pub fn check_env(schema: &Value, env: &Value) -> Result<(), EngineError> {
+    let env = match env {
+        Value::Object(env) => env,
+        _ => return Err(EngineError::Format("env must be an object".to_owned())),
+    };
+    let mut errors = vec![];
+
+    if let Some(required) = schema["required"].as_array() {
+        for key in required.iter().filter_map(|key| key.as_str()) {
+            if !env.contains_key(key) {
+                errors.push(format!("'{}' is missing", key));
+            }
+        }
+    }
+
+    if let Some(properties) = schema["properties"].as_object() {
+        for (key, property) in properties.iter() {
+            let expected = match property["type"].as_str() {
+                Some(expected) => expected,
+                None => continue,
+            };
+
+            if let Some(value) = env.get(key) {
+                let found = get_json_type(value);
+                if found != expected && !(expected == "number" && found == "integer") {
+                    errors.push(format!("'{}' must be of type {}, found {}", key, expected, found));
+                }
+            }
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(EngineError::Format(format!("invalid env: {}", errors.join(", ")))),
+    }
+}
+
+fn append_file(
+    archive: &mut tar::Builder<GzEncoder<Vec<u8>>>,
+    path: &str,
+    content: &[u8],
+) -> Result<(), EngineError> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(content.len() as u64);
+    header.set_mode(0o644);
+    // no date, so that a given version always gives the same archive
+    header.set_mtime(0);
+    header.set_cksum();
+
+    archive.append_data(&mut header, path, content)?;
+    Ok(())
+}
+
+/**
+ * Package a version of a bot
+ */
+pub fn export_bot_package(version: &BotVersion) -> Result<Vec<u8>, EngineError> {
+    let bot = &version.bot;
+    let mut used = HashSet::new();
+    let mut files: Vec<(String, Vec<u8>)> = vec![];
+
+    let mut flows = vec![];
+    for flow in bot.flows.iter() {
+        let file = get_file_path("flows", &flow.name, "csml", &mut used);
+        files.push((file.to_owned(), flow.content.as_bytes().to_vec()));
+
+        flows.push(PackageFlow {
+            id: flow.id.to_owned(),
+            name: flow.name.to_owned(),
+            file,
+            commands: flow.commands.to_owned(),
+        });
+    }
+
+    let mut components = vec![];
+    if let Some(Value::Object(custom_components)) = &bot.custom_components {
+        for (name, component) in custom_components.iter() {
+            let file = get_file_path("components", name, "json", &mut used);
+            files.push((file.to_owned(), serde_json::to_vec_pretty(component)?));
+
+            components.push(PackageFile {
+                name: name.to_owned(),
+                file,
+            });
+        }
+    }
+
+    let env_schema = match &bot.env {
+        Some(Value::Object(env)) => {
+            files.push((ENV_SCHEMA_FILE.to_owned(), serde_json::to_vec_pretty(&get_env_schema(env))?));
+            Some(ENV_SCHEMA_FILE.to_owned())
+        }
+        _ => None,
+    };
+
+    let manifest = PackageManifest {
+        format_version: PACKAGE_FORMAT_VERSION,
+        id: bot.id.to_owned(),
+        name: bot.name.to_owned(),
+        default_flow: bot.default_flow.to_owned(),
+        fn_endpoint: bot.fn_endpoint.to_owned(),
+        version_id: Some(version.version_id.to_owned()),
+        engine_version: Some(version.engine_version.to_owned()),
+        flows,
+        components,
+        env_schema,
+    };
+
+    let mut archive = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+    append_file(&mut archive, MANIFEST_FILE, &serde_json::to_vec_pretty(&manifest)?)?;
+    for (path, content) in files.iter() {
+        append_file(&mut archive, path, content)?;
+    }
+
+    Ok(archive.into_inner()?.finish()?)
+}
+
+/**
+ * Only the regular files of the archive are read, and their paths must stay inside the package
+ */
+fn read_package_files(package: &[u8]) -> Result<BTreeMap<String, Vec<u8>>, EngineError> {
+    let mut archive = tar::Archive::new(GzDecoder::new(package));
+    let mut files = BTreeMap::new();
+    let mut remaining = MAX_PACKAGE_SIZE;
+
+    let entries = archive.entries().map_err(|err| package_error(&err.to_string()))?;
+    for entry in entries {
+        let entry = entry.map_err(|err| package_error(&err.to_string()))?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().map_err(|err| package_error(&err.to_string()))?;
+        let mut parts = vec![];
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+                Component::CurDir => continue,
+                _ => return Err(package_error(&format!("invalid path '{}'", path.display()))),
+            }
+        }
+        let path = parts.join("/");
+
+        let mut content = vec![];
+        entry
+            .take(remaining + 1)
+            .read_to_end(&mut content)
+            .map_err(|err| package_error(&err.to_string()))?;
+        if content.len() as u64 > remaining {
+            return Err(package_error(&format!(
+                "files are larger than {} bytes",
+                MAX_PACKAGE_SIZE
+            )));
+        }
+        remaining -= content.len() as u64;
+
+        files.insert(path, content);
+    }
+
+    Ok(files)
+}
+
+fn get_package_file<'a>(files: &'a BTreeMap<String, Vec<u8>>, path: &str) -> Result<&'a [u8], EngineError> {
+    // paths of the manifest are compared with the normalized paths of the archive
+    let mut normalized = vec![];
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part.to_string_lossy().to_string()),
+            Component::CurDir => continue,
+            _ => return Err(package_error(&format!("invalid path '{}'", path))),
+        }
+    }
+
+    match files.get(&normalized.join("/")) {
+        Some(content) => Ok(content),
+        None => Err(package_error(&format!("'{}' not found", path))),
+    }
+}
+
+fn parse_json_file(files: &BTreeMap<String, Vec<u8>>, path: &str) -> Result<Value, EngineError> {
+    serde_json::from_slice(get_package_file(files, path)?)
+        .map_err(|err| package_error(&format!("'{}' is not valid JSON: {}", path, err)))
+}
+
+/**
+ * Read a package as a bot named `bot_id`. If the package has an env schema,
+ * `env` must match it. The flows are not validated here.
+ */
+pub fn import_bot_package(
+    package: &[u8],
+    bot_id: &str,
+    env: Option<Value>,
+) -> Result<CsmlBot, EngineError> {
+    let files = read_package_files(package)?;
+
+    let manifest: PackageManifest = serde_json::from_value(parse_json_file(&files, MANIFEST_FILE)?)
+        .map_err(|err| package_error(&format!("invalid {}: {}", MANIFEST_FILE, err)))?;
+    if manifest.format_version > PACKAGE_FORMAT_VERSION {
+        return Err(package_error(&format!(
+            "format version {} is not supported (latest is {})",
+            manifest.format_version, PACKAGE_FORMAT_VERSION
+        )));
+    }
+
+    let mut flows = vec![];
+    for flow in manifest.flows.iter() {
+        let content = std::str::from_utf8(get_package_file(&files, &flow.file)?)
+            .map_err(|_| package_error(&format!("'{}' is not valid UTF-8", flow.file)))?;
+
+        flows.push(CsmlFlow {
+            id: flow.id.to_owned(),
+            name: flow.name.to_owned(),
+            content: content.to_owned(),
+            commands: flow.commands.to_owned(),
+        });
+    }
+
+    let custom_components = match manifest.components.is_empty() {
+        true => None,
+        false => {
+            let mut components = Map::new();
+            for component in manifest.components.iter() {
+                components.insert(component.name.to_owned(), parse_json_file(&files, &component.file)?);
+            }
+            Some(Value::Object(components))
+        }
+    };
+
+    if let Some(schema) = &manifest.env_schema {
+        let schema = parse_json_file(&files, schema)?;
+        check_env(&schema, env.as_ref().unwrap_or(&json!({})))?;
+    }
+
+    Ok(CsmlBot {
+        id: bot_id.to_owned(),
+        name: manifest.name,
+        fn_endpoint: manifest.fn_endpoint,
+        flows,
+        native_components: None,
+        custom_components,
+        default_flow: manifest.default_flow,
+        bot_ast: None,
+        env,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version() -> BotVersion {
+        BotVersion {
+            bot: CsmlBot {
+                id: "botid".to_owned(),
+                name: "bot".to_owned(),
+                fn_endpoint: None,
+                flows: vec![
+                    CsmlFlow {
+                        id: "1".to_owned(),
+                        name: "Default".to_owned(),
+                        content: "start:\n  say \"hello\"\n  goto end".to_owned(),
+                        commands: vec!["/start".to_owned()],
+                    },
+                    CsmlFlow {
+                        id: "2".to_owned(),
+                        name: "default".to_owned(),
+                        content: "start:\n  goto end".to_owned(),
+                        commands: vec![],
+                    },
+                ],
+                native_components: None,
+                custom_components: Some(json!({"Greeting": {"params": [{"name": {"required": true}}]}})),
+                default_flow: "Default".to_owned(),
+                bot_ast: None,
+                env: Some(json!({"api_key": "secret", "retries": 3})),
+            },
+            version_id: "versionid".to_owned(),
+            engine_version: "1.0.0".to_owned(),
+        }
+    }
+
+    fn make_package(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut archive = tar::Builder::new(GzEncoder::new(vec![], Compression::default()));
+        for (path, content) in files.iter() {
+            let mut header = tar::Header::new_gnu();
+            // set_path would refuse the invalid paths we test
+            header.as_gnu_mut().unwrap().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            archive.append(&header, content.as_bytes()).unwrap();
+        }
+
+        archive.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn ok_export_import() {
+        let version = version();
+        let package = export_bot_package(&version).unwrap();
+
+        // the same version always gives the same package
+        assert_eq!(package, export_bot_package(&version).unwrap());
+
+        let files = read_package_files(&package).unwrap();
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec![
+                "components/Greeting.json",
+                "env.schema.json",
+                "flows/Default.csml",
+                "flows/default-2.csml",
+                "manifest.json",
+            ]
+        );
+        // env values are not exported
+        assert!(!String::from_utf8_lossy(&files["env.schema.json"]).contains("secret"));
+
+        let env = json!({"api_key": "other", "retries": 5});
+        let bot = import_bot_package(&package, "otherid", Some(env.to_owned())).unwrap();
+
+        assert_eq!(bot.id, "otherid");
+        assert_eq!(bot.name, version.bot.name);
+        assert_eq!(bot.default_flow, version.bot.default_flow);
+        assert_eq!(json!(bot.flows), json!(version.bot.flows));
+        assert_eq!(bot.custom_components, version.bot.custom_components);
+        assert_eq!(bot.env, Some(env));
+    }
+
+    #[test]
+    fn ko_import_invalid_env() {
+        let package = export_bot_package(&version()).unwrap();
+
+        assert!(import_bot_package(&package, "botid", None).is_err());
+        assert!(import_bot_package(&package, "botid", Some(json!({"api_key": "key"}))).is_err());
+        assert!(
+            import_bot_package(&package, "botid", Some(json!({"api_key": 1, "retries": 5}))).is_err()
+        );
+    }
+
+    #[test]
+    fn ko_import_invalid_package() {
+        assert!(import_bot_package(b"not a package", "botid", None).is_err());
+
+        let manifest = json!({
+            "format_version": 1,
+            "id": "botid",
+            "name": "bot",
+            "default_flow": "Default",
+            "fn_endpoint": null,
+            "version_id": null,
+            "engine_version": null,
+            "flows": [{"id": "1", "name": "Default", "file": "../Default.csml", "commands": []}],
+            "components": [],
+            "env_schema": null,
+        })
+        .to_string();
+
+        let package = make_package(&[("manifest.json", &manifest)]);
+        assert!(import_bot_package(&package, "botid", None).is_err());
+
+        let package = make_package(&[
+            ("manifest.json", &manifest),
+            ("../Default.csml", "start:\n  goto end"),
+        ]);
+        assert!(read_package_files(&package).is_err());
+    }
+}
@@ -3,15 +3,25 @@ use csml_interpreter::data::{CsmlBot, CsmlFlow, Message};
 use curl::easy::Easy;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::sync::mpsc;
 
 pub const DEBUG: &str = "DEBUG";
 pub const DISABLE_SSL_VERIFY: &str = "DISABLE_SSL_VERIFY";
+pub const DEBOUNCE_WINDOW: &str = "DEBOUNCE_WINDOW_MS";
+pub const REQUEST_LOCK_TTL: &str = "REQUEST_LOCK_TTL_MS";
+pub const REQUEST_LOCK_TIMEOUT: &str = "REQUEST_LOCK_TIMEOUT_MS";
+pub const IDEMPOTENCY_WINDOW: &str = "IDEMPOTENCY_WINDOW_MS";
+pub const HANDOFF_SINK: &str = "HANDOFF_SINK_URL";
+pub const HTTP_POLICY: &str = "ENGINE_HTTP_POLICY";
+pub const HTTP_POLICY_DB: &str = "ENGINE_HTTP_POLICY_DB";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RunRequest {
     pub bot: Option<CsmlBot>,
     pub bot_id: Option<String>,
     pub version_id: Option<String>,
+    pub label: Option<String>,
+    pub split: Option<Vec<VersionWeight>>,
     pub fn_endpoint: Option<String>,
     pub event: CsmlRequest,
 }
@@ -33,6 +43,26 @@ impl RunRequest {
                 bot_id,
                 fn_endpoint,
             }),
+            RunRequest {
+                split: Some(split),
+                bot_id: Some(bot_id),
+                fn_endpoint,
+                ..
+            } => Ok(BotOpt::Split {
+                split,
+                bot_id,
+                fn_endpoint,
+            }),
+            RunRequest {
+                label: Some(label),
+                bot_id: Some(bot_id),
+                fn_endpoint,
+                ..
+            } => Ok(BotOpt::Label {
+                label,
+                bot_id,
+                fn_endpoint,
+            }),
             RunRequest {
                 bot_id: Some(bot_id),
                 fn_endpoint,
@@ -46,6 +76,49 @@ impl RunRequest {
     }
 }
 
+/**
+ * Token bucket: at most `burst` requests at once, refilled at `per_second` requests per second
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RateLimit {
+    pub burst: f64,
+    pub per_second: f64,
+}
+
+/**
+ * Limits of the requests made to a bot: for the whole bot, for each of its channels
+ * and for each of its users, plus a maximum number of interactions per day (UTC)
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BotLimits {
+    pub bot: Option<RateLimit>,
+    pub channel: Option<RateLimit>,
+    pub user: Option<RateLimit>,
+    pub daily_interactions: Option<u64>,
+}
+
+/**
+ * A label (e.g. staging, production) pointing to a version of a bot.
+ * `history` holds the versions the label pointed to before, the most recent one last.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BotLabel {
+    pub label: String,
+    pub version_id: String,
+    pub history: Vec<String>,
+    pub updated_at: String,
+}
+
+/**
+ * A version of a bot and its share of the traffic in a split.
+ * Weights are relative to the sum of the weights of the split.
+ */
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct VersionWeight {
+    pub version_id: String,
+    pub weight: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum BotOpt {
     #[serde(rename = "bot")]
@@ -61,21 +134,32 @@ pub enum BotOpt {
         bot_id: String,
         fn_endpoint: Option<String>,
     },
+    #[serde(rename = "label")]
+    Label {
+        label: String,
+        bot_id: String,
+        fn_endpoint: Option<String>,
+    },
+    #[serde(rename = "split")]
+    Split {
+        split: Vec<VersionWeight>,
+        bot_id: String,
+        fn_endpoint: Option<String>,
+    },
 }
 
 impl BotOpt {
-    pub fn search_bot(&self, db: &mut Database) -> CsmlBot {
+    pub fn search_bot(&self, db: &mut Database) -> Result<CsmlBot, EngineError> {
         match self {
-            BotOpt::CsmlBot(csml_bot) => csml_bot.to_owned(),
+            BotOpt::CsmlBot(csml_bot) => Ok(csml_bot.to_owned()),
             BotOpt::BotId {
                 bot_id,
                 fn_endpoint,
             } => {
-                let mut bot_version = db_connectors::bot::get_last_bot_version(&bot_id, db)
-                    .unwrap()
-                    .unwrap();
+                let mut bot_version = db_connectors::bot::get_last_bot_version(&bot_id, db)?
+                    .ok_or_else(|| EngineError::Format(format!("bot '{}' has no version", bot_id)))?;
                 bot_version.bot.fn_endpoint = fn_endpoint.to_owned();
-                bot_version.bot
+                Ok(bot_version.bot)
             }
             BotOpt::Id {
                 version_id,
@@ -83,12 +167,29 @@ impl BotOpt {
                 fn_endpoint,
             } => {
                 let mut bot_version =
-                    db_connectors::bot::get_by_version_id(&version_id, &bot_id, db)
-                        .unwrap()
-                        .unwrap();
+                    db_connectors::bot::get_by_version_id(&version_id, &bot_id, db)?
+                        .ok_or_else(|| EngineError::Format(format!("bot version '{}' not found", version_id)))?;
+                bot_version.bot.fn_endpoint = fn_endpoint.to_owned();
+                Ok(bot_version.bot)
+            }
+            BotOpt::Label {
+                label,
+                bot_id,
+                fn_endpoint,
+            } => {
+                let bot_label = db_connectors::bot_labels::get_bot_label(&bot_id, &label, db)?
+                    .ok_or_else(|| EngineError::Format(format!("bot label '{}' not found", label)))?;
+                let mut bot_version =
+                    db_connectors::bot::get_by_version_id(&bot_label.version_id, &bot_id, db)?
+                        .ok_or_else(|| EngineError::Format(format!("bot version '{}' not found", bot_label.version_id)))?;
                 bot_version.bot.fn_endpoint = fn_endpoint.to_owned();
-                bot_version.bot
+                Ok(bot_version.bot)
             }
+            // the version of a split depends on the client, see traffic_split::resolve_split
+            BotOpt::Split { bot_id, .. } => Err(EngineError::Format(format!(
+                "the version of bot '{}' to run must be picked from its split first",
+                bot_id
+            ))),
         }
     }
 }
@@ -337,6 +438,7 @@ pub struct ConversationInfo {
     pub context: Context,
     pub metadata: Value,
     pub messages: Vec<Message>,
+    pub stream: Option<mpsc::Sender<Value>>,
     pub db: Database,
 }
 
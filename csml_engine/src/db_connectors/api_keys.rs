@@ -0,0 +1,61 @@
+#[cfg(feature = "dynamo")]
+use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
+#[cfg(feature = "mongo")]
+use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::{DbApiKey, DbTimer};
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Database, EngineError};
+
+pub fn create_api_key(api_key: &DbApiKey, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("api_keys::create_api_key", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::api_keys::create_api_key(api_key, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::api_keys::create_api_key(api_key, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn get_api_key(id: &str, db: &mut Database) -> Result<Option<DbApiKey>, EngineError> {
+    let _timer = DbTimer::start("api_keys::get_api_key", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::api_keys::get_api_key(id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::api_keys::get_api_key(id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn delete_api_key(id: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("api_keys::delete_api_key", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::api_keys::delete_api_key(id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::api_keys::delete_api_key(id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
@@ -2,6 +2,7 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{BotVersion, CsmlBot, Database, EngineError};
 
@@ -10,6 +11,8 @@ pub fn create_bot_version(
     csml_bot: CsmlBot,
     db: &mut Database,
 ) -> Result<String, EngineError> {
+    let _timer = DbTimer::start("bot::create_bot_version", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let serializable_bot = crate::data::to_serializable_bot(&csml_bot);
@@ -44,6 +47,8 @@ pub fn get_last_bot_version(
     bot_id: &str,
     db: &mut Database,
 ) -> Result<Option<BotVersion>, EngineError> {
+    let _timer = DbTimer::start("bot::get_last_bot_version", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -64,6 +69,8 @@ pub fn get_by_version_id(
     _bot_id: &str,
     db: &mut Database,
 ) -> Result<Option<BotVersion>, EngineError> {
+    let _timer = DbTimer::start("bot::get_by_version_id", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -85,6 +92,8 @@ pub fn get_bot_versions(
     pagination_key: Option<String>,
     db: &mut Database,
 ) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("bot::get_bot_versions", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -109,6 +118,8 @@ pub fn delete_bot_version(
     version_id: &str,
     db: &mut Database,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot::delete_bot_version", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -125,6 +136,8 @@ pub fn delete_bot_version(
 }
 
 pub fn delete_bot_versions(bot_id: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot::delete_bot_versions", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -142,6 +155,8 @@ pub fn delete_bot_versions(bot_id: &str, db: &mut Database) -> Result<(), Engine
 
 
 pub fn delete_all_bot_data(bot_id: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot::delete_all_bot_data", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         delete_bot_versions(bot_id, db)?;
@@ -154,6 +169,7 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut Database) -> Result<(), Engine
         mongodb_connector::bot::delete_all_bot_data(bot_id, "conversation", db)?;
         mongodb_connector::bot::delete_all_bot_data(bot_id, "state", db)?;
         mongodb_connector::bot::delete_all_bot_data(bot_id, "path", db)?;
+        mongodb_connector::bot_labels::delete_bot_labels(bot_id, db)?;
 
         return Ok(());
     }
@@ -169,6 +185,7 @@ pub fn delete_all_bot_data(bot_id: &str, db: &mut Database) -> Result<(), Engine
         dynamodb_connector::bot::delete_all_bot_data(bot_id, "interaction", db)?;
         dynamodb_connector::bot::delete_all_bot_data(bot_id, "conversation", db)?;
         dynamodb_connector::bot::delete_all_bot_data(bot_id, "state", db)?;
+        dynamodb_connector::bot_labels::delete_bot_labels(bot_id, db)?;
         return Ok(());
     }
 
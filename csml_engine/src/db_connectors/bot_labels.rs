@@ -0,0 +1,84 @@
+#[cfg(feature = "dynamo")]
+use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
+#[cfg(feature = "mongo")]
+use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::data::BotLabel;
+use crate::db_connectors::DbTimer;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Database, EngineError};
+
+pub fn set_bot_label(bot_id: &str, label: &BotLabel, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot_labels::set_bot_label", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_labels::set_bot_label(bot_id, label, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_labels::set_bot_label(bot_id, label, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn get_bot_label(
+    bot_id: &str,
+    label: &str,
+    db: &mut Database,
+) -> Result<Option<BotLabel>, EngineError> {
+    let _timer = DbTimer::start("bot_labels::get_bot_label", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_labels::get_bot_label(bot_id, label, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_labels::get_bot_label(bot_id, label, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn get_bot_labels(bot_id: &str, db: &mut Database) -> Result<Vec<BotLabel>, EngineError> {
+    let _timer = DbTimer::start("bot_labels::get_bot_labels", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_labels::get_bot_labels(bot_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_labels::get_bot_labels(bot_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn delete_bot_label(bot_id: &str, label: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot_labels::delete_bot_label", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_labels::delete_bot_label(bot_id, label, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_labels::delete_bot_label(bot_id, label, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
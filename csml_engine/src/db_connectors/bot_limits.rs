@@ -0,0 +1,89 @@
+#[cfg(feature = "dynamo")]
+use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
+#[cfg(feature = "mongo")]
+use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::data::BotLimits;
+use crate::db_connectors::DbTimer;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Database, EngineError};
+
+pub fn set_bot_limits(bot_id: &str, limits: &BotLimits, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot_limits::set_bot_limits", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_limits::set_bot_limits(bot_id, limits, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_limits::set_bot_limits(bot_id, limits, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn get_bot_limits(bot_id: &str, db: &mut Database) -> Result<Option<BotLimits>, EngineError> {
+    let _timer = DbTimer::start("bot_limits::get_bot_limits", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_limits::get_bot_limits(bot_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_limits::get_bot_limits(bot_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn delete_bot_limits(bot_id: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("bot_limits::delete_bot_limits", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_limits::delete_bot_limits(bot_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_limits::delete_bot_limits(bot_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Atomically increment the number of interactions of a bot on a given day if it is below max,
+ * and return the updated number (None if max was already reached)
+ */
+pub fn increment_bot_usage(
+    bot_id: &str,
+    day: &str,
+    max: u64,
+    db: &mut Database,
+) -> Result<Option<u64>, EngineError> {
+    let _timer = DbTimer::start("bot_limits::increment_bot_usage", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::bot_limits::increment_bot_usage(bot_id, day, max, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::bot_limits::increment_bot_usage(bot_id, day, max, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
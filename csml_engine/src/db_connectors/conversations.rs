@@ -2,8 +2,10 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, ConversationInfo, Database, DbConversation, EngineError};
+use chrono::{DateTime, Utc};
 
 pub fn create_conversation(
     flow_id: &str,
@@ -11,6 +13,8 @@ pub fn create_conversation(
     client: &Client,
     db: &mut Database,
 ) -> Result<String, EngineError> {
+    let _timer = DbTimer::start("conversations::create_conversation", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -31,6 +35,8 @@ pub fn create_conversation(
 }
 
 pub fn close_conversation(id: &str, client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("conversations::close_conversation", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -47,6 +53,8 @@ pub fn close_conversation(id: &str, client: &Client, db: &mut Database) -> Resul
 }
 
 pub fn close_all_conversations(client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("conversations::close_all_conversations", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -66,6 +74,8 @@ pub fn get_latest_open(
     client: &Client,
     db: &mut Database,
 ) -> Result<Option<DbConversation>, EngineError> {
+    let _timer = DbTimer::start("conversations::get_latest_open", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -86,6 +96,8 @@ pub fn update_conversation(
     flow_id: Option<String>,
     step_id: Option<String>,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("conversations::update_conversation", &data.db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(&data.db)?;
@@ -119,6 +131,8 @@ pub fn get_client_conversations(
     limit: Option<i64>,
     pagination_key: Option<String>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("conversations::get_client_conversations", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -147,3 +161,138 @@ pub fn get_client_conversations(
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
+
+pub fn get_bot_conversations(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &mut Database,
+) -> Result<Vec<DbConversation>, EngineError> {
+    let _timer = DbTimer::start("conversations::get_bot_conversations", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::get_bot_conversations(bot_id, from, to, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::conversations::get_bot_conversations(bot_id, from, to, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Retrieve a conversation of a client by its id, whatever its status
+ */
+pub fn get_conversation(
+    client: &Client,
+    id: &str,
+    db: &mut Database,
+) -> Result<Option<DbConversation>, EngineError> {
+    let _timer = DbTimer::start("conversations::get_conversation", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::conversations::get_conversation(client, id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::conversations::get_conversation(client, id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * List the conversations that are still open for any client of a bot
+ */
+pub fn get_bot_open_conversations(
+    bot_id: &str,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    db: &mut Database,
+) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("conversations::get_bot_open_conversations", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
+
+        return mongodb_connector::conversations::get_bot_open_conversations(
+            bot_id,
+            limit,
+            pagination_key,
+            db,
+        );
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
+
+        return dynamodb_connector::conversations::get_bot_open_conversations(
+            bot_id,
+            limit,
+            pagination_key,
+            db,
+        );
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * List the clients that had at least one conversation with a bot.
+ * Clients can be restricted to a given channel and to the users whose id starts with `user_id`.
+ */
+pub fn search_bot_clients(
+    bot_id: &str,
+    channel_id: Option<&str>,
+    user_id: Option<&str>,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    db: &mut Database,
+) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("conversations::search_bot_clients", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        let pagination_key = mongodb_connector::get_pagination_key(pagination_key)?;
+
+        return mongodb_connector::conversations::search_bot_clients(
+            bot_id,
+            channel_id,
+            user_id,
+            limit,
+            pagination_key,
+            db,
+        );
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        let pagination_key = dynamodb_connector::get_pagination_key(pagination_key)?;
+
+        return dynamodb_connector::conversations::search_bot_clients(
+            bot_id,
+            channel_id,
+            user_id,
+            limit,
+            pagination_key,
+            db,
+        );
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
@@ -0,0 +1,70 @@
+use crate::data::DynamoDbClient;
+use crate::db_connectors::dynamodb::{ApiKey, DynamoDbKey};
+use crate::db_connectors::DbApiKey;
+use crate::EngineError;
+use rusoto_dynamodb::*;
+
+use crate::db_connectors::dynamodb::utils::*;
+
+pub fn create_api_key(api_key: &DbApiKey, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item = ApiKey::new(api_key);
+
+    let input = PutItemInput {
+        table_name: get_table_name()?,
+        item: serde_dynamodb::to_hashmap(&item)?,
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+pub fn get_api_key(id: &str, db: &mut DynamoDbClient) -> Result<Option<DbApiKey>, EngineError> {
+    let item_key = DynamoDbKey {
+        hash: ApiKey::get_hash(id),
+        range: ApiKey::get_range(),
+    };
+
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let res = db.runtime.block_on(future)?;
+
+    match res.item {
+        Some(val) => {
+            let api_key: ApiKey = serde_dynamodb::from_hashmap(val)?;
+
+            Ok(Some(DbApiKey {
+                id: api_key.id,
+                scopes: api_key.scopes,
+                bot_ids: api_key.bot_ids,
+                created_at: api_key.created_at,
+            }))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn delete_api_key(id: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item_key = DynamoDbKey {
+        hash: ApiKey::get_hash(id),
+        range: ApiKey::get_range(),
+    };
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
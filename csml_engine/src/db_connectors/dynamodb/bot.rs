@@ -9,6 +9,8 @@ use std::collections::HashMap;
 use crate::EngineError;
 use crate::db_connectors::dynamodb::utils::*;
 
+type PaginationKey = Option<HashMap<String, AttributeValue>>;
+
 pub fn create_bot_version(
     bot_id: String,
     bot: String,
@@ -371,9 +373,25 @@ fn query_bot_info(
     db: &mut DynamoDbClient,
     pagination_key: Option<HashMap<String, AttributeValue>>,
 ) -> Result<QueryOutput, EngineError> {
-    let hash = format!("bot_id:{}#", bot_id);
+    let hash_prefix = format!("bot_id:{}#", bot_id);
 
-    let expr_attr_names = [
+    query_class_by_client(class, &hash_prefix, None, limit, db, pagination_key)
+}
+
+/**
+ * Query the keys of the items of a given class whose hash starts with `hash_prefix`.
+ * The optional filter is an expression on #hashKey or #rangeKey with a single :filterVal value.
+ * It is applied after the limit, so a page can hold fewer items than the limit.
+ */
+fn query_class_by_client(
+    class: &str,
+    hash_prefix: &str,
+    filter: Option<(&str, &str)>,
+    limit: i64,
+    db: &mut DynamoDbClient,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+) -> Result<QueryOutput, EngineError> {
+    let mut expr_attr_names: HashMap<String, String> = [
         (String::from("#classKey"), String::from("class")),
         (String::from("#hashKey"), String::from("hash")),
     ]
@@ -381,7 +399,7 @@ fn query_bot_info(
     .cloned()
     .collect();
 
-    let expr_attr_values = [
+    let mut expr_attr_values: HashMap<String, AttributeValue> = [
         (
             String::from(":classPrefix"),
             AttributeValue {
@@ -392,7 +410,7 @@ fn query_bot_info(
         (
             String::from(":hashPrefix"),
             AttributeValue {
-                s: Some(hash),
+                s: Some(hash_prefix.to_owned()),
                 ..Default::default()
             },
         ),
@@ -401,6 +419,25 @@ fn query_bot_info(
     .cloned()
     .collect();
 
+    let filter_expression = match filter {
+        Some((expression, value)) => {
+            // unused attribute names are rejected by DynamoDB
+            if expression.contains("#rangeKey") {
+                expr_attr_names.insert(String::from("#rangeKey"), String::from("range"));
+            }
+            expr_attr_values.insert(
+                String::from(":filterVal"),
+                AttributeValue {
+                    s: Some(value.to_owned()),
+                    ..Default::default()
+                },
+            );
+
+            Some(expression.to_owned())
+        }
+        None => None,
+    };
+
     // Class key = class val and begins with (hash key, hash prefix)
     // "#hashKey = :hashVal AND begins_with(#classKey, :classPrefix)".to_owned(),
     let input = QueryInput {
@@ -408,6 +445,7 @@ fn query_bot_info(
         key_condition_expression: Some(
             "#classKey = :classPrefix AND begins_with(#hashKey, :hashPrefix)".to_owned(),
         ),
+        filter_expression,
         index_name: Some("ClassByClientIndex".to_owned()),
         expression_attribute_names: Some(expr_attr_names),
         expression_attribute_values: Some(expr_attr_values),
@@ -422,6 +460,131 @@ fn query_bot_info(
     Ok(data)
 }
 
+/**
+ * Fetch the items matching the keys returned by the ClassByClientIndex
+ * (at most 100 keys, the maximum number of items in a single BatchGetItemInput)
+ */
+fn get_items_by_class_keys(
+    class_keys: Vec<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<HashMap<String, AttributeValue>>, EngineError> {
+    let mut items = vec![];
+
+    let mut keys = vec![];
+    for item in class_keys {
+        let class: Class = serde_dynamodb::from_hashmap(item)?;
+
+        keys.push(serde_dynamodb::to_hashmap(&DynamoDbKey {
+            hash: class.hash,
+            range: class.range,
+        })?);
+    }
+
+    while !keys.is_empty() {
+        let table_name = get_table_name()?;
+        let request_items = [(
+            table_name.to_owned(),
+            KeysAndAttributes {
+                keys,
+                ..Default::default()
+            },
+        )]
+        .iter()
+        .cloned()
+        .collect();
+
+        let input = BatchGetItemInput {
+            request_items,
+            ..Default::default()
+        };
+
+        let future = db.client.batch_get_item(input);
+        let output = db.runtime.block_on(future)?;
+
+        if let Some(mut responses) = output.responses {
+            items.append(responses.entry(table_name.to_owned()).or_default());
+        }
+
+        // unprocessed keys must be fetched again
+        keys = match output.unprocessed_keys {
+            Some(mut unprocessed) => match unprocessed.remove(&table_name) {
+                Some(unprocessed) => unprocessed.keys,
+                None => vec![],
+            },
+            None => vec![],
+        };
+    }
+
+    Ok(items)
+}
+
+/**
+ * Get all the items of a given class for a bot.
+ * The ClassByClientIndex only returns the keys of the items, so each page of keys
+ * is then fetched in a single batch.
+ */
+pub fn get_all_bot_items(
+    bot_id: &str,
+    class: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<HashMap<String, AttributeValue>>, EngineError> {
+    let mut items = vec![];
+    let mut pagination_key = None;
+
+    loop {
+        // 100 is the maximum number of items in a single BatchGetItemInput
+        let data = query_bot_info(bot_id, class, 100, db, pagination_key)?;
+
+        items.append(&mut get_items_by_class_keys(data.items.unwrap_or_default(), db)?);
+
+        pagination_key = data.last_evaluated_key;
+        if let None = &pagination_key {
+            return Ok(items);
+        }
+    }
+}
+
+/**
+ * Get a page of the items of a given class whose hash starts with `hash_prefix`,
+ * with the key of the next page if there is one
+ */
+pub fn get_class_items_page(
+    class: &str,
+    hash_prefix: &str,
+    filter: Option<(&str, &str)>,
+    limit: i64,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<(Vec<HashMap<String, AttributeValue>>, PaginationKey), EngineError> {
+    // 100 is the maximum number of items in a single BatchGetItemInput
+    let data = query_class_by_client(class, hash_prefix, filter, limit.min(100), db, pagination_key)?;
+    let items = get_items_by_class_keys(data.items.unwrap_or_default(), db)?;
+
+    Ok((items, data.last_evaluated_key))
+}
+
+/**
+ * Get a page of the keys of the items of a given class whose hash starts with `hash_prefix`,
+ * with the key of the next page if there is one
+ */
+pub fn get_class_keys_page(
+    class: &str,
+    hash_prefix: &str,
+    filter: Option<(&str, &str)>,
+    limit: i64,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<(Vec<Class>, PaginationKey), EngineError> {
+    let data = query_class_by_client(class, hash_prefix, filter, limit, db, pagination_key)?;
+
+    let mut keys = vec![];
+    for item in data.items.unwrap_or_default() {
+        keys.push(serde_dynamodb::from_hashmap(item)?);
+    }
+
+    Ok((keys, data.last_evaluated_key))
+}
+
 pub fn delete_all_bot_data(
     bot_id: &str,
     class: &str,
@@ -0,0 +1,146 @@
+use crate::data::{BotLabel, DynamoDbClient};
+use crate::db_connectors::dynamodb::{DynamoDbKey, Label};
+use crate::EngineError;
+use rusoto_dynamodb::*;
+use std::collections::HashMap;
+
+use crate::db_connectors::dynamodb::utils::*;
+
+pub fn set_bot_label(bot_id: &str, label: &BotLabel, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item = Label::new(bot_id, label);
+
+    let input = PutItemInput {
+        table_name: get_table_name()?,
+        item: serde_dynamodb::to_hashmap(&item)?,
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+pub fn get_bot_label(
+    bot_id: &str,
+    label: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Option<BotLabel>, EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Label::get_hash(bot_id),
+        range: Label::get_range(label),
+    };
+
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let res = db.runtime.block_on(future)?;
+
+    match res.item {
+        Some(val) => {
+            let item: Label = serde_dynamodb::from_hashmap(val)?;
+
+            Ok(Some(item.label))
+        }
+        None => Ok(None),
+    }
+}
+
+fn query_bot_labels(
+    bot_id: &str,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<QueryOutput, EngineError> {
+    let expr_attr_names = [
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#rangeKey"), String::from("range")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let expr_attr_values = [
+        (
+            String::from(":hashVal"),
+            AttributeValue {
+                s: Some(Label::get_hash(bot_id)),
+                ..Default::default()
+            },
+        ),
+        (
+            String::from(":rangePrefix"),
+            AttributeValue {
+                s: Some(String::from("bot_label#")),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = QueryInput {
+        table_name: get_table_name()?,
+        key_condition_expression: Some(
+            "#hashKey = :hashVal AND begins_with(#rangeKey, :rangePrefix)".to_owned(),
+        ),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        exclusive_start_key: pagination_key,
+        ..Default::default()
+    };
+
+    let future = db.client.query(input);
+    let data = db.runtime.block_on(future)?;
+
+    Ok(data)
+}
+
+pub fn get_bot_labels(bot_id: &str, db: &mut DynamoDbClient) -> Result<Vec<BotLabel>, EngineError> {
+    let mut labels = vec![];
+    let mut pagination_key = None;
+
+    loop {
+        let data = query_bot_labels(bot_id, pagination_key, db)?;
+
+        for item in data.items.unwrap_or_default() {
+            let item: Label = serde_dynamodb::from_hashmap(item)?;
+            labels.push(item.label);
+        }
+
+        pagination_key = data.last_evaluated_key;
+        if pagination_key.is_none() {
+            return Ok(labels);
+        }
+    }
+}
+
+pub fn delete_bot_label(bot_id: &str, label: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Label::get_hash(bot_id),
+        range: Label::get_range(label),
+    };
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+pub fn delete_bot_labels(bot_id: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    for label in get_bot_labels(bot_id, db)? {
+        delete_bot_label(bot_id, &label.label, db)?;
+    }
+
+    Ok(())
+}
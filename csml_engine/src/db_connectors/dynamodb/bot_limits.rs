@@ -0,0 +1,144 @@
+use crate::data::{BotLimits, DynamoDbClient};
+use crate::db_connectors::dynamodb::{DynamoDbKey, Limits};
+use crate::EngineError;
+use rusoto_core::RusotoError;
+use rusoto_dynamodb::*;
+use std::collections::HashMap;
+
+use crate::db_connectors::dynamodb::utils::*;
+
+pub fn set_bot_limits(bot_id: &str, limits: &BotLimits, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item = Limits::new(bot_id, limits);
+
+    let input = PutItemInput {
+        table_name: get_table_name()?,
+        item: serde_dynamodb::to_hashmap(&item)?,
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+pub fn get_bot_limits(bot_id: &str, db: &mut DynamoDbClient) -> Result<Option<BotLimits>, EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Limits::get_hash(bot_id),
+        range: Limits::get_range(),
+    };
+
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let res = db.runtime.block_on(future)?;
+
+    match res.item {
+        Some(val) => {
+            let item: Limits = serde_dynamodb::from_hashmap(val)?;
+
+            Ok(Some(item.limits))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn delete_bot_limits(bot_id: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Limits::get_hash(bot_id),
+        range: Limits::get_range(),
+    };
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+/**
+ * hash = bot_usage#BOT_ID
+ * range = bot_usage#DAY
+ */
+pub fn increment_bot_usage(
+    bot_id: &str,
+    day: &str,
+    max: u64,
+    db: &mut DynamoDbClient,
+) -> Result<Option<u64>, EngineError> {
+    let item_key = DynamoDbKey {
+        hash: format!("bot_usage#{}", bot_id),
+        range: format!("bot_usage#{}", day),
+    };
+
+    let expr_attr_names: HashMap<String, String> = [
+        (String::from("#interactions"), String::from("interactions")),
+        (String::from("#class"), String::from("class")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let expr_attr_values: HashMap<String, AttributeValue> = [
+        (
+            String::from(":one"),
+            AttributeValue {
+                n: Some("1".to_owned()),
+                ..Default::default()
+            },
+        ),
+        (
+            String::from(":class"),
+            AttributeValue {
+                s: Some("bot_usage".to_owned()),
+                ..Default::default()
+            },
+        ),
+        (
+            String::from(":max"),
+            AttributeValue {
+                n: Some(max.to_string()),
+                ..Default::default()
+            },
+        ),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = UpdateItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        update_expression: Some("ADD #interactions :one SET #class = :class".to_owned()),
+        condition_expression: Some(
+            "attribute_not_exists(#interactions) OR #interactions < :max".to_owned(),
+        ),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        return_values: Some("UPDATED_NEW".to_owned()),
+        ..Default::default()
+    };
+
+    let future = db.client.update_item(input);
+    let res = match db.runtime.block_on(future) {
+        Ok(res) => res,
+        Err(RusotoError::Service(UpdateItemError::ConditionalCheckFailed(_))) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let interactions = res
+        .attributes
+        .and_then(|attributes| attributes.get("interactions").and_then(|value| value.n.to_owned()))
+        .and_then(|value| value.parse::<u64>().ok());
+
+    Ok(Some(interactions.unwrap_or(1)))
+}
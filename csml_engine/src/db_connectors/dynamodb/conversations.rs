@@ -1,9 +1,13 @@
 use crate::data::DynamoDbClient;
-use crate::db_connectors::dynamodb::{Conversation, ConversationDeleteInfo, DynamoDbKey};
+use crate::db_connectors::dynamodb::{
+    bot::{get_all_bot_items, get_class_items_page, get_class_keys_page},
+    Conversation, ConversationDeleteInfo, DynamoDbKey,
+};
 use crate::db_connectors::DbConversation;
 use crate::{
     Client, EngineError,
 };
+use chrono::{DateTime, Utc};
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
@@ -562,3 +566,188 @@ pub fn get_client_conversations(
         None => Ok(serde_json::json!({ "conversations": conversations })),
     }
 }
+
+/**
+ * Conversations saved before the client was added to the item only have their client in the hash
+ */
+fn format_conversation_struct(conv: Conversation, bot_id: &str) -> DbConversation {
+    let client = match conv.client {
+        Some(client) => client,
+        None => Client {
+            bot_id: conv.bot_id.unwrap_or_else(|| bot_id.to_owned()),
+            channel_id: conv.channel_id.unwrap_or_default(),
+            user_id: conv.user_id.unwrap_or_default(),
+        },
+    };
+
+    DbConversation {
+        id: conv.id,
+        client,
+        flow_id: conv.flow_id,
+        step_id: conv.step_id,
+        status: conv.status,
+        last_interaction_at: conv.last_interaction_at,
+        updated_at: conv.updated_at,
+        created_at: conv.created_at,
+    }
+}
+
+pub fn get_bot_conversations(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<DbConversation>, EngineError> {
+    let mut conversations = vec![];
+
+    for item in get_all_bot_items(bot_id, "conversation", db)? {
+        let conv: Conversation = serde_dynamodb::from_hashmap(item)?;
+
+        if !is_in_date_range(&conv.created_at, from, to) {
+            continue;
+        }
+
+        conversations.push(format_conversation_struct(conv, bot_id));
+    }
+
+    conversations.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(conversations)
+}
+
+/**
+ * The status of a conversation is part of its range key, so both statuses are tried
+ */
+pub fn get_conversation(
+    client: &Client,
+    id: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Option<DbConversation>, EngineError> {
+    for status in ["OPEN", "CLOSED"].iter() {
+        let input = GetItemInput {
+            table_name: get_table_name()?,
+            key: serde_dynamodb::to_hashmap(&Conversation::get_key(client, status, id))?,
+            ..Default::default()
+        };
+
+        let future = db.client.get_item(input);
+        let res = db.runtime.block_on(future)?;
+
+        if let Some(item) = res.item {
+            let conv: Conversation = serde_dynamodb::from_hashmap(item)?;
+
+            return Ok(Some(format_conversation_struct(conv, &client.bot_id)));
+        }
+    }
+
+    Ok(None)
+}
+
+/**
+ * Open conversations are listed in the order of the ClassByClientIndex (by client).
+ * As the status is filtered after the limit, a page can hold fewer conversations than the limit.
+ */
+pub fn get_bot_open_conversations(
+    bot_id: &str,
+    limit: Option<i64>,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let limit = match limit {
+        Some(limit) if limit >= 1 => limit,
+        Some(_limit) => 20,
+        None => 20,
+    };
+
+    let (items, last_evaluated_key) = get_class_items_page(
+        "conversation",
+        &format!("bot_id:{}#", bot_id),
+        Some(("begins_with(#rangeKey, :filterVal)", "conversation#OPEN#")),
+        limit,
+        pagination_key,
+        db,
+    )?;
+
+    let mut conversations = vec![];
+    for item in items {
+        let conv: Conversation = serde_dynamodb::from_hashmap(item)?;
+
+        conversations.push(format_conversation_struct(conv, bot_id));
+    }
+
+    match last_evaluated_key {
+        Some(pagination_key) => {
+            let pagination_key = base64::encode(serde_json::json!(pagination_key).to_string());
+
+            Ok(serde_json::json!({"conversations": conversations, "pagination_key": pagination_key}))
+        }
+        None => Ok(serde_json::json!({ "conversations": conversations })),
+    }
+}
+
+/**
+ * Clients are read from the hash of their conversations in the ClassByClientIndex,
+ * which is sorted by hash: the conversations of a client follow each other, but a client
+ * whose conversations are split between two pages is returned in both of them.
+ */
+pub fn search_bot_clients(
+    bot_id: &str,
+    channel_id: Option<&str>,
+    user_id: Option<&str>,
+    limit: Option<i64>,
+    pagination_key: Option<HashMap<String, AttributeValue>>,
+    db: &mut DynamoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let limit = match limit {
+        Some(limit) if limit >= 1 => limit,
+        Some(_limit) => 20,
+        None => 20,
+    };
+
+    let bot_prefix = format!("bot_id:{}#channel_id:", bot_id);
+    let user_filter = user_id.map(|user_id| format!("#user_id:{}", user_id));
+
+    // the hash is bot_id:xxxx#channel_id:xxxx#user_id:xxxx, so a user id prefix can only
+    // be part of the key condition once the channel is known
+    let (hash_prefix, filter) = match (channel_id, &user_filter) {
+        (Some(channel_id), Some(user_filter)) => (format!("{}{}{}", bot_prefix, channel_id, user_filter), None),
+        (Some(channel_id), None) => (format!("{}{}#user_id:", bot_prefix, channel_id), None),
+        (None, Some(user_filter)) => (bot_prefix.to_owned(), Some(("contains(#hashKey, :filterVal)", user_filter.as_str()))),
+        (None, None) => (bot_prefix.to_owned(), None),
+    };
+
+    let (keys, last_evaluated_key) = get_class_keys_page(
+        "conversation",
+        &hash_prefix,
+        filter,
+        limit,
+        pagination_key,
+        db,
+    )?;
+
+    let mut clients: Vec<Client> = vec![];
+    for key in keys {
+        let (channel_id, user_id) = match key.hash[bot_prefix.len()..].split_once("#user_id:") {
+            Some(client) => client,
+            None => continue,
+        };
+
+        match clients.last() {
+            Some(last) if last.channel_id == channel_id && last.user_id == user_id => continue,
+            _ => clients.push(Client {
+                bot_id: bot_id.to_owned(),
+                channel_id: channel_id.to_owned(),
+                user_id: user_id.to_owned(),
+            }),
+        }
+    }
+
+    match last_evaluated_key {
+        Some(pagination_key) => {
+            let pagination_key = base64::encode(serde_json::json!(pagination_key).to_string());
+
+            Ok(serde_json::json!({"clients": clients, "pagination_key": pagination_key}))
+        }
+        None => Ok(serde_json::json!({ "clients": clients })),
+    }
+}
@@ -0,0 +1,65 @@
+use crate::data::DynamoDbClient;
+use crate::db_connectors::dynamodb::{DynamoDbKey, Policy};
+use crate::EngineError;
+use csml_interpreter::data::HttpPolicy;
+use rusoto_dynamodb::*;
+
+use crate::db_connectors::dynamodb::utils::*;
+
+pub fn set_http_policy(bot_id: &str, policy: &HttpPolicy, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item = Policy::new(bot_id, policy);
+
+    let input = PutItemInput {
+        table_name: get_table_name()?,
+        item: serde_dynamodb::to_hashmap(&item)?,
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
+pub fn get_http_policy(bot_id: &str, db: &mut DynamoDbClient) -> Result<Option<HttpPolicy>, EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Policy::get_hash(bot_id),
+        range: Policy::get_range(),
+    };
+
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let res = db.runtime.block_on(future)?;
+
+    match res.item {
+        Some(val) => {
+            let item: Policy = serde_dynamodb::from_hashmap(val)?;
+
+            Ok(Some(item.policy))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn delete_http_policy(bot_id: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let item_key = DynamoDbKey {
+        hash: Policy::get_hash(bot_id),
+        range: Policy::get_range(),
+    };
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
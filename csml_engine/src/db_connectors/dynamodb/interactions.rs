@@ -1,6 +1,10 @@
 use crate::data::DynamoDbClient;
-use crate::db_connectors::dynamodb::{Interaction, InteractionDeleteInfo, DynamoDbKey};
-use crate::{encrypt::encrypt_data, Client, EngineError};
+use crate::db_connectors::dynamodb::{
+    bot::get_all_bot_items, Interaction, InteractionDeleteInfo, DynamoDbKey,
+};
+use crate::db_connectors::DbInteraction;
+use crate::{encrypt::{encrypt_data, decrypt_data}, Client, EngineError};
+use chrono::{DateTime, Utc};
 use rusoto_dynamodb::*;
 use uuid::Uuid;
 use std::collections::HashMap;
@@ -10,11 +14,13 @@ use crate::db_connectors::dynamodb::utils::*;
 pub fn init_interaction(
     event: serde_json::Value,
     client: &Client,
+    version_id: Option<&str>,
     db: &mut DynamoDbClient,
 ) -> Result<String, EngineError> {
     let id = Uuid::new_v4();
     let encrypted_event = encrypt_data(&event)?;
-    let interaction = Interaction::new(&id, client, &encrypted_event);
+    let mut interaction = Interaction::new(&id, client, &encrypted_event);
+    interaction.version_id = version_id.map(|id| id.to_owned());
 
     let expr_attr_names = [
         (String::from("#hashKey"), String::from("hash")),
@@ -247,3 +253,73 @@ pub fn delete_user_interactions(client: &Client, db: &mut DynamoDbClient) -> Res
         }
     }
 }
+
+pub fn get_bot_interactions(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<DbInteraction>, EngineError> {
+    let mut interactions = vec![];
+
+    for item in get_all_bot_items(bot_id, "interaction", db)? {
+        let interaction: Interaction = serde_dynamodb::from_hashmap(item)?;
+
+        if !is_in_date_range(&interaction.created_at, from, to) {
+            continue;
+        }
+
+        let client = match interaction.client {
+            Some(client) => client,
+            None => Client {
+                bot_id: interaction.bot_id.unwrap_or_else(|| bot_id.to_owned()),
+                channel_id: interaction.channel_id.unwrap_or_default(),
+                user_id: interaction.user_id.unwrap_or_default(),
+            },
+        };
+
+        interactions.push(DbInteraction {
+            id: interaction.id,
+            client,
+            success: interaction.success,
+            event: decrypt_data(interaction.event)?,
+            version_id: interaction.version_id,
+            updated_at: interaction.updated_at,
+            created_at: interaction.created_at,
+        });
+    }
+
+    interactions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(interactions)
+}
+
+pub fn get_interaction(
+    client: &Client,
+    id: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Option<DbInteraction>, EngineError> {
+    let input = GetItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&Interaction::get_key(client, id))?,
+        ..Default::default()
+    };
+
+    let future = db.client.get_item(input);
+    let res = db.runtime.block_on(future)?;
+
+    let interaction: Interaction = match res.item {
+        Some(item) => serde_dynamodb::from_hashmap(item)?,
+        None => return Ok(None),
+    };
+
+    Ok(Some(DbInteraction {
+        id: interaction.id,
+        client: client.to_owned(),
+        success: interaction.success,
+        event: decrypt_data(interaction.event)?,
+        version_id: interaction.version_id,
+        updated_at: interaction.updated_at,
+        created_at: interaction.created_at,
+    }))
+}
@@ -1,4 +1,5 @@
 use crate::db_connectors::dynamodb::{get_db, Message, MessageDeleteInfo, DynamoDbClient, DynamoDbKey,};
+use crate::db_connectors::DbMessage;
 use crate::{encrypt::{encrypt_data, decrypt_data}, ConversationInfo, EngineError, Client};
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
@@ -205,6 +206,73 @@ pub fn get_client_messages(
     }
 }
 
+pub fn get_conversation_messages(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<DbMessage>, EngineError> {
+    let mut messages = vec![];
+    let mut pagination_key = None;
+
+    let expr_attr_names: HashMap<String, String> = [
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#rangeKey"), String::from("range")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    loop {
+        let data = query_messages(
+            client,
+            db,
+            Message::get_range(conversation_id, ""),
+            None,
+            100,
+            pagination_key,
+            None,
+            Some(expr_attr_names.clone()),
+        )?;
+
+        for item in data.items.unwrap_or_default() {
+            let message: Message = serde_dynamodb::from_hashmap(item)?;
+
+            messages.push(DbMessage {
+                id: message.id,
+                client: message.client.unwrap_or_else(|| client.to_owned()),
+                interaction_id: message.interaction_id,
+                conversation_id: message.conversation_id,
+                flow_id: message.flow_id,
+                step_id: message.step_id,
+                message_order: message.message_order,
+                interaction_order: message.interaction_order,
+                direction: message.direction,
+                payload: decrypt_data(message.payload)?,
+                content_type: message.content_type,
+                created_at: message.created_at,
+            });
+        }
+
+        pagination_key = data.last_evaluated_key;
+        if let None = &pagination_key {
+            break;
+        }
+    }
+
+    // message ids are random, so the range key does not keep the messages in order:
+    // received messages are always saved before the messages sent in the same interaction
+    messages.sort_by(|a, b| {
+        (&a.created_at, a.direction != "RECEIVE", a.interaction_order, a.message_order).cmp(&(
+            &b.created_at,
+            b.direction != "RECEIVE",
+            b.interaction_order,
+            b.message_order,
+        ))
+    });
+
+    Ok(messages)
+}
+
 pub fn delete_user_messages(client: &Client, db: &mut DynamoDbClient) -> Result<(), EngineError> {
     let mut pagination_key = None;
 
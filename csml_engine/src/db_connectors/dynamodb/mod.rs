@@ -1,13 +1,19 @@
-use crate::data::DynamoDbClient;
+use crate::data::{BotLabel, BotLimits, DynamoDbClient};
+use crate::db_connectors::DbApiKey;
 use crate::{Client, Database, EngineError};
+use csml_interpreter::data::HttpPolicy;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::collections::HashMap;
 use rusoto_dynamodb::AttributeValue;
 
+pub mod api_keys;
 pub mod aws_s3;
 pub mod bot;
+pub mod bot_labels;
+pub mod bot_limits;
 pub mod conversations;
+pub mod http_policies;
 pub mod interactions;
 pub mod memories;
 pub mod messages;
@@ -59,6 +65,17 @@ pub fn get_db<'a>(db: &'a mut Database) -> Result<&'a mut DynamoDbClient, Engine
     }
 }
 
+pub fn ping(db: &mut DynamoDbClient) -> Result<(), EngineError> {
+    let input = rusoto_dynamodb::DescribeTableInput {
+        table_name: get_table_name()?,
+    };
+
+    let future = rusoto_dynamodb::DynamoDb::describe_table(&db.client, input);
+    db.runtime.block_on(future)?;
+
+    Ok(())
+}
+
 pub fn get_pagination_key(pagination_key: Option<String>) ->  Result<Option<HashMap<String, AttributeValue>>, EngineError> {
     match pagination_key {
         Some(key) => {
@@ -90,6 +107,158 @@ impl DynamoDbKey {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiKey {
+    pub hash: String,
+    pub range: String,
+    pub class: String,
+
+    pub id: String,
+    pub scopes: Vec<String>,
+    pub bot_ids: Option<Vec<String>>,
+    pub created_at: String,
+}
+
+impl ApiKey {
+    pub fn get_hash(id: &str) -> String {
+        format!("api_key#{}", id)
+    }
+
+    pub fn get_range() -> String {
+        "api_key".to_owned()
+    }
+
+    /**
+     * hash = api_key#xxxx
+     * range = api_key
+     */
+    pub fn new(api_key: &DbApiKey) -> Self {
+        let class_name = "api_key";
+
+        Self {
+            hash: Self::get_hash(&api_key.id),
+            range: Self::get_range(),
+            class: class_name.to_owned(),
+            id: api_key.id.to_owned(),
+            scopes: api_key.scopes.to_owned(),
+            bot_ids: api_key.bot_ids.to_owned(),
+            created_at: get_date_time(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Limits {
+    pub hash: String,
+    pub range: String,
+    pub class: String,
+
+    pub bot_id: String,
+    pub limits: BotLimits,
+    pub updated_at: String,
+}
+
+impl Limits {
+    pub fn get_hash(bot_id: &str) -> String {
+        format!("bot_limits#{}", bot_id)
+    }
+
+    pub fn get_range() -> String {
+        "bot_limits".to_owned()
+    }
+
+    /**
+     * hash = bot_limits#BOT_ID
+     * range = bot_limits
+     */
+    pub fn new(bot_id: &str, limits: &BotLimits) -> Self {
+        let class_name = "bot_limits";
+
+        Self {
+            hash: Self::get_hash(bot_id),
+            range: Self::get_range(),
+            class: class_name.to_owned(),
+            bot_id: bot_id.to_owned(),
+            limits: limits.to_owned(),
+            updated_at: get_date_time(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Policy {
+    pub hash: String,
+    pub range: String,
+    pub class: String,
+
+    pub bot_id: String,
+    pub policy: HttpPolicy,
+    pub updated_at: String,
+}
+
+impl Policy {
+    pub fn get_hash(bot_id: &str) -> String {
+        format!("http_policy#{}", bot_id)
+    }
+
+    pub fn get_range() -> String {
+        "http_policy".to_owned()
+    }
+
+    /**
+     * hash = http_policy#BOT_ID
+     * range = http_policy
+     */
+    pub fn new(bot_id: &str, policy: &HttpPolicy) -> Self {
+        let class_name = "http_policy";
+
+        Self {
+            hash: Self::get_hash(bot_id),
+            range: Self::get_range(),
+            class: class_name.to_owned(),
+            bot_id: bot_id.to_owned(),
+            policy: policy.to_owned(),
+            updated_at: get_date_time(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Label {
+    pub hash: String,
+    pub range: String,
+    pub class: String,
+
+    pub bot_id: String,
+    pub label: BotLabel,
+}
+
+impl Label {
+    pub fn get_hash(bot_id: &str) -> String {
+        format!("bot_label#{}", bot_id)
+    }
+
+    pub fn get_range(label: &str) -> String {
+        make_range(&["bot_label", label])
+    }
+
+    /**
+     * hash = bot_label#BOT_ID
+     * range = bot_label#LABEL
+     */
+    pub fn new(bot_id: &str, label: &BotLabel) -> Self {
+        let class_name = "bot_label";
+
+        Self {
+            hash: Self::get_hash(bot_id),
+            range: Self::get_range(&label.label),
+            class: class_name.to_owned(),
+            bot_id: bot_id.to_owned(),
+            label: label.to_owned(),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Bot {
     pub hash: String,
@@ -228,6 +397,7 @@ pub struct Interaction {
     pub user_id: Option<String>,
     pub success: bool,
     pub event: String,
+    pub version_id: Option<String>,
     pub updated_at: String,
     pub created_at: String,
 }
@@ -268,6 +438,7 @@ impl Interaction {
             user_id: Some(client.user_id.to_owned()),
             success: false,
             event: encrypted_event.to_owned(),
+            version_id: None,
             updated_at: now.to_owned(),
             created_at: now.to_owned(),
         }
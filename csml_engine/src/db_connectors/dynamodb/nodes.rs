@@ -1,5 +1,6 @@
 use crate::db_connectors::dynamodb::{get_db, Node, NodeDeleteInfo, DynamoDbKey, DynamoDbClient};
-use crate::{ConversationInfo, EngineError};
+use crate::db_connectors::{DbConversation, DbNode};
+use crate::{Client, ConversationInfo, EngineError};
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
@@ -116,6 +117,60 @@ fn query_nodes(
     Ok(data)
 }
 
+pub fn get_conversation_nodes(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut DynamoDbClient,
+) -> Result<Vec<DbNode>, EngineError> {
+    let mut nodes = vec![];
+    let mut pagination_key = None;
+
+    let expr_attr_names: HashMap<String, String> = [
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#rangeKey"), String::from("range")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    loop {
+        let data = query_nodes(
+            conversation_id,
+            db,
+            100,
+            pagination_key,
+            None,
+            Some(expr_attr_names.clone()),
+        )?;
+
+        for item in data.items.unwrap_or_default() {
+            let node: Node = serde_dynamodb::from_hashmap(item)?;
+
+            nodes.push(DbNode {
+                id: node.id,
+                client: node.client.unwrap_or_else(|| client.to_owned()),
+                interaction_id: node.interaction_id,
+                conversation_id: node.conversation_id,
+                flow_id: node.flow_id,
+                step_id: node.step_id,
+                next_step: node.next_step,
+                next_flow: node.next_flow,
+                created_at: node.created_at,
+            });
+        }
+
+        pagination_key = data.last_evaluated_key;
+        if let None = &pagination_key {
+            break;
+        }
+    }
+
+    // node ids are random, so only the creation date keeps the path in order
+    nodes.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(nodes)
+}
+
 pub fn delete_conversation_nodes(conversation_id: &str, db: &mut DynamoDbClient) -> Result<(), EngineError> {
     let mut pagination_key = None;
     let expr_attr_names: HashMap<String, String> = [
@@ -182,3 +237,20 @@ pub fn delete_conversation_nodes(conversation_id: &str, db: &mut DynamoDbClient)
         }
     }
 }
+
+/**
+ * Retrieve the nodes of the given conversations. Nodes are stored under the key of their
+ * conversation, so there is one query per conversation.
+ */
+pub fn get_bot_nodes(
+    conversations: &[DbConversation],
+    db: &mut DynamoDbClient,
+) -> Result<Vec<DbNode>, EngineError> {
+    let mut nodes = vec![];
+
+    for conversation in conversations.iter() {
+        nodes.append(&mut get_conversation_nodes(&conversation.client, &conversation.id, db)?);
+    }
+
+    Ok(nodes)
+}
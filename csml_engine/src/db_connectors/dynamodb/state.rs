@@ -4,6 +4,7 @@ use crate::{
     encrypt::{decrypt_data, encrypt_data},
     Client, EngineError,
 };
+use rusoto_core::RusotoError;
 use rusoto_dynamodb::*;
 use std::collections::HashMap;
 
@@ -154,6 +155,111 @@ pub fn set_state_items(
     Ok(())
 }
 
+/**
+ * The lease is written with a condition so that it only succeeds if the key
+ * does not exist yet or if the previous lease has already expired.
+ * Dates are stored in the sortable get_date_time format, so they can be compared as strings.
+ */
+pub fn acquire_state_lease(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    owner: &str,
+    ttl: i64,
+    db: &mut DynamoDbClient,
+) -> Result<bool, EngineError> {
+    let now = get_date_time();
+    let expires_at = (chrono::Utc::now() + chrono::Duration::milliseconds(ttl))
+        .format("%Y-%m-%dT%H:%M:%S.%3fZ")
+        .to_string();
+
+    let mut lease = State::new(client, _type, key, owner);
+    lease.expires_at = Some(expires_at);
+
+    let expr_attr_names = [
+        (String::from("#hashKey"), String::from("hash")),
+        (String::from("#expiresAtKey"), String::from("expires_at")),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    let expr_attr_values = [(
+        String::from(":nowVal"),
+        AttributeValue {
+            s: Some(now),
+            ..Default::default()
+        },
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = PutItemInput {
+        table_name: get_table_name()?,
+        item: serde_dynamodb::to_hashmap(&lease)?,
+        condition_expression: Some(
+            "attribute_not_exists(#hashKey) OR #expiresAtKey < :nowVal".to_owned(),
+        ),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        ..Default::default()
+    };
+
+    let future = db.client.put_item(input);
+    match db.runtime.block_on(future) {
+        Ok(_) => Ok(true),
+        Err(RusotoError::Service(PutItemError::ConditionalCheckFailed(_))) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn release_state_lease(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    owner: &str,
+    db: &mut DynamoDbClient,
+) -> Result<(), EngineError> {
+    let item_key = DynamoDbKey {
+        hash: State::get_hash(client),
+        range: State::get_range(_type, key),
+    };
+
+    let expr_attr_names = [(String::from("#valueKey"), String::from("value"))]
+        .iter()
+        .cloned()
+        .collect();
+
+    let expr_attr_values = [(
+        String::from(":ownerVal"),
+        AttributeValue {
+            s: Some(owner.to_owned()),
+            ..Default::default()
+        },
+    )]
+    .iter()
+    .cloned()
+    .collect();
+
+    let input = DeleteItemInput {
+        table_name: get_table_name()?,
+        key: serde_dynamodb::to_hashmap(&item_key)?,
+        condition_expression: Some("#valueKey = :ownerVal".to_owned()),
+        expression_attribute_names: Some(expr_attr_names),
+        expression_attribute_values: Some(expr_attr_values),
+        ..Default::default()
+    };
+
+    let future = db.client.delete_item(input);
+    match db.runtime.block_on(future) {
+        Ok(_) => Ok(()),
+        // the lease expired and was taken over by someone else, leave it be
+        Err(RusotoError::Service(DeleteItemError::ConditionalCheckFailed(_))) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
 fn query_states(
     client: &Client,
     db: &mut DynamoDbClient,
@@ -1,4 +1,5 @@
 use crate::{Client, EngineError, data::DynamoDbClient};
+use chrono::{DateTime, Utc};
 use rusoto_core::RusotoError;
 use rusoto_dynamodb::{BatchWriteItemError, BatchWriteItemInput, DynamoDb};
 use std::{thread, time};
@@ -23,6 +24,21 @@ pub fn get_date_time() -> String {
         .to_string();
 }
 
+/**
+ * Check that a date formatted by get_date_time is in the given range.
+ * Both bounds are optional: `from` is inclusive and `to` is exclusive.
+ */
+pub fn is_in_date_range(
+    date: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> bool {
+    let format = |date: DateTime<Utc>| date.format("%Y-%m-%dT%H:%M:%S.%3fZ").to_string();
+
+    from.map_or(true, |from| date >= format(from).as_str())
+        && to.map_or(true, |to| date < format(to).as_str())
+}
+
 /**
  * Return the table's name
  */
@@ -0,0 +1,62 @@
+#[cfg(feature = "dynamo")]
+use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
+#[cfg(feature = "mongo")]
+use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
+use crate::error_messages::ERROR_DB_SETUP;
+use crate::{Database, EngineError};
+use csml_interpreter::data::HttpPolicy;
+
+pub fn set_http_policy(bot_id: &str, policy: &HttpPolicy, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("http_policies::set_http_policy", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::http_policies::set_http_policy(bot_id, policy, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::http_policies::set_http_policy(bot_id, policy, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn get_http_policy(bot_id: &str, db: &mut Database) -> Result<Option<HttpPolicy>, EngineError> {
+    let _timer = DbTimer::start("http_policies::get_http_policy", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::http_policies::get_http_policy(bot_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::http_policies::get_http_policy(bot_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub fn delete_http_policy(bot_id: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("http_policies::delete_http_policy", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::http_policies::delete_http_policy(bot_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::http_policies::delete_http_policy(bot_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
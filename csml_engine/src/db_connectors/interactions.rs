@@ -2,30 +2,38 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
+use crate::db_connectors::DbInteraction;
 use crate::{Client, ConversationInfo, Database, EngineError};
+use chrono::{DateTime, Utc};
 
 pub fn init_interaction(
     event: serde_json::Value,
     client: &Client,
+    version_id: Option<&str>,
     db: &mut Database,
 ) -> Result<String, EngineError> {
+    let _timer = DbTimer::start("interactions::init_interaction", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
-        return mongodb_connector::interactions::init_interaction(event, client, db);
+        return mongodb_connector::interactions::init_interaction(event, client, version_id, db);
     }
 
     #[cfg(feature = "dynamo")]
     if is_dynamodb() {
         let db = dynamodb_connector::get_db(db)?;
-        return dynamodb_connector::interactions::init_interaction(event, client, db);
+        return dynamodb_connector::interactions::init_interaction(event, client, version_id, db);
     }
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
 pub fn update_interaction(data: &mut ConversationInfo, success: bool) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("interactions::update_interaction", &data.db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(&data.db)?;
@@ -50,3 +58,51 @@ pub fn update_interaction(data: &mut ConversationInfo, success: bool) -> Result<
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
+
+pub fn get_bot_interactions(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &mut Database,
+) -> Result<Vec<DbInteraction>, EngineError> {
+    let _timer = DbTimer::start("interactions::get_bot_interactions", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::interactions::get_bot_interactions(bot_id, from, to, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::interactions::get_bot_interactions(bot_id, from, to, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Retrieve an interaction of a client by its id
+ */
+pub fn get_interaction(
+    client: &Client,
+    id: &str,
+    db: &mut Database,
+) -> Result<Option<DbInteraction>, EngineError> {
+    let _timer = DbTimer::start("interactions::get_interaction", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::interactions::get_interaction(client, id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::interactions::get_interaction(client, id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
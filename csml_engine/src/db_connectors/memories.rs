@@ -2,6 +2,7 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, ConversationInfo, Database, EngineError, Memory};
 use std::collections::HashMap;
@@ -10,6 +11,7 @@ pub fn add_memories(
     data: &mut ConversationInfo,
     memories: &HashMap<String, Memory>,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("memories::add_memories", &data.db);
 
     #[cfg(feature = "mongo")]
     if is_mongodb() {
@@ -30,6 +32,8 @@ pub fn create_client_memory(
     value: serde_json::Value,
     db: &mut Database
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("memories::create_client_memory", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -47,6 +51,8 @@ pub fn create_client_memory(
 }
 
 pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("memories::internal_use_get_memories", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -66,6 +72,8 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
  * Get client Memories
  */
  pub fn get_memories(client: &Client, db: &mut Database) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("memories::get_memories", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -85,6 +93,8 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
  * Get client Memory
  */
  pub fn get_memory(client: &Client, key: &str, db: &mut Database) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("memories::get_memory", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -102,6 +112,8 @@ pub fn internal_use_get_memories(client: &Client, db: &mut Database) -> Result<s
 
 
 pub fn delete_client_memory(client: &Client, key: &str, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("memories::delete_client_memory", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -118,6 +130,8 @@ pub fn delete_client_memory(client: &Client, key: &str, db: &mut Database) -> Re
 }
 
 pub fn delete_client_memories(client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("memories::delete_client_memories", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
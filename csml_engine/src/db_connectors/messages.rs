@@ -2,7 +2,9 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
+use crate::db_connectors::DbMessage;
 use crate::{Database, ConversationInfo, EngineError, Client};
 
 pub fn add_messages_bulk(
@@ -11,6 +13,8 @@ pub fn add_messages_bulk(
     interaction_order: i32,
     direction: &str,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("messages::add_messages_bulk", &data.db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         return mongodb_connector::messages::add_messages_bulk(
@@ -40,6 +44,8 @@ pub fn get_client_messages(
     limit: Option<i64>,
     pagination_key: Option<String>,
 ) -> Result<serde_json::Value, EngineError> {
+    let _timer = DbTimer::start("messages::get_client_messages", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -68,3 +74,33 @@ pub fn get_client_messages(
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
+
+/**
+ * Retrieve all the messages (both received and sent) of a given conversation,
+ * in the order they were saved
+ */
+pub fn get_conversation_messages(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<Vec<DbMessage>, EngineError> {
+    let _timer = DbTimer::start("messages::get_conversation_messages", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::messages::get_conversation_messages(client, conversation_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::messages::get_conversation_messages(
+            client,
+            conversation_id,
+            db,
+        );
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
@@ -34,15 +34,21 @@
 use crate::data::{Database, EngineError};
 use crate::error_messages::ERROR_DB_SETUP;
 use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::metrics::{self, Metric};
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
 
 #[cfg(feature = "dynamo")]
 use self::dynamodb as dynamodb_connector;
 #[cfg(feature = "mongo")]
 use self::mongodb as mongodb_connector;
 
+pub mod api_keys;
 pub mod bot;
+pub mod bot_labels;
+pub mod bot_limits;
 pub mod conversations;
+pub mod http_policies;
 pub mod interactions;
 pub mod memories;
 pub mod messages;
@@ -76,6 +82,8 @@ pub struct DbInteraction {
     pub client: Client,
     pub success: bool,
     pub event: serde_json::Value,
+    // version of the bot picked by a traffic split
+    pub version_id: Option<String>,
     pub updated_at: String,
     pub created_at: String,
 }
@@ -136,6 +144,14 @@ pub struct DbState {
     pub created_at: String,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DbApiKey {
+    pub id: String,
+    pub scopes: Vec<String>,
+    pub bot_ids: Option<Vec<String>>,
+    pub created_at: String,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DbBot {
     pub id: String,
@@ -158,6 +174,14 @@ pub struct BotVersionCreated {
     pub engine_version: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApiKeyCreated {
+    pub id: String,
+    pub key: String,
+    pub scopes: Vec<String>,
+    pub bot_ids: Option<Vec<String>>,
+}
+
 impl BotVersion {
     pub fn flatten(&self) -> serde_json::Value {
         let mut value = serde_json::json!(self.bot);
@@ -199,3 +223,64 @@ pub fn init_db() -> Result<Database, EngineError> {
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
+
+/**
+ * Check that the database can be reached
+ */
+pub fn check_db(db: &mut Database) -> Result<(), EngineError> {
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::ping(db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::ping(db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+pub const DB_OPERATION_DURATION: Metric = Metric {
+    name: "csml_db_operation_duration_seconds",
+    help: "Duration of the database operations, per connector",
+};
+
+/**
+ * Measure the duration of a database operation until the timer is dropped
+ */
+pub struct DbTimer {
+    connector: &'static str,
+    operation: &'static str,
+    start: Instant,
+    _span: tracing::span::EnteredSpan,
+}
+
+impl DbTimer {
+    pub fn start(operation: &'static str, db: &Database) -> Self {
+        let connector = match db {
+            #[cfg(feature = "mongo")]
+            Database::Mongo(_) => "mongodb",
+            #[cfg(feature = "dynamo")]
+            Database::Dynamodb(_) => "dynamodb",
+            Database::None => "none",
+        };
+
+        Self {
+            connector,
+            operation,
+            start: Instant::now(),
+            _span: tracing::debug_span!("db", connector, operation).entered(),
+        }
+    }
+}
+
+impl Drop for DbTimer {
+    fn drop(&mut self) {
+        let labels = [("connector", self.connector), ("operation", self.operation)];
+
+        metrics::observe_since(&DB_OPERATION_DURATION, &labels, self.start);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{db_connectors::DbApiKey, EngineError, MongoDbClient};
+use bson::{doc, Bson};
+use chrono::SecondsFormat;
+
+fn format_api_key_struct(api_key: bson::document::Document) -> Result<DbApiKey, EngineError> {
+    Ok(DbApiKey {
+        id: api_key.get_str("key_id").unwrap().to_owned(),
+        scopes: bson::from_bson(api_key.get("scopes").unwrap().to_owned())?,
+        bot_ids: match api_key.get("bot_ids") {
+            Some(Bson::Array(bot_ids)) => Some(bson::from_bson(Bson::Array(bot_ids.to_owned()))?),
+            _ => None,
+        },
+        created_at: api_key
+            .get_datetime("created_at")
+            .unwrap()
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    })
+}
+
+pub fn create_api_key(api_key: &DbApiKey, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("api_key");
+
+    let doc = doc! {
+        "key_id": &api_key.id,
+        "scopes": bson::to_bson(&api_key.scopes)?,
+        "bot_ids": bson::to_bson(&api_key.bot_ids)?,
+        "created_at": Bson::DateTime(chrono::Utc::now()),
+    };
+
+    collection.insert_one(doc, None)?;
+
+    Ok(())
+}
+
+pub fn get_api_key(id: &str, db: &MongoDbClient) -> Result<Option<DbApiKey>, EngineError> {
+    let collection = db.client.collection("api_key");
+
+    let filter = doc! {
+        "key_id": id,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(api_key) => Ok(Some(format_api_key_struct(api_key)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn delete_api_key(id: &str, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("api_key");
+
+    let filter = doc! {
+        "key_id": id,
+    };
+
+    collection.delete_one(filter, None)?;
+
+    Ok(())
+}
@@ -130,8 +130,14 @@ pub fn get_bot_by_version_id(
 ) -> Result<Option<BotVersion>, EngineError> {
     let collection = db.client.collection("bot");
 
+    // an id that is not an ObjectId can not match any version
+    let id = match bson::oid::ObjectId::with_string(id) {
+        Ok(id) => id,
+        Err(_) => return Ok(None),
+    };
+
     let filter = doc! {
-        "_id": bson::oid::ObjectId::with_string(id).unwrap()
+        "_id": id
     };
 
     let find_options = mongodb::options::FindOneOptions::builder()
@@ -0,0 +1,93 @@
+use crate::{data::BotLabel, EngineError, MongoDbClient};
+use bson::doc;
+use mongodb::options::{FindOptions, ReplaceOptions};
+
+pub fn set_bot_label(bot_id: &str, label: &BotLabel, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("bot_label");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+        "label": &label.label,
+    };
+    let doc = doc! {
+        "bot_id": bot_id,
+        "label": &label.label,
+        "version_id": &label.version_id,
+        "history": &label.history,
+        "updated_at": &label.updated_at,
+    };
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    collection.replace_one(filter, doc, options)?;
+
+    Ok(())
+}
+
+fn format_bot_label(doc: bson::document::Document) -> Result<BotLabel, EngineError> {
+    Ok(BotLabel {
+        label: doc.get_str("label").unwrap().to_owned(),
+        version_id: doc.get_str("version_id").unwrap().to_owned(),
+        history: bson::from_bson(doc.get("history").unwrap().to_owned())?,
+        updated_at: doc.get_str("updated_at").unwrap().to_owned(),
+    })
+}
+
+pub fn get_bot_label(
+    bot_id: &str,
+    label: &str,
+    db: &MongoDbClient,
+) -> Result<Option<BotLabel>, EngineError> {
+    let collection = db.client.collection("bot_label");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+        "label": label,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(doc) => Ok(Some(format_bot_label(doc)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn get_bot_labels(bot_id: &str, db: &MongoDbClient) -> Result<Vec<BotLabel>, EngineError> {
+    let collection = db.client.collection("bot_label");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+    let find_options = FindOptions::builder().sort(doc! { "label": 1 }).build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut labels = vec![];
+    for doc in cursor {
+        labels.push(format_bot_label(doc?)?);
+    }
+
+    Ok(labels)
+}
+
+pub fn delete_bot_label(bot_id: &str, label: &str, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("bot_label");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+        "label": label,
+    };
+
+    collection.delete_one(filter, None)?;
+
+    Ok(())
+}
+
+pub fn delete_bot_labels(bot_id: &str, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("bot_label");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+
+    collection.delete_many(filter, None)?;
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::{data::BotLimits, EngineError, MongoDbClient};
+use bson::{doc, Bson};
+use mongodb::options::{FindOneAndUpdateOptions, ReplaceOptions, ReturnDocument, UpdateOptions};
+
+pub fn set_bot_limits(bot_id: &str, limits: &BotLimits, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("bot_limits");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+    let doc = doc! {
+        "bot_id": bot_id,
+        "limits": bson::to_bson(limits)?,
+        "updated_at": Bson::DateTime(chrono::Utc::now()),
+    };
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    collection.replace_one(filter, doc, options)?;
+
+    Ok(())
+}
+
+pub fn get_bot_limits(bot_id: &str, db: &MongoDbClient) -> Result<Option<BotLimits>, EngineError> {
+    let collection = db.client.collection("bot_limits");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(doc) => {
+            let limits = doc.get("limits").unwrap().to_owned();
+
+            Ok(Some(bson::from_bson(limits)?))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn delete_bot_limits(bot_id: &str, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("bot_limits");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+
+    collection.delete_one(filter, None)?;
+
+    Ok(())
+}
+
+pub fn increment_bot_usage(
+    bot_id: &str,
+    day: &str,
+    max: u64,
+    db: &MongoDbClient,
+) -> Result<Option<u64>, EngineError> {
+    let collection = db.client.collection("bot_usage");
+
+    // create the usage of the day first, so that the increment below never has to insert it
+    let filter = doc! {
+        "bot_id": bot_id,
+        "day": day,
+    };
+    let insert = doc! {
+        "$setOnInsert": { "interactions": 0_i64 },
+    };
+    let options = UpdateOptions::builder().upsert(true).build();
+    collection.update_one(filter, insert, options)?;
+
+    let filter = doc! {
+        "bot_id": bot_id,
+        "day": day,
+        "interactions": { "$lt": max as i64 },
+    };
+    let update = doc! {
+        "$inc": { "interactions": 1_i64 },
+    };
+    let options = FindOneAndUpdateOptions::builder()
+        .return_document(ReturnDocument::After)
+        .build();
+
+    match collection.find_one_and_update(filter, update, options)? {
+        Some(usage) => Ok(Some(usage.get_i64("interactions").unwrap_or(1) as u64)),
+        None => Ok(None),
+    }
+}
@@ -1,6 +1,9 @@
-use crate::{db_connectors::DbConversation, Client, EngineError, MongoDbClient};
+use crate::{
+    db_connectors::{mongodb::get_bot_date_filter, DbConversation},
+    Client, EngineError, MongoDbClient,
+};
 use bson::{doc, Bson};
-use chrono::SecondsFormat;
+use chrono::{DateTime, SecondsFormat, Utc};
 
 fn format_conversation_struct(
     conversation: bson::document::Document,
@@ -236,3 +239,204 @@ pub fn get_client_conversations(
         false => Ok(serde_json::json!({ "conversations": conversations })),
     }
 }
+
+pub fn get_bot_conversations(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &MongoDbClient,
+) -> Result<Vec<DbConversation>, EngineError> {
+    let collection = db.client.collection("conversation");
+
+    let filter = get_bot_date_filter(bot_id, from, to);
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(100)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut conversations = vec![];
+    for doc in cursor {
+        conversations.push(format_conversation_struct(doc?)?);
+    }
+
+    Ok(conversations)
+}
+
+pub fn get_conversation(
+    client: &Client,
+    id: &str,
+    db: &MongoDbClient,
+) -> Result<Option<DbConversation>, EngineError> {
+    let collection = db.client.collection("conversation");
+
+    // an id that is not an ObjectId can not match any conversation
+    let id = match bson::oid::ObjectId::with_string(id) {
+        Ok(id) => id,
+        Err(_) => return Ok(None),
+    };
+
+    let filter = doc! {
+        "_id": id,
+        "client": bson::to_bson(&client)?,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(conv) => Ok(Some(format_conversation_struct(conv)?)),
+        None => Ok(None),
+    }
+}
+
+/**
+ * Open conversations are listed from the most recent one, the pagination key being
+ * the id of the last conversation of the previous page
+ */
+pub fn get_bot_open_conversations(
+    bot_id: &str,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    db: &MongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let collection = db.client.collection("conversation");
+
+    let limit = match limit {
+        Some(limit) if limit >= 1 => limit + 1,
+        Some(_limit) => 21,
+        None => 21,
+    };
+
+    let mut filter = doc! {
+        "client.bot_id": bot_id,
+        "status": "OPEN",
+    };
+    if let Some(key) = pagination_key {
+        let last_id = match bson::oid::ObjectId::with_string(&key) {
+            Ok(last_id) => last_id,
+            Err(_) => return Err(EngineError::Manager("Invalid pagination_key".to_owned())),
+        };
+
+        filter.insert("_id", doc! { "$lt": last_id });
+    }
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": -1 })
+        .batch_size(30)
+        .limit(limit)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut conversations = vec![];
+    for doc in cursor {
+        conversations.push(format_conversation_struct(doc?)?);
+    }
+
+    match conversations.len() == limit as usize {
+        true => {
+            conversations.pop();
+            match conversations.last() {
+                Some(last) => {
+                    let pagination_key = base64::encode(serde_json::json!(last.id).to_string());
+
+                    Ok(serde_json::json!({"conversations": conversations, "pagination_key": pagination_key}))
+                }
+                None => Ok(serde_json::json!({ "conversations": conversations })),
+            }
+        }
+        false => Ok(serde_json::json!({ "conversations": conversations })),
+    }
+}
+
+/**
+ * Clients are grouped from the conversations of the bot and sorted by channel then user,
+ * the pagination key being the last client of the previous page
+ */
+pub fn search_bot_clients(
+    bot_id: &str,
+    channel_id: Option<&str>,
+    user_id: Option<&str>,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+    db: &MongoDbClient,
+) -> Result<serde_json::Value, EngineError> {
+    let collection = db.client.collection("conversation");
+
+    let limit = match limit {
+        Some(limit) if limit >= 1 => limit + 1,
+        Some(_limit) => 21,
+        None => 21,
+    };
+
+    let mut filter = doc! { "client.bot_id": bot_id };
+    if let Some(channel_id) = channel_id {
+        filter.insert("client.channel_id", channel_id);
+    }
+    if let Some(user_id) = user_id {
+        filter.insert(
+            "client.user_id",
+            bson::Regex {
+                pattern: format!("^{}", escape_regex(user_id)),
+                options: String::new(),
+            },
+        );
+    }
+
+    let mut pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$group": { "_id": "$client" } },
+        doc! { "$sort": { "_id.channel_id": 1, "_id.user_id": 1 } },
+    ];
+
+    if let Some(key) = pagination_key {
+        let last: Client = match serde_json::from_str(&key) {
+            Ok(last) => last,
+            Err(_) => return Err(EngineError::Manager("Invalid pagination_key".to_owned())),
+        };
+
+        pipeline.push(doc! {
+            "$match": {
+                "$or": [
+                    { "_id.channel_id": { "$gt": &last.channel_id } },
+                    { "_id.channel_id": &last.channel_id, "_id.user_id": { "$gt": &last.user_id } },
+                ]
+            }
+        });
+    }
+    pipeline.push(doc! { "$limit": limit });
+
+    let cursor = collection.aggregate(pipeline, None)?;
+
+    let mut clients = vec![];
+    for doc in cursor {
+        let client: Client = bson::from_bson(doc?.get("_id").unwrap().to_owned())?;
+        clients.push(client);
+    }
+
+    match clients.len() == limit as usize {
+        true => {
+            clients.pop();
+            match clients.last() {
+                Some(last) => {
+                    let last = serde_json::json!(last).to_string();
+                    let pagination_key = base64::encode(serde_json::json!(last).to_string());
+
+                    Ok(serde_json::json!({"clients": clients, "pagination_key": pagination_key}))
+                }
+                None => Ok(serde_json::json!({ "clients": clients })),
+            }
+        }
+        false => Ok(serde_json::json!({ "clients": clients })),
+    }
+}
+
+fn escape_regex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+
+    for c in value.chars() {
+        if "\\^$.|?*+()[]{}".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
@@ -0,0 +1,51 @@
+use crate::{EngineError, MongoDbClient};
+use bson::{doc, Bson};
+use csml_interpreter::data::HttpPolicy;
+use mongodb::options::ReplaceOptions;
+
+pub fn set_http_policy(bot_id: &str, policy: &HttpPolicy, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("http_policies");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+    let doc = doc! {
+        "bot_id": bot_id,
+        "policy": bson::to_bson(policy)?,
+        "updated_at": Bson::DateTime(chrono::Utc::now()),
+    };
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    collection.replace_one(filter, doc, options)?;
+
+    Ok(())
+}
+
+pub fn get_http_policy(bot_id: &str, db: &MongoDbClient) -> Result<Option<HttpPolicy>, EngineError> {
+    let collection = db.client.collection("http_policies");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(doc) => {
+            let policy = doc.get("policy").unwrap().to_owned();
+
+            Ok(Some(bson::from_bson(policy)?))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn delete_http_policy(bot_id: &str, db: &MongoDbClient) -> Result<(), EngineError> {
+    let collection = db.client.collection("http_policies");
+
+    let filter = doc! {
+        "bot_id": bot_id,
+    };
+
+    collection.delete_one(filter, None)?;
+
+    Ok(())
+}
@@ -1,25 +1,59 @@
 use serde_json::Value;
 
 use crate::{
+    db_connectors::{mongodb::{field_error, get_bot_date_filter}, DbInteraction},
     Client, EngineError, MongoDbClient,
-    encrypt::encrypt_data
+    encrypt::{encrypt_data, decrypt_data}
 };
 use bson::{doc, Bson};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+fn format_interaction_struct(
+    interaction: bson::document::Document,
+) -> Result<DbInteraction, EngineError> {
+    let field = |key: &str| interaction.get(key).ok_or_else(|| field_error("interaction", key));
+    let date = |key: &str| {
+        interaction
+            .get_datetime(key)
+            .map(|date| date.to_rfc3339_opts(SecondsFormat::Millis, true))
+            .map_err(|_| field_error("interaction", key))
+    };
+
+    let encrypted_event: String = bson::from_bson(field("event")?.clone())?;
+
+    Ok(DbInteraction {
+        id: interaction
+            .get_object_id("_id")
+            .map_err(|_| field_error("interaction", "_id"))?
+            .to_hex(),
+        client: bson::from_bson(field("client")?.to_owned())?,
+        // success is only set once the interaction is over
+        success: interaction.get_bool("success").unwrap_or(false),
+        event: decrypt_data(encrypted_event)?,
+        version_id: interaction.get_str("version_id").ok().map(|id| id.to_owned()),
+        updated_at: date("updated_at")?,
+        created_at: date("created_at")?,
+    })
+}
 
 pub fn init_interaction(
     event: Value,
     client: &Client,
+    version_id: Option<&str>,
     db: &MongoDbClient,
 ) -> Result<String, EngineError> {
     let collection = db.client.collection("interaction");
     let time = Bson::DateTime(chrono::Utc::now());
 
-    let doc = doc! {
+    let mut doc = doc! {
         "client": bson::to_bson(&client)?,
         "event": encrypt_data(&event)?, // encrypted
         "updated_at": &time,
         "created_at": &time
     };
+    if let Some(version_id) = version_id {
+        doc.insert("version_id", version_id);
+    }
 
     let inserted = collection.insert_one(doc.clone(), None)?;
 
@@ -63,4 +97,50 @@ pub fn delete_user_interactions(client: &Client, db: &MongoDbClient) -> Result<(
     collection.delete_many(filter, None)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+pub fn get_bot_interactions(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+    db: &MongoDbClient,
+) -> Result<Vec<DbInteraction>, EngineError> {
+    let collection = db.client.collection("interaction");
+
+    let filter = get_bot_date_filter(bot_id, from, to);
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(100)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut interactions = vec![];
+    for doc in cursor {
+        interactions.push(format_interaction_struct(doc?)?);
+    }
+
+    Ok(interactions)
+}
+
+pub fn get_interaction(
+    client: &Client,
+    id: &str,
+    db: &MongoDbClient,
+) -> Result<Option<DbInteraction>, EngineError> {
+    let collection = db.client.collection("interaction");
+
+    // an id that is not an ObjectId can not match any interaction
+    let id = match bson::oid::ObjectId::with_string(id) {
+        Ok(id) => id,
+        Err(_) => return Ok(None),
+    };
+
+    let filter = doc! {
+        "_id": id,
+        "client": bson::to_bson(&client)?,
+    };
+
+    match collection.find_one(filter, None)? {
+        Some(interaction) => Ok(Some(format_interaction_struct(interaction)?)),
+        None => Ok(None),
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     MongoDbClient
 };
 use bson::{doc, Bson, Document};
+use chrono::SecondsFormat;
 
 fn format_messages(
     data: &ConversationInfo,
@@ -62,7 +63,10 @@ fn format_message_struct(
         direction: message.get_str("direction").unwrap().to_owned(),
         payload,
         content_type: message.get_str("content_type").unwrap().to_owned(),
-        created_at: message.get_str("created_at").unwrap().to_owned(),
+        created_at: message
+            .get_datetime("created_at")
+            .unwrap()
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
     })
 }
 
@@ -168,4 +172,30 @@ pub fn get_client_messages(
         }
         false => Ok(serde_json::json!({ "messages": messages })),
     }
-}
\ No newline at end of file
+}
+
+pub fn get_conversation_messages(
+    client: &Client,
+    conversation_id: &str,
+    db: &MongoDbClient,
+) -> Result<Vec<DbMessage>, EngineError> {
+    let collection = db.client.collection("message");
+
+    let filter = doc! {
+        "client": bson::to_bson(&client)?,
+        "conversation_id": conversation_id,
+    };
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(30)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut messages = vec![];
+    for doc in cursor {
+        messages.push(format_message_struct(doc?)?);
+    }
+
+    Ok(messages)
+}
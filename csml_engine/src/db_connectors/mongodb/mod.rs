@@ -1,5 +1,9 @@
+pub mod api_keys;
 pub mod bot;
+pub mod bot_labels;
+pub mod bot_limits;
 pub mod conversations;
+pub mod http_policies;
 pub mod interactions;
 pub mod memories;
 pub mod messages;
@@ -7,6 +11,8 @@ pub mod nodes;
 pub mod state;
 
 use crate::{Database, EngineError, MongoDbClient};
+use bson::{doc, Bson};
+use chrono::{DateTime, Utc};
 
 fn init_mongo_credentials() -> Option<mongodb::options::Credential> {
     let username = match std::env::var("MONGODB_USERNAME") {
@@ -73,6 +79,12 @@ pub fn get_db<'a>(db: &'a Database) -> Result<&'a MongoDbClient, EngineError> {
     }
 }
 
+pub fn ping(db: &MongoDbClient) -> Result<(), EngineError> {
+    db.client.run_command(doc! {"ping": 1}, None)?;
+
+    Ok(())
+}
+
 pub fn get_pagination_key(pagination_key: Option<String>) ->  Result<Option<String>, EngineError> {
     match pagination_key {
         Some(key) => {
@@ -90,4 +102,35 @@ pub fn get_pagination_key(pagination_key: Option<String>) ->  Result<Option<Stri
         },
         None => Ok(None)
     }
-}
\ No newline at end of file
+}
+/**
+ * Filter documents by bot and creation date, both bounds of the date range being optional
+ */
+pub fn get_bot_date_filter(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+) -> bson::Document {
+    let mut filter = doc! { "client.bot_id": bot_id };
+    let mut created_at = bson::Document::new();
+
+    if let Some(from) = from {
+        created_at.insert("$gte", Bson::DateTime(from));
+    }
+    if let Some(to) = to {
+        created_at.insert("$lt", Bson::DateTime(to));
+    }
+    if !created_at.is_empty() {
+        filter.insert("created_at", created_at);
+    }
+
+    filter
+}
+
+/**
+ * Error of a document whose field is missing or does not have the expected type,
+ * so that a single malformed document does not panic the request reading it
+ */
+pub fn field_error(collection: &str, key: &str) -> EngineError {
+    EngineError::Format(format!("invalid or missing field '{}' in a document of '{}'", key, collection))
+}
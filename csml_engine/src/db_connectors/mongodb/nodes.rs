@@ -1,5 +1,32 @@
-use crate::{db_connectors::mongodb::get_db, ConversationInfo, EngineError, Client, MongoDbClient};
+use crate::{
+    db_connectors::{mongodb::{field_error, get_bot_date_filter, get_db}, DbNode},
+    ConversationInfo, EngineError, Client, MongoDbClient
+};
 use bson::{doc, Bson};
+use chrono::{DateTime, SecondsFormat, Utc};
+
+fn format_node_struct(node: bson::document::Document) -> Result<DbNode, EngineError> {
+    let string = |key: &str| {
+        node.get_str(key)
+            .map(|value| value.to_owned())
+            .map_err(|_| field_error("path", key))
+    };
+
+    Ok(DbNode {
+        id: node.get_object_id("_id").map_err(|_| field_error("path", "_id"))?.to_hex(),
+        client: bson::from_bson(node.get("client").ok_or_else(|| field_error("path", "client"))?.to_owned())?,
+        interaction_id: string("interaction_id")?,
+        conversation_id: string("conversation_id")?,
+        flow_id: string("flow_id")?,
+        step_id: string("step_id")?,
+        next_step: node.get_str("next_step").ok().map(|step| step.to_owned()),
+        next_flow: node.get_str("next_flow").ok().map(|flow| flow.to_owned()),
+        created_at: node
+            .get_datetime("created_at")
+            .map_err(|_| field_error("path", "created_at"))?
+            .to_rfc3339_opts(SecondsFormat::Millis, true),
+    })
+}
 
 pub fn create_node(
     data: &mut ConversationInfo,
@@ -47,4 +74,56 @@ pub fn delete_conversation_nodes(client: &Client, db: &MongoDbClient) -> Result<
     collection.delete_many(filter, None)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+pub fn get_conversation_nodes(
+    client: &Client,
+    conversation_id: &str,
+    db: &MongoDbClient,
+) -> Result<Vec<DbNode>, EngineError> {
+    let collection = db.client.collection("path");
+
+    let filter = doc! {
+        "client": bson::to_bson(&client)?,
+        "conversation_id": conversation_id,
+    };
+
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(30)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut nodes = vec![];
+    for doc in cursor {
+        nodes.push(format_node_struct(doc?)?);
+    }
+
+    Ok(nodes)
+}
+
+/**
+ * Retrieve the nodes of every conversation of a bot created since a given date,
+ * in the order they were created
+ */
+pub fn get_bot_nodes(
+    bot_id: &str,
+    from: Option<DateTime<Utc>>,
+    db: &MongoDbClient,
+) -> Result<Vec<DbNode>, EngineError> {
+    let collection = db.client.collection("path");
+
+    let filter = get_bot_date_filter(bot_id, from, None);
+    let find_options = mongodb::options::FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .batch_size(100)
+        .build();
+    let cursor = collection.find(filter, find_options)?;
+
+    let mut nodes = vec![];
+    for doc in cursor {
+        nodes.push(format_node_struct(doc?)?);
+    }
+
+    Ok(nodes)
+}
@@ -129,4 +129,76 @@ pub fn delete_user_state(client: &Client, db: &MongoDbClient) -> Result<(), Engi
     collection.delete_many(filter, None)?;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn is_duplicate_key(err: &mongodb::error::Error) -> bool {
+    use mongodb::error::{ErrorKind, WriteFailure};
+
+    match err.kind.as_ref() {
+        ErrorKind::WriteError(WriteFailure::WriteError(err)) => err.code == 11000,
+        ErrorKind::CommandError(err) => err.code == 11000,
+        _ => false,
+    }
+}
+
+/**
+ * The lease of a client is a single document whose _id is made of the client, type and key.
+ * _id is always unique, so two requests can not both create it: the upsert either takes over
+ * an expired lease, creates the missing one, or fails with a duplicate key if the lease is held.
+ */
+pub fn acquire_state_lease(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    owner: &str,
+    ttl: i64,
+    db: &MongoDbClient,
+) -> Result<bool, EngineError> {
+    let state = db.client.collection("state");
+    let client = bson::to_bson(client)?;
+
+    let now = chrono::Utc::now();
+    let expires_at = Bson::DateTime(now + chrono::Duration::milliseconds(ttl));
+    let lease = doc! {
+        "client": client.clone(),
+        "type": _type,
+        "key": key,
+        "value": owner,
+        "expires_at": expires_at,
+        "created_at": Bson::DateTime(now)
+    };
+
+    let filter = doc! {
+        "_id": { "client": client, "type": _type, "key": key },
+        "expires_at": { "$lt": Bson::DateTime(now) },
+    };
+    let options = mongodb::options::UpdateOptions::builder()
+        .upsert(true)
+        .build();
+
+    match state.update_one(filter, doc! { "$set": lease }, options) {
+        Ok(_) => Ok(true),
+        Err(err) if is_duplicate_key(&err) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+pub fn release_state_lease(
+    client: &Client,
+    _type: &str,
+    key: &str,
+    owner: &str,
+    db: &MongoDbClient,
+) -> Result<(), EngineError> {
+    let state = db.client.collection("state");
+
+    let filter = doc! {
+        "client": bson::to_bson(client)?,
+        "type": _type,
+        "key": key,
+        "value": owner,
+    };
+    state.delete_one(filter, None)?;
+
+    Ok(())
+}
@@ -2,14 +2,21 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
-use crate::{ConversationInfo, EngineError};
+use crate::db_connectors::{DbConversation, DbNode};
+use crate::{Client, ConversationInfo, Database, EngineError};
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 
 pub fn create_node(
     conversation: &mut ConversationInfo,
     nextflow: Option<String>,
     nextstep: Option<String>,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("nodes::create_node", &conversation.db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         return mongodb_connector::nodes::create_node(conversation, nextflow, nextstep);
@@ -22,3 +29,77 @@ pub fn create_node(
 
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
+
+/**
+ * Retrieve the path (every flow/step transition) of a given conversation,
+ * in the order it was taken
+ */
+pub fn get_conversation_nodes(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<Vec<DbNode>, EngineError> {
+    let _timer = DbTimer::start("nodes::get_conversation_nodes", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::nodes::get_conversation_nodes(client, conversation_id, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::nodes::get_conversation_nodes(client, conversation_id, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Retrieve the paths of conversations of a bot created since a given date, by conversation id,
+ * each in the order it was taken. Nodes of other conversations are left out.
+ */
+pub fn get_bot_nodes(
+    bot_id: &str,
+    conversations: &[DbConversation],
+    from: Option<DateTime<Utc>>,
+    db: &mut Database,
+) -> Result<HashMap<String, Vec<DbNode>>, EngineError> {
+    let _timer = DbTimer::start("nodes::get_bot_nodes", db);
+
+    let mut paths: HashMap<String, Vec<DbNode>> = conversations
+        .iter()
+        .map(|conversation| (conversation.id.to_owned(), vec![]))
+        .collect();
+
+    let nodes = get_nodes(bot_id, conversations, from, db)?;
+    for node in nodes.into_iter() {
+        if let Some(path) = paths.get_mut(&node.conversation_id) {
+            path.push(node);
+        }
+    }
+
+    Ok(paths)
+}
+
+fn get_nodes(
+    _bot_id: &str,
+    _conversations: &[DbConversation],
+    _from: Option<DateTime<Utc>>,
+    db: &mut Database,
+) -> Result<Vec<DbNode>, EngineError> {
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::nodes::get_bot_nodes(_bot_id, _from, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::nodes::get_bot_nodes(_conversations, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
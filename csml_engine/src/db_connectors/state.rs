@@ -2,6 +2,7 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Database, EngineError};
 use csml_interpreter::data::Client;
@@ -12,6 +13,8 @@ pub fn delete_state_key(
     _key: &str,
     db: &mut Database,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("state::delete_state_key", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -33,6 +36,8 @@ pub fn get_state_key(
     _key: &str,
     db: &mut Database,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _timer = DbTimer::start("state::get_state_key", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -52,6 +57,8 @@ pub fn get_current_state(
     client: &Client,
     db: &mut Database,
 ) -> Result<Option<serde_json::Value>, EngineError> {
+    let _timer = DbTimer::start("state::get_current_state", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -73,6 +80,8 @@ pub fn set_state_items(
     keys_values: Vec<(&str, &serde_json::Value)>,
     db: &mut Database,
 ) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("state::set_state_items", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -88,6 +97,64 @@ pub fn set_state_items(
     Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
 }
 
+/**
+ * Try to take a lease on a given state key, for `ttl` milliseconds.
+ * The lease is granted if the key is free or if the previous lease has expired.
+ * Returns `false` if the key is currently held by another owner.
+ */
+pub fn acquire_state_lease(
+    client: &Client,
+    _type: &str,
+    _key: &str,
+    owner: &str,
+    ttl: i64,
+    db: &mut Database,
+) -> Result<bool, EngineError> {
+    let _timer = DbTimer::start("state::acquire_state_lease", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::state::acquire_state_lease(client, _type, _key, owner, ttl, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::state::acquire_state_lease(client, _type, _key, owner, ttl, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
+/**
+ * Release a lease previously taken with `acquire_state_lease`.
+ * Nothing happens if the lease is now held by another owner.
+ */
+pub fn release_state_lease(
+    client: &Client,
+    _type: &str,
+    _key: &str,
+    owner: &str,
+    db: &mut Database,
+) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("state::release_state_lease", db);
+
+    #[cfg(feature = "mongo")]
+    if is_mongodb() {
+        let db = mongodb_connector::get_db(db)?;
+        return mongodb_connector::state::release_state_lease(client, _type, _key, owner, db);
+    }
+
+    #[cfg(feature = "dynamo")]
+    if is_dynamodb() {
+        let db = dynamodb_connector::get_db(db)?;
+        return dynamodb_connector::state::release_state_lease(client, _type, _key, owner, db);
+    }
+
+    Err(EngineError::Manager(ERROR_DB_SETUP.to_owned()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,4 +214,27 @@ mod tests {
             None => {}
         }
     }
+
+    #[test]
+    fn ok_lease() {
+        let client = Client {
+            bot_id: "bot_id".to_owned(),
+            channel_id: "channel_id".to_owned(),
+            user_id: "test_lease".to_owned(),
+        };
+        let mut db = init_db().unwrap();
+
+        release_state_lease(&client, "lock", "request", "first", &mut db).unwrap();
+
+        assert!(acquire_state_lease(&client, "lock", "request", "first", 10_000, &mut db).unwrap());
+        assert!(!acquire_state_lease(&client, "lock", "request", "second", 10_000, &mut db).unwrap());
+
+        // releasing a lease we do not own must not free it
+        release_state_lease(&client, "lock", "request", "second", &mut db).unwrap();
+        assert!(!acquire_state_lease(&client, "lock", "request", "second", 10_000, &mut db).unwrap());
+
+        release_state_lease(&client, "lock", "request", "first", &mut db).unwrap();
+        assert!(acquire_state_lease(&client, "lock", "request", "second", 10_000, &mut db).unwrap());
+        release_state_lease(&client, "lock", "request", "second", &mut db).unwrap();
+    }
 }
@@ -2,10 +2,13 @@
 use crate::db_connectors::{dynamodb as dynamodb_connector, is_dynamodb};
 #[cfg(feature = "mongo")]
 use crate::db_connectors::{is_mongodb, mongodb as mongodb_connector};
+use crate::db_connectors::DbTimer;
 use crate::error_messages::ERROR_DB_SETUP;
 use crate::{Client, Database, EngineError};
 
 pub fn delete_client(client: &Client, db: &mut Database) -> Result<(), EngineError> {
+    let _timer = DbTimer::start("user::delete_client", db);
+
     #[cfg(feature = "mongo")]
     if is_mongodb() {
         let db = mongodb_connector::get_db(db)?;
@@ -0,0 +1,169 @@
+use crate::data::{ConversationInfo, CsmlRequest, Database, EngineError};
+use crate::db_connectors::{conversations::*, interactions::*, messages::*, state::*};
+use crate::init::init_context;
+use crate::send::send_to_handoff_sink;
+use crate::utils::messages_formater;
+
+use chrono::{prelude::Utc, SecondsFormat};
+use csml_interpreter::data::Client;
+use serde_json::{json, map::Map, Value};
+
+/**
+ * Return the current handoff of a client, if the bot was paused to let a human take over.
+ */
+pub fn get_handoff(client: &Client, db: &mut Database) -> Result<Option<Value>, EngineError> {
+    get_state_key(client, "handoff", "status", db)
+}
+
+/**
+ * Pause the bot for the current client of a conversation.
+ * Until the handoff is ended, incoming messages are saved and forwarded to the handoff sink
+ * without being interpreted.
+ */
+pub fn start_handoff(data: &mut ConversationInfo, reason: Value) -> Result<(), EngineError> {
+    let handoff = json!({
+        "reason": reason,
+        "conversation_id": data.conversation_id,
+        "flow": data.context.flow,
+        "step": data.context.step,
+        "started_at": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+    });
+
+    set_state_items(&data.client, "handoff", vec![("status", &handoff)], &mut data.db)?;
+
+    send_to_handoff_sink(&json!({
+        "type": "handoff_start",
+        "client": data.client,
+        "handoff": handoff,
+    }));
+
+    Ok(())
+}
+
+/**
+ * Start a handoff for the conversation that is currently open for a client
+ */
+pub fn start_client_handoff(
+    client: &Client,
+    reason: Value,
+    mut db: Database,
+) -> Result<(), EngineError> {
+    let conversation = match get_latest_open(client, &mut db)? {
+        Some(conversation) => conversation,
+        None => {
+            return Err(EngineError::Manager(
+                "no open conversation to hand off for this client".to_owned(),
+            ))
+        }
+    };
+
+    let position = json!({
+        "conversation_id": conversation.id,
+        "flow": conversation.flow_id,
+        "step": conversation.step_id,
+    });
+    let mut data = init_handoff_info(client, &position, String::new(), db);
+
+    start_handoff(&mut data, reason)
+}
+
+/**
+ * Save a message received during a handoff in the conversation that was handed off,
+ * and forward it to the handoff sink instead of interpreting it.
+ */
+pub fn forward_to_handoff(
+    request: &CsmlRequest,
+    handoff: Value,
+    mut db: Database,
+) -> Result<Map<String, Value>, EngineError> {
+    let interaction_id = init_interaction(request.payload.clone(), &request.client, None, &mut db)?;
+
+    let mut data = init_handoff_info(&request.client, &handoff, interaction_id, db);
+    data.request_id = request.request_id.to_owned();
+    data.metadata = request.metadata.to_owned();
+
+    add_messages_bulk(&mut data, vec![request.payload.to_owned()], 0, "RECEIVE")?;
+
+    send_to_handoff_sink(&json!({
+        "type": "handoff_message",
+        "client": request.client,
+        "request_id": request.request_id,
+        "payload": request.payload,
+        "metadata": request.metadata,
+        "handoff": handoff,
+    }));
+
+    update_interaction(&mut data, true)?;
+
+    let mut response = messages_formater(&mut data, vec![], 0, false);
+    response.insert("handoff".to_owned(), json!(true));
+
+    Ok(response)
+}
+
+/**
+ * End the handoff of a client and give the conversation back to the bot.
+ * If a flow or a step is given, the conversation resumes at this position for the next
+ * incoming message. Otherwise it resumes where it was when the handoff started.
+ */
+pub fn end_handoff(
+    client: &Client,
+    flow: Option<String>,
+    step: Option<String>,
+    mut db: Database,
+) -> Result<(), EngineError> {
+    let handoff = match get_handoff(client, &mut db)? {
+        Some(handoff) => handoff,
+        None => return Ok(()),
+    };
+
+    delete_state_key(client, "handoff", "status", &mut db)?;
+
+    let mut data = init_handoff_info(client, &handoff, String::new(), db);
+
+    if flow.is_some() || step.is_some() {
+        let step = step.unwrap_or_else(|| "start".to_owned());
+
+        // the previous hold position is not relevant anymore
+        delete_state_key(client, "hold", "position", &mut data.db)?;
+        update_conversation(&mut data, flow, Some(step))?;
+    }
+
+    send_to_handoff_sink(&json!({
+        "type": "handoff_end",
+        "client": client,
+        "handoff": handoff,
+    }));
+
+    Ok(())
+}
+
+fn init_handoff_info(
+    client: &Client,
+    handoff: &Value,
+    interaction_id: String,
+    db: Database,
+) -> ConversationInfo {
+    let mut context = init_context(
+        handoff["flow"].as_str().unwrap_or_default().to_owned(),
+        client.to_owned(),
+        &None,
+    );
+    context.step = handoff["step"].as_str().unwrap_or_default().to_owned();
+
+    ConversationInfo {
+        request_id: String::new(),
+        curl: None,
+        conversation_id: handoff["conversation_id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_owned(),
+        interaction_id,
+        client: client.to_owned(),
+        context,
+        metadata: Value::Null,
+        messages: vec![],
+        stream: None,
+        db,
+    }
+}
@@ -0,0 +1,59 @@
+/**
+ * Outbound HTTP policy of a bot, enforced by the interpreter on every HTTP() and App() request.
+ * Without any configuration, the default policy only blocks private addresses.
+ */
+use crate::data::{Database, EngineError, HTTP_POLICY, HTTP_POLICY_DB};
+use crate::db_connectors::http_policies;
+
+use csml_interpreter::data::HttpPolicy;
+use std::env;
+
+pub fn validate_http_policy(policy: &HttpPolicy) -> Result<(), EngineError> {
+    let hosts = policy.allow_hosts.iter().flatten().chain(policy.deny_hosts.iter());
+
+    for host in hosts {
+        let domain = host.strip_prefix("*.").unwrap_or(host);
+
+        if domain.is_empty() || domain.contains(|c: char| c == '*' || c == '/' || c.is_whitespace()) {
+            return Err(EngineError::Format(format!(
+                "invalid host '{}': hosts must be like 'api.example.com' or '*.example.com'",
+                host
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Default policy of every bot, set with ENGINE_HTTP_POLICY
+ */
+fn get_default_http_policy() -> Result<HttpPolicy, EngineError> {
+    match env::var(HTTP_POLICY) {
+        Ok(value) if !value.trim().is_empty() => {
+            let policy: HttpPolicy = serde_json::from_str(&value).map_err(|err| {
+                EngineError::Manager(format!("invalid {}: {}", HTTP_POLICY, err))
+            })?;
+            validate_http_policy(&policy)?;
+
+            Ok(policy)
+        }
+        _ => Ok(HttpPolicy::default()),
+    }
+}
+
+/**
+ * Policy set for the bot with POST /bots/{bot_id}/http_policy if ENGINE_HTTP_POLICY_DB is enabled,
+ * and the default policy otherwise
+ */
+pub fn get_bot_http_policy(bot_id: &str, db: &mut Database) -> Result<HttpPolicy, EngineError> {
+    if let Ok(var) = env::var(HTTP_POLICY_DB) {
+        if var == "true" {
+            if let Some(policy) = http_policies::get_http_policy(bot_id, db)? {
+                return Ok(policy);
+            }
+        }
+    }
+
+    get_default_http_policy()
+}
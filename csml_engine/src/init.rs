@@ -1,4 +1,5 @@
 use crate::db_connectors::{conversations::*, interactions::*, memories::*};
+use crate::http_policy::get_bot_http_policy;
 use crate::{
     data::{ConversationInfo, CsmlRequest, Database, EngineError},
     utils::{get_default_flow, get_flow_by_id, search_flow},
@@ -17,6 +18,7 @@ use curl::{
     Error as CurlError,
 };
 use std::collections::HashMap;
+use std::sync::{atomic::AtomicUsize, Arc};
 
 /**
  * Initialize a new ConversationInfo data, usually upon new chat request.
@@ -28,21 +30,31 @@ use std::collections::HashMap;
  * - the current status of the request (steps, messages, variables, context...)
  * - the DB to use for data persistence
  * - the cached Curl connexion to the configured callback_url, if any
+ * - the channel where messages are streamed as they are processed, if any
  *
  * This method takes care of the initialization of the data as well as setting up
  * some information in the database (conversation_id, metadata, state...).
+ * `version_id` is the version picked by a traffic split, if any: it is saved
+ * with the interaction to compare the variants of the split.
  */
 pub fn init_conversation_info<'a>(
     default_flow: String,
     event: &Event,
     request: &'a CsmlRequest,
     bot: &'a CsmlBot,
+    version_id: Option<String>,
     mut db: Database,
 ) -> Result<ConversationInfo, EngineError> {
     // Create a new interaction. An interaction is basically each request,
     // initiated from the bot or the user.
-    let interaction_id = init_interaction(request.payload.clone(), &request.client, &mut db)?;
+    let interaction_id = init_interaction(
+        request.payload.clone(),
+        &request.client,
+        version_id.as_deref(),
+        &mut db,
+    )?;
     let mut context = init_context(default_flow, request.client.clone(), &bot.fn_endpoint);
+    context.http_policy = Some(get_bot_http_policy(&bot.id, &mut db)?);
 
     // Create and cache a curl agent to call the callback_url for every new message.
     // If no callback_url is set, no message will be sent as they are processed and
@@ -85,6 +97,7 @@ pub fn init_conversation_info<'a>(
         curl,
         client: request.client.clone(),
         messages: vec![],
+        stream: None,
         db,
     };
 
@@ -150,6 +163,10 @@ pub fn init_context(flow: String, client: Client, fn_endpoint: &Option<String>)
         hold: None,
         step: "start".to_owned(),
         flow,
+        http_policy: None,
+        http_requests: Arc::new(AtomicUsize::new(0)),
+        http_transport: None,
+        now: None,
     }
 }
 
@@ -0,0 +1,104 @@
+use crate::data::{Database, EngineError};
+use crate::db_connectors::{conversations, interactions, messages, nodes, state};
+
+use csml_interpreter::data::Client;
+use serde_json::{json, Value};
+
+/**
+ * Gather everything that was saved for a conversation: the conversation itself,
+ * its path (nodes), the interactions it went through and all its messages.
+ * Interactions are not linked to a conversation in the database, so they are found
+ * from the interaction ids of the messages and nodes of the conversation.
+ */
+pub fn get_conversation_details(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<Option<Value>, EngineError> {
+    let conversation = match conversations::get_conversation(client, conversation_id, db)? {
+        Some(conversation) => conversation,
+        None => return Ok(None),
+    };
+
+    let messages = messages::get_conversation_messages(client, conversation_id, db)?;
+    let nodes = nodes::get_conversation_nodes(client, conversation_id, db)?;
+
+    let mut interaction_ids: Vec<&str> = vec![];
+    let ids = messages
+        .iter()
+        .map(|message| message.interaction_id.as_str())
+        .chain(nodes.iter().map(|node| node.interaction_id.as_str()));
+    for id in ids {
+        if !interaction_ids.contains(&id) {
+            interaction_ids.push(id);
+        }
+    }
+
+    let mut interactions = vec![];
+    for id in interaction_ids {
+        if let Some(interaction) = interactions::get_interaction(client, id, db)? {
+            interactions.push(interaction);
+        }
+    }
+    interactions.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+    Ok(Some(json!({
+        "conversation": conversation,
+        "interactions": interactions,
+        "nodes": nodes,
+        "messages": messages,
+    })))
+}
+
+/**
+ * Close a conversation of a client, whatever the flow and step it is in.
+ * As in user_close_all_conversations, the hold position of the client is deleted so that
+ * it is not resumed in its next conversation.
+ * Return false if the client has no conversation with this id.
+ */
+pub fn close_conversation(
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<bool, EngineError> {
+    match conversations::get_conversation(client, conversation_id, db)? {
+        Some(conversation) if conversation.status == "OPEN" => {
+            state::delete_state_key(client, "hold", "position", db)?;
+            conversations::close_conversation(conversation_id, client, db)?;
+            Ok(true)
+        }
+        Some(_) => Ok(true),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db_connectors::init_db;
+
+    #[test]
+    fn ok_close_conversation_on_hold() {
+        let client = Client {
+            bot_id: "bot_id".to_owned(),
+            channel_id: "channel_id".to_owned(),
+            user_id: "test_close".to_owned(),
+        };
+        let mut db = init_db().unwrap();
+
+        let conversation_id =
+            conversations::create_conversation("Default", "start", &client, &mut db).unwrap();
+        let hold = json!({"index": {"command_index": 0, "loop_index": []}, "step_vars": {}, "hash": "hash"});
+        state::set_state_items(&client, "hold", vec![("position", &hold)], &mut db).unwrap();
+
+        assert!(close_conversation(&client, &conversation_id, &mut db).unwrap());
+        assert!(state::get_state_key(&client, "hold", "position", &mut db)
+            .unwrap()
+            .is_none());
+
+        let conversation = conversations::get_conversation(&client, &conversation_id, &mut db)
+            .unwrap()
+            .unwrap();
+        assert_eq!(conversation.status, "CLOSED");
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{data::*, delete_client_memories};
+use crate::handoff::start_handoff;
 use crate::db_connectors::{
     conversations::*, interactions::*, memories::*, messages::*, nodes::*, state::*,
 };
@@ -7,10 +8,21 @@ use crate::utils::*;
 use csml_interpreter::{
     data::{ast::ForgetMemory, csml_bot::CsmlBot, csml_flow::CsmlFlow, Event, Hold, MSG},
     interpret,
+    metrics::{self, Metric},
 };
 use std::collections::HashMap;
 use serde_json::{map::Map, Value};
-use std::{env, sync::mpsc, thread, time::SystemTime};
+use std::{sync::mpsc, thread, time::Instant};
+
+pub const INTERPRET_DURATION: Metric = Metric {
+    name: "csml_interpret_duration_seconds",
+    help: "Duration of the interpretation of an event, until a hold or the end of the conversation",
+};
+
+pub const STEPS_EXECUTED: Metric = Metric {
+    name: "csml_steps_executed_total",
+    help: "Number of steps executed",
+};
 
 /**
  * This is the CSML Engine action.
@@ -28,10 +40,21 @@ pub fn interpret_step(
     let mut interaction_success = true;
     let (sender, receiver) = mpsc::channel::<MSG>();
     let context = data.context.clone();
-    let interpret_step = SystemTime::now();
+    let start = Instant::now();
+
+    let span = tracing::info_span!(
+        "interaction",
+        conversation_id = %data.conversation_id,
+        interaction_id = %data.interaction_id,
+        flow = %data.context.flow,
+        step = %data.context.step,
+    );
+    let _enter = span.enter();
 
     let new_bot = bot.clone();
+    let interpreter_span = span.clone();
     thread::spawn(move || {
+        let _enter = interpreter_span.enter();
         interpret(new_bot, context, event, Some(sender));
     });
 
@@ -126,6 +149,7 @@ pub fn interpret_step(
                     }
                 }
             },
+            MSG::Handoff { reason } => start_handoff(data, reason)?,
             MSG::Error(err_msg) => {
                 conversation_end = true;
                 interaction_success = false;
@@ -136,19 +160,9 @@ pub fn interpret_step(
         }
     }
 
-    if let Ok(var) = env::var(DEBUG) {
-        if var == "true" {
-            let el = interpret_step.elapsed()?;
-            eprintln!(
-                "Total Time interpret step {} - {}.{}",
-                data.context.step,
-                el.as_secs(),
-                el.as_millis()
-            );
-        }
-    }
+    metrics::observe_since(&INTERPRET_DURATION, &[], start);
+    metrics::inc_counter(&STEPS_EXECUTED, &[], (interaction_order + 1) as f64);
 
-    let now = SystemTime::now();
     // save in db
     let msgs: Vec<serde_json::Value> = data
         .messages
@@ -159,17 +173,6 @@ pub fn interpret_step(
     add_messages_bulk(data, msgs, interaction_order, "SEND")?;
     add_memories(data, &memories)?;
 
-    if let Ok(var) = env::var(DEBUG) {
-        if var == "true" {
-            let el = now.elapsed()?;
-            eprintln!(
-                "Save message & memories bulk at the end of step - {}.{}",
-                el.as_secs(),
-                el.as_millis()
-            );
-        }
-    }
-
     update_interaction(data, interaction_success)?;
 
     Ok(messages_formater(
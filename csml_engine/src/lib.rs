@@ -5,7 +5,7 @@ pub use csml_interpreter::{
         ast::{Expr, Flow, InstructionScope},
         error_info::ErrorInfo,
         warnings::Warnings,
-        Client, CsmlResult,
+        Client, CsmlResult, HttpPolicy,
     }
 };
 use serde_json::json;
@@ -13,25 +13,41 @@ use serde_json::json;
 mod db_connectors;
 mod error_messages;
 
+mod analytics;
+mod api_keys;
+mod batching;
+mod bot_diff;
+mod bot_labels;
+mod bot_limits;
+mod bot_package;
 mod encrypt;
+mod handoff;
+mod http_policy;
 mod init;
+mod inspect;
 mod interpreter_actions;
+mod replay;
+mod request_cache;
 mod send;
+mod traffic_split;
 mod utils;
 
+use batching::*;
 use data::*;
 use db_connectors::{
-    bot, memories, user, messages, conversations, init_db, state, BotVersion, BotVersionCreated,
-    DbConversation,
+    bot, memories, user, messages, conversations, init_db, state, ApiKeyCreated, BotVersion,
+    BotVersionCreated, DbApiKey, DbConversation,
 };
 use init::*;
 use interpreter_actions::interpret_step;
+use request_cache::*;
 use utils::*;
 
 use csml_interpreter::data::{
     csml_bot::CsmlBot, csml_flow::CsmlFlow, Context, Hold, IndexInfo, Memory,
 };
-use std::{collections::HashMap, env, time::SystemTime};
+use chrono::Utc;
+use std::{collections::HashMap, env, sync::mpsc};
 
 /**
  * Initiate a CSML chat request.
@@ -45,17 +61,101 @@ use std::{collections::HashMap, env, time::SystemTime};
  * - bot_id: differentiate bots handled by the same CSML engine instance
  * - channel_id: a given bot may be used on different channels (messenger, slack...)
  * - user_id: differentiate users on the same communication channel
+ *
+ * Requests of a same client are processed one at a time. If DEBOUNCE_WINDOW_MS is set,
+ * rapid consecutive text events are merged into a single event before being interpreted.
+ * If IDEMPOTENCY_WINDOW_MS is set, duplicate request_ids get the response of the first request.
  */
 pub fn start_conversation(
     request: CsmlRequest,
     bot_opt: BotOpt,
 ) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
-    let now = SystemTime::now();
+    run_conversation(request, bot_opt, None)
+}
+
+/**
+ * Same as start_conversation, but every message is also sent to the given channel
+ * as soon as it is processed, formatted as for a callback_url.
+ * The messages of cached, debounced or handed off requests are only part of the returned value.
+ */
+pub fn start_conversation_stream(
+    request: CsmlRequest,
+    bot_opt: BotOpt,
+    stream: mpsc::Sender<serde_json::Value>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    run_conversation(request, bot_opt, Some(stream))
+}
+
+fn run_conversation(
+    mut request: CsmlRequest,
+    bot_opt: BotOpt,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request.request_id,
+        bot_id = %request.client.bot_id,
+        channel_id = %request.client.channel_id,
+        user_id = %request.client.user_id,
+    );
+    let _enter = span.enter();
+
+    let mut lock_db = init_db()?;
+    if let Some(response) = get_cached_response(&request, &mut lock_db)? {
+        return Ok(response);
+    }
+
+    if !debounce_event(&mut request, &mut lock_db)? {
+        return Ok(debounced_response(&request));
+    }
+
+    let owner = lock_client(&request.client, &mut lock_db)?;
+    let res = handle_request(&request, bot_opt, stream, &mut lock_db);
+    // the lock expires anyway, the result of the request matters more
+    if let Err(err) = unlock_client(&request.client, &owner, &mut lock_db) {
+        tracing::error!(error = ?err, "failed to release the client lock");
+    }
+
+    if let Err(err) = &res {
+        tracing::error!(error = ?err, "request failed");
+    }
+    res
+}
+
+/**
+ * A duplicate request may have been processed while we were waiting for the client's lock
+ */
+fn handle_request(
+    request: &CsmlRequest,
+    bot_opt: BotOpt,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
+    db: &mut Database,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
+    if let Some(response) = get_cached_response(request, db)? {
+        return Ok(response);
+    }
+
+    let response = process_request(request, bot_opt, stream)?;
+    cache_response(request, &response, db)?;
+
+    Ok(response)
+}
 
+fn process_request(
+    request: &CsmlRequest,
+    bot_opt: BotOpt,
+    stream: Option<mpsc::Sender<serde_json::Value>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, EngineError> {
     let formatted_event = format_event(json!(request))?;
     let mut db = init_db()?;
 
-    let mut bot = bot_opt.search_bot(&mut db);
+    // a human agent took over the conversation: the bot is paused
+    if let Some(handoff) = handoff::get_handoff(&request.client, &mut db)? {
+        return handoff::forward_to_handoff(request, handoff, db);
+    }
+
+    let (bot_opt, version_id) = traffic_split::resolve_split(bot_opt, &request.client, &mut db)?;
+    let mut bot = bot_opt.search_bot(&mut db)?;
     init_bot(&mut bot)?;
 
     let mut data = init_conversation_info(
@@ -63,8 +163,10 @@ pub fn start_conversation(
         &formatted_event,
         &request,
         &bot,
+        version_id,
         db,
     )?;
+    data.stream = stream;
 
     // save event in db as message RECEIVE
     let msgs = vec![request.payload.to_owned()];
@@ -72,15 +174,88 @@ pub fn start_conversation(
 
     check_for_hold(&mut data, &bot)?;
 
-    let res = interpret_step(&mut data, formatted_event.to_owned(), &bot);
+    interpret_step(&mut data, formatted_event.to_owned(), &bot)
+}
 
-    if let Ok(var) = env::var(DEBUG) {
-        if var == "true" {
-            let el = now.elapsed()?;
-            println!("Total time Manager - {}.{}", el.as_secs(), el.as_millis());
-        }
-    }
-    res
+/**
+ * Replay the events received in a past conversation against a given bot version,
+ * to find out how this version would have handled them.
+ *
+ * For each interaction, the recorded path (nodes) and messages are compared with the ones
+ * produced by the replay, and every difference is reported as a divergence.
+ * Nothing is saved in the database during the replay. Memories are not linked to
+ * an interaction in the database: the replay starts with an empty memory and reports
+ * the memories saved by each replayed interaction.
+ * Calls to external apps or HTTP endpoints are not sent again: they fail, and each replayed
+ * interaction lists them in its skipped_requests.
+ * A traffic split is replayed with the version recorded on the interactions of the conversation.
+ */
+pub fn replay_conversation(
+    client: &Client,
+    conversation_id: &str,
+    bot_opt: BotOpt,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+
+    let bot_opt = traffic_split::resolve_recorded_split(bot_opt, client, conversation_id, &mut db)?;
+    let mut bot = bot_opt.search_bot(&mut db)?;
+    init_bot(&mut bot)?;
+
+    replay::replay_conversation(client, conversation_id, &bot, &mut db)
+}
+
+/**
+ * Pause the bot for a given client to let a human agent take over the open conversation.
+ * During the handoff, incoming messages are saved and forwarded to HANDOFF_SINK_URL
+ * (if set) without being interpreted. A handoff can also be started from CSML
+ * with `do Handoff(reason="...")`.
+ */
+pub fn start_client_handoff(client: &Client, reason: serde_json::Value) -> Result<(), EngineError> {
+    let db = init_db()?;
+
+    handoff::start_client_handoff(client, reason, db)
+}
+
+/**
+ * Return the current handoff of a client, or None if the bot is not paused
+ */
+pub fn get_client_handoff(client: &Client) -> Result<Option<serde_json::Value>, EngineError> {
+    let mut db = init_db()?;
+
+    handoff::get_handoff(client, &mut db)
+}
+
+/**
+ * End the handoff of a client. The next incoming message is interpreted by the bot
+ * at the given flow/step, or where the conversation was when the handoff started.
+ */
+pub fn end_client_handoff(
+    client: &Client,
+    flow: Option<String>,
+    step: Option<String>,
+) -> Result<(), EngineError> {
+    let db = init_db()?;
+
+    handoff::end_handoff(client, flow, step, db)
+}
+
+/**
+ * Aggregate the conversations of a bot to get its most visited steps, drop-off points,
+ * flow completion rates, average conversation length and error rate, along with the error rate
+ * of each version picked by a traffic split.
+ * `from` (inclusive) and `to` (exclusive) are optional RFC 3339 dates
+ * to restrict the analytics to the conversations and interactions created in this time range.
+ * The time range defaults to the last 90 days and can not be longer than 90 days.
+ */
+pub fn get_bot_analytics(
+    bot_id: &str,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<serde_json::Value, EngineError> {
+    let (from, to) = analytics::get_date_range(parse_date(from)?, parse_date(to)?, Utc::now())?;
+    let mut db = init_db()?;
+
+    analytics::get_bot_analytics(bot_id, from, to, &mut db)
 }
 
 /**
@@ -126,6 +301,61 @@ pub fn get_client_conversations(
     conversations::get_client_conversations(client, &mut db, limit, pagination_key)
 }
 
+/**
+ * List the conversations that are still open for any client of a bot
+ */
+pub fn get_bot_open_conversations(
+    bot_id: &str,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+
+    conversations::get_bot_open_conversations(bot_id, limit, pagination_key, &mut db)
+}
+
+/**
+ * Return a conversation of a client with all its nodes, interactions and messages,
+ * or None if the client has no conversation with this id
+ */
+pub fn get_conversation_details(
+    client: &Client,
+    conversation_id: &str,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let mut db = init_db()?;
+
+    inspect::get_conversation_details(client, conversation_id, &mut db)
+}
+
+/**
+ * Close a given conversation of a client.
+ * Return false if the client has no conversation with this id.
+ */
+pub fn close_client_conversation(
+    client: &Client,
+    conversation_id: &str,
+) -> Result<bool, EngineError> {
+    let mut db = init_db()?;
+
+    inspect::close_conversation(client, conversation_id, &mut db)
+}
+
+/**
+ * Search the clients that had a conversation with a bot, optionally in a given channel
+ * and with a user id starting with `user_id`
+ */
+pub fn search_bot_clients(
+    bot_id: &str,
+    channel_id: Option<&str>,
+    user_id: Option<&str>,
+    limit: Option<i64>,
+    pagination_key: Option<String>,
+) -> Result<serde_json::Value, EngineError> {
+    let mut db = init_db()?;
+
+    conversations::search_bot_clients(bot_id, channel_id, user_id, limit, pagination_key, &mut db)
+}
+
 /**
  * Get current State ether Hold or NULL
  */
@@ -183,6 +413,37 @@ pub fn get_last_bot_version(bot_id: &str) -> Result<Option<BotVersion>, EngineEr
     bot::get_last_bot_version(bot_id, &mut db)
 }
 
+/**
+ * Export a version of a bot as a package: a .tar.gz archive with a manifest, a .csml file
+ * for each flow, a JSON file for each custom component and the JSON schema of the bot's env.
+ * The values of the env are not exported. Return None if the version does not exist.
+ */
+pub fn export_bot_version(bot_id: &str, version_id: &str) -> Result<Option<Vec<u8>>, EngineError> {
+    let mut db = init_db()?;
+
+    match bot::get_by_version_id(version_id, bot_id, &mut db)? {
+        Some(version) if version.bot.id == bot_id => {
+            Ok(Some(bot_package::export_bot_package(&version)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/**
+ * Import a package made by export_bot_version as a new version of a given bot.
+ * If the package has an env schema, `env` must match it. The bot is validated
+ * before the version is created, as with create_bot_version.
+ */
+pub fn import_bot_package(
+    bot_id: &str,
+    package: &[u8],
+    env: Option<serde_json::Value>,
+) -> Result<BotVersionCreated, EngineError> {
+    let csml_bot = bot_package::import_bot_package(package, bot_id, env)?;
+
+    create_bot_version(csml_bot)
+}
+
 /**
  * get bot by version_id
  */
@@ -233,6 +494,78 @@ pub fn delete_all_bot_versions(bot_id: &str) -> Result<(), EngineError> {
     bot::delete_bot_versions(bot_id, &mut db)
 }
 
+/**
+ * List the labels of a bot, with the version each of them points to
+ */
+pub fn get_bot_labels(bot_id: &str) -> Result<Vec<BotLabel>, EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::bot_labels::get_bot_labels(bot_id, &mut db)
+}
+
+/**
+ * Get a label of a bot, or None if it does not exist
+ */
+pub fn get_bot_label(bot_id: &str, label: &str) -> Result<Option<BotLabel>, EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::bot_labels::get_bot_label(bot_id, label, &mut db)
+}
+
+/**
+ * Make a label (e.g. staging, production) point to a given version of a bot.
+ * The label is created if it does not exist yet; the version it pointed to before
+ * is kept in its history.
+ */
+pub fn promote_bot_version(
+    bot_id: &str,
+    label: &str,
+    version_id: &str,
+) -> Result<BotLabel, EngineError> {
+    bot_labels::validate_label(label)?;
+    let mut db = init_db()?;
+
+    bot_labels::promote_bot_version(bot_id, label, version_id, &mut db)
+}
+
+/**
+ * Make a label point back to the version it pointed to before, or to a given version.
+ * Return None if the label does not exist.
+ */
+pub fn rollback_bot_label(
+    bot_id: &str,
+    label: &str,
+    version_id: Option<&str>,
+) -> Result<Option<BotLabel>, EngineError> {
+    let mut db = init_db()?;
+
+    bot_labels::rollback_bot_label(bot_id, label, version_id, &mut db)
+}
+
+/**
+ * Delete a label of a bot. The version it points to is not deleted.
+ */
+pub fn delete_bot_label(bot_id: &str, label: &str) -> Result<(), EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::bot_labels::delete_bot_label(bot_id, label, &mut db)
+}
+
+/**
+ * Compare the flows of two versions of a bot: flows added, removed or modified,
+ * with the steps they gained or lost and a unified diff of their content.
+ * Return None if one of the versions does not exist.
+ */
+pub fn diff_bot_versions(
+    bot_id: &str,
+    from_version_id: &str,
+    to_version_id: &str,
+) -> Result<Option<serde_json::Value>, EngineError> {
+    let mut db = init_db()?;
+
+    bot_diff::diff_bot_versions(bot_id, from_version_id, to_version_id, &mut db)
+}
+
 /**
  * Delete all data related to bot: versions, conversations, messages, memories, nodes, integrations
  */
@@ -269,6 +602,124 @@ pub fn delete_client(client: &Client) -> Result<(), EngineError> {
     user::delete_client(client, &mut db)
 }
 
+/**
+ * Create a new API key with the given scopes (run, read, admin), optionally restricted
+ * to a list of bots. The key is only returned once: only its hash is stored.
+ */
+pub fn create_api_key(
+    scopes: Vec<String>,
+    bot_ids: Option<Vec<String>>,
+) -> Result<ApiKeyCreated, EngineError> {
+    api_keys::validate_scopes(&scopes)?;
+
+    let mut db = init_db()?;
+
+    api_keys::create_api_key(scopes, bot_ids, &mut db)
+}
+
+/**
+ * Find the API key matching a key sent by a client, if it exists
+ */
+pub fn get_api_key(key: &str) -> Result<Option<DbApiKey>, EngineError> {
+    let mut db = init_db()?;
+
+    api_keys::get_api_key(key, &mut db)
+}
+
+/**
+ * Revoke an API key by id
+ */
+pub fn delete_api_key(id: &str) -> Result<(), EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::api_keys::delete_api_key(id, &mut db)
+}
+
+/**
+ * Check that every rate limit has a burst of at least 1 and a positive rate
+ */
+pub fn validate_bot_limits(limits: &BotLimits) -> Result<(), EngineError> {
+    bot_limits::validate_bot_limits(limits)
+}
+
+/**
+ * Set the rate limits and daily quota of a bot, replacing any previous limits
+ */
+pub fn set_bot_limits(bot_id: &str, limits: BotLimits) -> Result<(), EngineError> {
+    bot_limits::validate_bot_limits(&limits)?;
+
+    let mut db = init_db()?;
+
+    db_connectors::bot_limits::set_bot_limits(bot_id, &limits, &mut db)
+}
+
+/**
+ * Get the rate limits and daily quota of a bot, if any were set
+ */
+pub fn get_bot_limits(bot_id: &str) -> Result<Option<BotLimits>, EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::bot_limits::get_bot_limits(bot_id, &mut db)
+}
+
+/**
+ * Remove the rate limits and daily quota of a bot
+ */
+pub fn delete_bot_limits(bot_id: &str) -> Result<(), EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::bot_limits::delete_bot_limits(bot_id, &mut db)
+}
+
+/**
+ * Set the outbound HTTP policy of a bot, replacing any previous policy.
+ * It is only used if ENGINE_HTTP_POLICY_DB is enabled.
+ */
+pub fn set_http_policy(bot_id: &str, policy: HttpPolicy) -> Result<(), EngineError> {
+    http_policy::validate_http_policy(&policy)?;
+
+    let mut db = init_db()?;
+
+    db_connectors::http_policies::set_http_policy(bot_id, &policy, &mut db)
+}
+
+/**
+ * Get the outbound HTTP policy of a bot, if one was set
+ */
+pub fn get_http_policy(bot_id: &str) -> Result<Option<HttpPolicy>, EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::http_policies::get_http_policy(bot_id, &mut db)
+}
+
+/**
+ * Remove the outbound HTTP policy of a bot: the default policy applies again
+ */
+pub fn delete_http_policy(bot_id: &str) -> Result<(), EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::http_policies::delete_http_policy(bot_id, &mut db)
+}
+
+/**
+ * Count a new interaction of a bot for today (UTC), and return the number of interactions of the day.
+ * Nothing is counted once the bot has had max interactions today: None is returned instead.
+ */
+pub fn increment_daily_interactions(bot_id: &str, max: u64) -> Result<Option<u64>, EngineError> {
+    let mut db = init_db()?;
+
+    bot_limits::increment_daily_interactions(bot_id, max, &mut db)
+}
+
+/**
+ * Check that the configured database can be reached
+ */
+pub fn check_db() -> Result<(), EngineError> {
+    let mut db = init_db()?;
+
+    db_connectors::check_db(&mut db)
+}
+
 /**
  * List all the steps in every flow of a given CSML bot
  */
@@ -0,0 +1,449 @@
+use crate::data::{Database, EngineError};
+use crate::db_connectors::{messages, nodes, DbMessage, DbNode};
+use crate::http_policy::get_bot_http_policy;
+use crate::init::init_context;
+use crate::utils::{format_event, get_default_flow, match_flow};
+
+use csml_interpreter::{
+    data::{
+        ast::ForgetMemory, csml_bot::CsmlBot, Client, Context, Hold, HttpPolicy, HttpRequest,
+        HttpResponse, HttpTransport, Interval, MSG,
+    },
+    interpret,
+    interpreter::json_to_literal,
+};
+use serde_json::{json, map::Map, Value};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+/**
+ * What happened during a single interaction, either as it was recorded in the database
+ * or as it happens when the same event is interpreted again.
+ */
+#[derive(Debug, Default)]
+struct InteractionTrace {
+    flow: String,
+    step: String,
+    path: Vec<Value>,
+    messages: Vec<Value>,
+    memories: Map<String, Value>,
+    skipped_requests: Vec<Value>,
+    conversation_end: bool,
+}
+
+/**
+ * Transport of the replayed interactions. The requests of HTTP() and App() may have
+ * side effects (payments, emails, webhooks...): they are recorded to be reported as skipped,
+ * and fail instead of being sent.
+ */
+#[derive(Debug, Default)]
+struct SkippedTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+impl HttpTransport for SkippedTransport {
+    fn send(&self, request: &HttpRequest, _policy: Option<&HttpPolicy>) -> Result<HttpResponse, String> {
+        self.requests.lock().unwrap().push(request.to_owned());
+
+        Err(format!(
+            "the request {} {} is not sent during a replay",
+            request.method, request.url
+        ))
+    }
+}
+
+impl InteractionTrace {
+    fn to_json(&self) -> Value {
+        json!({
+            "flow": self.flow,
+            "step": self.step,
+            "path": self.path,
+            "messages": self.messages,
+        })
+    }
+}
+
+/**
+ * Replay all the events that were received in a conversation against the given bot,
+ * without writing anything in the database.
+ * Each interaction starts from the position reached by the previous replayed interaction.
+ */
+pub fn replay_conversation(
+    client: &Client,
+    conversation_id: &str,
+    bot: &CsmlBot,
+    db: &mut Database,
+) -> Result<Value, EngineError> {
+    let messages = messages::get_conversation_messages(client, conversation_id, db)?;
+    let nodes = nodes::get_conversation_nodes(client, conversation_id, db)?;
+
+    let default_flow = get_default_flow(bot)?.name.to_owned();
+    let mut context = init_context(default_flow, client.clone(), &bot.fn_endpoint);
+    context.http_policy = Some(get_bot_http_policy(&bot.id, db)?);
+    let mut hold: Option<Hold> = None;
+    let mut diverged = false;
+    let mut interactions = vec![];
+
+    for (index, (event, recorded)) in get_recorded_interactions(&messages, &nodes)
+        .into_iter()
+        .enumerate()
+    {
+        let interaction_id = event.interaction_id.to_owned();
+        let (replayed, error) = match replay_event(bot, &mut context, &mut hold, event, index) {
+            Ok(replayed) => (replayed, None),
+            Err(err) => (InteractionTrace::default(), Some(format!("{:?}", err))),
+        };
+
+        let divergences = get_divergences(&recorded, &replayed);
+        diverged = diverged || error.is_some() || !divergences.is_empty();
+
+        let mut replayed_json = replayed.to_json();
+        replayed_json["memories"] = Value::Object(replayed.memories);
+        replayed_json["skipped_requests"] = json!(replayed.skipped_requests);
+        replayed_json["conversation_end"] = json!(replayed.conversation_end);
+        replayed_json["error"] = json!(error);
+
+        interactions.push(json!({
+            "interaction_id": interaction_id,
+            "event": event.payload,
+            "recorded": recorded.to_json(),
+            "replayed": replayed_json,
+            "divergences": divergences,
+        }));
+    }
+
+    Ok(json!({
+        "conversation_id": conversation_id,
+        "diverged": diverged,
+        "interactions": interactions,
+    }))
+}
+
+/**
+ * Group the recorded messages and nodes of a conversation by interaction,
+ * in the order in which the events were received.
+ */
+fn get_recorded_interactions<'a>(
+    messages: &'a [DbMessage],
+    nodes: &[DbNode],
+) -> Vec<(&'a DbMessage, InteractionTrace)> {
+    messages
+        .iter()
+        .filter(|message| message.direction == "RECEIVE")
+        .map(|event| {
+            let path = nodes
+                .iter()
+                .filter(|node| node.interaction_id == event.interaction_id)
+                .map(|node| {
+                    json!({
+                        "flow": node.next_flow.as_ref().unwrap_or(&node.flow_id),
+                        "step": node.next_step,
+                    })
+                })
+                .collect();
+
+            let messages = messages
+                .iter()
+                .filter(|message| {
+                    message.direction == "SEND" && message.interaction_id == event.interaction_id
+                })
+                .map(|message| message.payload.to_owned())
+                .collect();
+
+            let trace = InteractionTrace {
+                flow: event.flow_id.to_owned(),
+                step: event.step_id.to_owned(),
+                path,
+                messages,
+                ..Default::default()
+            };
+
+            (event, trace)
+        })
+        .collect()
+}
+
+/**
+ * Interpret a recorded event without any side effect on the database.
+ * As in a real request, an event matching a flow's commands restarts the conversation
+ * at the start of this flow.
+ */
+fn replay_event(
+    bot: &CsmlBot,
+    context: &mut Context,
+    hold: &mut Option<Hold>,
+    event: &DbMessage,
+    index: usize,
+) -> Result<InteractionTrace, EngineError> {
+    let formatted_event = format_event(json!({ "payload": event.payload }))?;
+
+    match match_flow(&formatted_event, bot) {
+        Ok(flow) => {
+            context.flow = flow.name.to_owned();
+            context.step = "start".to_owned();
+            *hold = None;
+        }
+        Err(..) if index == 0 => {
+            context.flow = get_default_flow(bot)?.name.to_owned();
+            context.step = "start".to_owned();
+        }
+        Err(..) => {}
+    };
+
+    let mut trace = InteractionTrace {
+        flow: context.flow.to_owned(),
+        step: context.step.to_owned(),
+        ..Default::default()
+    };
+
+    context.hold = hold.take();
+    let transport = Arc::new(SkippedTransport::default());
+    context.http_transport = Some(transport.clone());
+
+    let (sender, receiver) = mpsc::channel::<MSG>();
+    let new_bot = bot.clone();
+    let new_context = context.clone();
+    thread::spawn(move || {
+        interpret(new_bot, new_context, formatted_event, Some(sender));
+    });
+
+    for received in receiver {
+        match received {
+            MSG::Remember(mem) => {
+                let lit = json_to_literal(&mem.value, Interval::default(), &context.flow)
+                    .map_err(|err| EngineError::Interpreter(err.message))?;
+                context.current.insert(mem.key.to_owned(), lit);
+                trace.memories.insert(mem.key, mem.value);
+            }
+            MSG::Forget(mem) => match mem {
+                ForgetMemory::ALL => {
+                    context.current.clear();
+                    trace.memories.clear();
+                }
+                ForgetMemory::SINGLE(memory) => {
+                    context.current.remove(&memory.ident);
+                    trace.memories.remove(&memory.ident);
+                }
+                ForgetMemory::LIST(mem_list) => {
+                    for mem in mem_list.iter() {
+                        context.current.remove(&mem.ident);
+                        trace.memories.remove(&mem.ident);
+                    }
+                }
+            },
+            MSG::Message(mut msg) => trace.messages.push(msg.message_to_json()),
+            MSG::Hold(new_hold) => *hold = Some(new_hold),
+            MSG::Next {
+                flow: Some(flow),
+                step,
+            } => {
+                let step = step.unwrap_or_else(|| "start".to_owned());
+                trace.path.push(json!({"flow": flow, "step": step}));
+
+                context.flow = flow;
+                context.step = step;
+            }
+            MSG::Next { flow: None, step } => {
+                let step = step.unwrap_or_else(|| "end".to_owned());
+                trace.path.push(json!({"flow": context.flow, "step": step}));
+
+                if step == "end" {
+                    trace.conversation_end = true;
+                    break;
+                }
+                context.step = step;
+            }
+            // a handoff only pauses the bot for the following interactions
+            MSG::Handoff { .. } => {}
+            MSG::Error(mut err_msg) => {
+                trace.messages.push(err_msg.message_to_json());
+                trace.conversation_end = true;
+            }
+        }
+    }
+
+    trace.skipped_requests = transport
+        .requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|request| json!({"method": request.method, "url": request.url}))
+        .collect();
+
+    Ok(trace)
+}
+
+/**
+ * Compare a recorded interaction with its replay: the starting position, each node
+ * of the path and each message sent must be identical.
+ */
+fn get_divergences(recorded: &InteractionTrace, replayed: &InteractionTrace) -> Vec<Value> {
+    let mut divergences = vec![];
+
+    if recorded.flow != replayed.flow || recorded.step != replayed.step {
+        divergences.push(json!({
+            "kind": "start",
+            "index": 0,
+            "recorded": {"flow": recorded.flow, "step": recorded.step},
+            "replayed": {"flow": replayed.flow, "step": replayed.step},
+        }));
+    }
+
+    let mut compare = |kind: &str, recorded: &[Value], replayed: &[Value]| {
+        for index in 0..recorded.len().max(replayed.len()) {
+            let (recorded, replayed) = (recorded.get(index), replayed.get(index));
+
+            if recorded != replayed {
+                divergences.push(json!({
+                    "kind": kind,
+                    "index": index,
+                    "recorded": recorded,
+                    "replayed": replayed,
+                }));
+            }
+        }
+    };
+
+    compare("path", &recorded.path, &replayed.path);
+    compare("message", &recorded.messages, &replayed.messages);
+
+    divergences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use csml_interpreter::data::csml_flow::CsmlFlow;
+
+    fn trace(flow: &str, step: &str, path: Vec<Value>, messages: Vec<Value>) -> InteractionTrace {
+        InteractionTrace {
+            flow: flow.to_owned(),
+            step: step.to_owned(),
+            path,
+            messages,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ok_no_divergences() {
+        let recorded = trace(
+            "Default",
+            "start",
+            vec![json!({"flow": "Default", "step": "end"})],
+            vec![json!({"content_type": "text", "content": {"text": "hello"}})],
+        );
+        let replayed = trace(
+            "Default",
+            "start",
+            vec![json!({"flow": "Default", "step": "end"})],
+            vec![json!({"content_type": "text", "content": {"text": "hello"}})],
+        );
+
+        assert!(get_divergences(&recorded, &replayed).is_empty());
+    }
+
+    #[test]
+    fn ok_start_divergence() {
+        let recorded = trace("Default", "start", vec![], vec![]);
+        let replayed = trace("Other", "start", vec![], vec![]);
+
+        assert_eq!(
+            get_divergences(&recorded, &replayed),
+            vec![json!({
+                "kind": "start",
+                "index": 0,
+                "recorded": {"flow": "Default", "step": "start"},
+                "replayed": {"flow": "Other", "step": "start"},
+            })]
+        );
+    }
+
+    #[test]
+    fn ok_path_divergence() {
+        let recorded = trace(
+            "Default",
+            "start",
+            vec![json!({"flow": "Default", "step": "a"}), json!({"flow": "Default", "step": "end"})],
+            vec![],
+        );
+        let replayed = trace(
+            "Default",
+            "start",
+            vec![json!({"flow": "Default", "step": "a"})],
+            vec![],
+        );
+
+        assert_eq!(
+            get_divergences(&recorded, &replayed),
+            vec![json!({
+                "kind": "path",
+                "index": 1,
+                "recorded": {"flow": "Default", "step": "end"},
+                "replayed": null,
+            })]
+        );
+    }
+
+    #[test]
+    fn ok_message_divergence() {
+        let recorded = trace("Default", "start", vec![], vec![json!("hello"), json!("bye")]);
+        let replayed = trace("Default", "start", vec![], vec![json!("hello"), json!("see you")]);
+
+        assert_eq!(
+            get_divergences(&recorded, &replayed),
+            vec![json!({
+                "kind": "message",
+                "index": 1,
+                "recorded": "bye",
+                "replayed": "see you",
+            })]
+        );
+    }
+
+    #[test]
+    fn ok_replay_skips_http_requests() {
+        let mut bot = CsmlBot {
+            id: "botid".to_owned(),
+            name: "bot".to_owned(),
+            fn_endpoint: None,
+            flows: vec![CsmlFlow {
+                id: "Default".to_owned(),
+                name: "Default".to_owned(),
+                content: "start:\n  say HTTP(\"https://example.com/pay\").post({\"amount\": 1}).send()\n  say \"paid\"\n  goto end".to_owned(),
+                commands: vec![],
+            }],
+            native_components: None,
+            custom_components: None,
+            default_flow: "Default".to_owned(),
+            bot_ast: None,
+            env: None,
+        };
+        crate::init::init_bot(&mut bot).unwrap();
+
+        let client = Client::new("botid".to_owned(), "channel".to_owned(), "user".to_owned());
+        let event = DbMessage {
+            id: "id".to_owned(),
+            client: client.clone(),
+            interaction_id: "interaction_id".to_owned(),
+            conversation_id: "conversation_id".to_owned(),
+            flow_id: "Default".to_owned(),
+            step_id: "start".to_owned(),
+            message_order: 0,
+            interaction_order: 0,
+            direction: "RECEIVE".to_owned(),
+            payload: json!({"content_type": "text", "content": {"text": "hello"}}),
+            content_type: "text".to_owned(),
+            created_at: "".to_owned(),
+        };
+
+        let mut context = init_context("Default".to_owned(), client, &None);
+        let trace = replay_event(&bot, &mut context, &mut None, &event, 0).unwrap();
+
+        assert_eq!(
+            trace.skipped_requests,
+            vec![json!({"method": "POST", "url": "https://example.com/pay"})]
+        );
+        assert!(json!(trace.messages).to_string().contains("is not sent during a replay"));
+    }
+}
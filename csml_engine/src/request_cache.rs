@@ -0,0 +1,119 @@
+use crate::data::{CsmlRequest, Database, EngineError, IDEMPOTENCY_WINDOW};
+use crate::db_connectors::state::{delete_state_key, get_state_key, set_state_items};
+
+use chrono::prelude::Utc;
+use serde_json::{json, map::Map, Value};
+use std::env;
+
+/**
+ * Window in milliseconds, or 0 (disabled) if it is not set or not a valid number
+ */
+fn parse_window(value: Option<&str>) -> i64 {
+    match value.map(|value| value.parse::<i64>()) {
+        Some(Ok(window)) if window > 0 => window,
+        _ => 0,
+    }
+}
+
+fn get_window() -> i64 {
+    parse_window(env::var(IDEMPOTENCY_WINDOW).ok().as_deref())
+}
+
+/**
+ * The cached response, if it has not expired yet
+ */
+fn get_valid_response(cached: &Value, now: i64) -> Option<Map<String, Value>> {
+    match (cached["expires_at"].as_i64(), &cached["response"]) {
+        (Some(expires_at), Value::Object(response)) if expires_at > now => Some(response.to_owned()),
+        _ => None,
+    }
+}
+
+fn format_cached_response(response: &Map<String, Value>, now: i64, window: i64) -> Value {
+    json!({"response": response, "expires_at": now + window})
+}
+
+/**
+ * Channels may retry webhook deliveries, in which case we receive the same request_id
+ * more than once. If IDEMPOTENCY_WINDOW_MS is set, the response of each processed request
+ * is kept for that duration in the client's state, and returned as is for any duplicate
+ * request instead of interpreting the event again.
+ *
+ * Each response is saved in its own state key (the request_id) with its own expiry date,
+ * and deleted once it is found expired.
+ */
+pub fn get_cached_response(
+    request: &CsmlRequest,
+    db: &mut Database,
+) -> Result<Option<Map<String, Value>>, EngineError> {
+    if get_window() <= 0 || request.request_id.is_empty() {
+        return Ok(None);
+    }
+
+    let now = Utc::now().timestamp_millis();
+
+    match get_state_key(&request.client, "request", &request.request_id, db)? {
+        Some(cached) => match get_valid_response(&cached, now) {
+            Some(response) => Ok(Some(response)),
+            None => {
+                delete_state_key(&request.client, "request", &request.request_id, db)?;
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+/**
+ * Save the response of a processed request for the duration of the window.
+ * The responses of the other requests of the client are not touched.
+ */
+pub fn cache_response(
+    request: &CsmlRequest,
+    response: &Map<String, Value>,
+    db: &mut Database,
+) -> Result<(), EngineError> {
+    let window = get_window();
+    if window <= 0 || request.request_id.is_empty() {
+        return Ok(());
+    }
+
+    let now = Utc::now().timestamp_millis();
+    let cached = format_cached_response(response, now, window);
+
+    // an expired response of the same request_id may still be there
+    delete_state_key(&request.client, "request", &request.request_id, db)?;
+    set_state_items(
+        &request.client,
+        "request",
+        vec![(request.request_id.as_str(), &cached)],
+        db,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_parse_window() {
+        assert_eq!(parse_window(Some("5000")), 5000);
+        assert_eq!(parse_window(Some("0")), 0);
+        assert_eq!(parse_window(Some("-10")), 0);
+        assert_eq!(parse_window(Some("5s")), 0);
+        assert_eq!(parse_window(None), 0);
+    }
+
+    #[test]
+    fn ok_cached_response_expiry() {
+        let mut response = Map::new();
+        response.insert("request_id".to_owned(), json!("request_id"));
+        let cached = format_cached_response(&response, 1_000, 500);
+
+        assert_eq!(cached["expires_at"], 1_500);
+        assert_eq!(get_valid_response(&cached, 1_000), Some(response.to_owned()));
+        assert_eq!(get_valid_response(&cached, 1_499), Some(response));
+        assert_eq!(get_valid_response(&cached, 1_500), None);
+        assert_eq!(get_valid_response(&json!({"response": {}}), 0), None);
+    }
+}
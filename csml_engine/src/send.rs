@@ -1,11 +1,17 @@
-use crate::data::{ConversationInfo, DEBUG, DISABLE_SSL_VERIFY};
+use crate::data::{ConversationInfo, DISABLE_SSL_VERIFY, HANDOFF_SINK};
+use crate::init::init_curl;
+use csml_interpreter::metrics::{self, Metric};
 use curl::{easy::Easy, Error};
 use std::env;
 use std::io::Read;
-use std::time::SystemTime;
+
+pub const CALLBACK_FAILURES: Metric = Metric {
+    name: "csml_callback_failures_total",
+    help: "Number of messages that could not be sent to a callback_url or to the handoff sink",
+};
 
 fn format_and_transfer(curl: &mut Easy, mut msg: &[u8], result: &mut Vec<u8>) -> Result<(), Error> {
-    let now = SystemTime::now();
+    let _span = tracing::debug_span!("http_post", bytes = msg.len()).entered();
 
     match env::var(DISABLE_SSL_VERIFY) {
         Ok(var) if var == "true" => {
@@ -25,16 +31,6 @@ fn format_and_transfer(curl: &mut Easy, mut msg: &[u8], result: &mut Vec<u8>) ->
     })?;
     transfer.perform()?;
 
-    if let Ok(var) = env::var(DEBUG) {
-        if var == "true" {
-            let el = now.elapsed().unwrap();
-            println!(
-                "http post callback_url - {}.{}",
-                el.as_secs(),
-                el.as_millis()
-            );
-        }
-    }
     Ok(())
 }
 
@@ -50,12 +46,31 @@ pub fn send_to_callback_url(c_info: &mut ConversationInfo, msg: &[u8]) {
 
     let mut result = Vec::new();
     if let Err(err) = format_and_transfer(curl, msg, &mut result) {
-        match env::var(DEBUG) {
-            Ok(ref var) if var == "true" => {
-                println!("failed to send msg to callback_url {:?}", err)
-            }
-            _ => (),
-        };
-        return;
+        metrics::inc_counter(&CALLBACK_FAILURES, &[("target", "callback_url")], 1.0);
+        tracing::warn!(error = ?err, "failed to send msg to callback_url");
     };
 }
+
+/**
+ * If a HANDOFF_SINK_URL is defined, the events of a handoff (start, received messages, end)
+ * must be sent to its endpoint so that a human agent can take over the conversation.
+ * Otherwise, the messages are only saved in the database.
+ */
+pub fn send_to_handoff_sink(event: &serde_json::Value) {
+    let url = match env::var(HANDOFF_SINK) {
+        Ok(url) if !url.is_empty() => url,
+        _ => return,
+    };
+
+    let msg = event.to_string();
+    let mut result = Vec::new();
+    let sent = match init_curl(&url) {
+        Ok(mut curl) => format_and_transfer(&mut curl, msg.as_bytes(), &mut result),
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = sent {
+        metrics::inc_counter(&CALLBACK_FAILURES, &[("target", "handoff_sink")], 1.0);
+        tracing::warn!(error = ?err, "failed to send msg to handoff sink");
+    }
+}
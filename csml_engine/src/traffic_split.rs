@@ -0,0 +1,177 @@
+/**
+ * A traffic split runs a bot with one of several of its versions, picked at random
+ * according to their weights. Once a client got a version, it sticks to it
+ * (the version is kept in the client's state) as long as this version is part of the split,
+ * so that a user does not switch between variants in the middle of a test.
+ */
+use crate::data::{BotOpt, Database, EngineError, VersionWeight};
+use crate::db_connectors::state::{get_state_key, set_state_items};
+use crate::db_connectors::{interactions, messages};
+use csml_interpreter::data::Client;
+
+use rand::Rng;
+use serde_json::{json, Value};
+
+pub fn validate_split(split: &[VersionWeight]) -> Result<(), EngineError> {
+    if split.iter().all(|version| version.weight == 0) {
+        return Err(EngineError::Format(
+            "a split needs at least one version with a weight above 0".to_owned(),
+        ));
+    }
+
+    Ok(())
+}
+
+/**
+ * `roll` is a number between 0 and the sum of the weights (excluded)
+ */
+fn pick_version(split: &[VersionWeight], mut roll: u64) -> &str {
+    for version in split.iter() {
+        if roll < version.weight as u64 {
+            return &version.version_id;
+        }
+        roll -= version.weight as u64;
+    }
+
+    // unreachable as long as roll is below the sum of the weights
+    &split[split.len() - 1].version_id
+}
+
+fn get_sticky_version<'a>(split: &'a [VersionWeight], assigned: Option<Value>) -> Option<&'a str> {
+    let assigned = assigned?;
+    let assigned = assigned.as_str()?;
+
+    split
+        .iter()
+        .find(|version| version.version_id == assigned && version.weight > 0)
+        .map(|version| version.version_id.as_str())
+}
+
+/**
+ * Replace a split with the version the client is assigned to, assigning one if needed.
+ * Return the picked version_id along with the bot_opt, or None if bot_opt is not a split.
+ */
+pub fn resolve_split(
+    bot_opt: BotOpt,
+    client: &Client,
+    db: &mut Database,
+) -> Result<(BotOpt, Option<String>), EngineError> {
+    let (split, bot_id, fn_endpoint) = match bot_opt {
+        BotOpt::Split {
+            split,
+            bot_id,
+            fn_endpoint,
+        } => (split, bot_id, fn_endpoint),
+        bot_opt => return Ok((bot_opt, None)),
+    };
+    validate_split(&split)?;
+
+    let assigned = get_state_key(client, "split", "version_id", db)?;
+    let version_id = match get_sticky_version(&split, assigned) {
+        Some(version_id) => version_id.to_owned(),
+        None => {
+            let total: u64 = split.iter().map(|version| version.weight as u64).sum();
+            let version_id = pick_version(&split, rand::thread_rng().gen_range(0, total)).to_owned();
+
+            set_state_items(client, "split", vec![("version_id", &json!(version_id))], db)?;
+            version_id
+        }
+    };
+
+    let bot_opt = BotOpt::Id {
+        version_id: version_id.to_owned(),
+        bot_id,
+        fn_endpoint,
+    };
+
+    Ok((bot_opt, Some(version_id)))
+}
+
+/**
+ * Replace a split with the version that ran a recorded conversation, read from its
+ * interactions. Unlike resolve_split, the version the client is assigned to is neither
+ * read nor changed, so that replaying a conversation has no effect on the client.
+ */
+pub fn resolve_recorded_split(
+    bot_opt: BotOpt,
+    client: &Client,
+    conversation_id: &str,
+    db: &mut Database,
+) -> Result<BotOpt, EngineError> {
+    let (bot_id, fn_endpoint) = match bot_opt {
+        BotOpt::Split {
+            bot_id, fn_endpoint, ..
+        } => (bot_id, fn_endpoint),
+        bot_opt => return Ok(bot_opt),
+    };
+
+    let received = messages::get_conversation_messages(client, conversation_id, db)?
+        .into_iter()
+        .filter(|message| message.direction == "RECEIVE");
+    for message in received {
+        let interaction = interactions::get_interaction(client, &message.interaction_id, db)?;
+
+        if let Some(version_id) = interaction.and_then(|interaction| interaction.version_id) {
+            return Ok(BotOpt::Id {
+                version_id,
+                bot_id,
+                fn_endpoint,
+            });
+        }
+    }
+
+    Err(EngineError::Format(format!(
+        "no version of bot '{}' was recorded for conversation '{}'",
+        bot_id, conversation_id
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split() -> Vec<VersionWeight> {
+        vec![
+            VersionWeight {
+                version_id: "a".to_owned(),
+                weight: 90,
+            },
+            VersionWeight {
+                version_id: "b".to_owned(),
+                weight: 0,
+            },
+            VersionWeight {
+                version_id: "c".to_owned(),
+                weight: 10,
+            },
+        ]
+    }
+
+    #[test]
+    fn ok_pick_version() {
+        let split = split();
+
+        assert_eq!(pick_version(&split, 0), "a");
+        assert_eq!(pick_version(&split, 89), "a");
+        assert_eq!(pick_version(&split, 90), "c");
+        assert_eq!(pick_version(&split, 99), "c");
+    }
+
+    #[test]
+    fn ok_sticky_version() {
+        let split = split();
+
+        assert_eq!(get_sticky_version(&split, Some(json!("c"))), Some("c"));
+        // versions that left the split or no longer get traffic are assigned again
+        assert_eq!(get_sticky_version(&split, Some(json!("b"))), None);
+        assert_eq!(get_sticky_version(&split, Some(json!("d"))), None);
+        assert_eq!(get_sticky_version(&split, None), None);
+    }
+
+    #[test]
+    fn ko_validate_split() {
+        assert!(validate_split(&split()).is_ok());
+        assert!(validate_split(&[]).is_err());
+        assert!(validate_split(&split()[1..2]).is_err());
+    }
+}
@@ -1,11 +1,11 @@
 use crate::{
-    data::{ConversationInfo, Database, EngineError, DEBUG},
+    data::{ConversationInfo, Database, EngineError},
     db_connectors::state::delete_state_key,
     send::send_to_callback_url,
     CsmlBot, CsmlFlow,
 };
 
-use chrono::{prelude::Utc, SecondsFormat};
+use chrono::{prelude::Utc, DateTime, SecondsFormat};
 use csml_interpreter::{
     error_format::{ERROR_SIZE_IDENT, ERROR_NUMBER_AS_KEY, ERROR_KEY_ALPHANUMERIC},
     data::{ast::Flow, Client, Context,
@@ -17,7 +17,6 @@ use csml_interpreter::{
 use rand::seq::SliceRandom;
 use serde_json::{json, map::Map, Value};
 use std::collections::HashMap;
-use std::env;
 
 use md5::{Digest, Md5};
 
@@ -133,8 +132,24 @@ pub fn format_event(json_event: serde_json::Value) -> Result<Event, EngineError>
 }
 
 /**
- * Send a message to the configured callback_url.
- * If not callback_url is configured, skip this action.
+ * Parse an optional RFC 3339 date, such as 2021-03-12T12:33:42.123Z
+ */
+pub fn parse_date(date: Option<&str>) -> Result<Option<DateTime<Utc>>, EngineError> {
+    match date {
+        Some(date) => match DateTime::parse_from_rfc3339(date) {
+            Ok(date) => Ok(Some(date.with_timezone(&Utc))),
+            Err(_) => Err(EngineError::Format(format!(
+                "'{}' is not a valid RFC 3339 date",
+                date
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+/**
+ * Send a message to the configured callback_url and to the stream of the request.
+ * If neither a callback_url nor a stream is configured, skip this action.
  */
 pub fn send_msg_to_callback_url(
     data: &mut ConversationInfo,
@@ -144,12 +159,12 @@ pub fn send_msg_to_callback_url(
 ) {
     let messages = messages_formater(data, msg, interaction_order, end);
 
-    match env::var(DEBUG) {
-        Ok(ref var) if var == "true" => {
-            println!("conversation_end => {}", messages["conversation_end"]);
-        }
-        _ => (),
-    };
+    if let Some(stream) = &data.stream {
+        // the receiver may have been dropped if the client disconnected
+        stream.send(Value::Object(messages.clone())).ok();
+    }
+
+    tracing::debug!(conversation_end = %messages["conversation_end"], "messages sent");
 
     match serde_json::to_string(&messages) {
         Ok(string) => send_to_callback_url(data, string.as_bytes()),
@@ -257,9 +272,25 @@ pub fn search_flow<'a>(
     client: &Client,
     db: &mut Database,
 ) -> Result<&'a CsmlFlow, EngineError> {
+    if event.content_type == "flow_trigger" {
+        delete_state_key(&client, "hold", "position", db)?;
+        return match_flow(event, bot);
+    }
+
+    let flow = match_flow(event, bot)?;
+    delete_state_key(&client, "hold", "position", db)?;
+
+    Ok(flow)
+}
+
+/**
+ * Match the user's input against the bot's flows without touching the current state:
+ * - flow_trigger events match a flow's id or name
+ * - other events match a random flow among the ones with a matching command
+ */
+pub fn match_flow<'a>(event: &Event, bot: &'a CsmlBot) -> Result<&'a CsmlFlow, EngineError> {
     match event {
         event if event.content_type == "flow_trigger" => {
-            delete_state_key(&client, "hold", "position", db)?;
             get_flow_by_id(&event.content_value, &bot.flows)
         }
         event => {
@@ -277,10 +308,7 @@ pub fn search_flow<'a>(
             }
 
             match random_flows.choose(&mut rand::thread_rng()) {
-                Some(flow) => {
-                    delete_state_key(&client, "hold", "position", db)?;
-                    Ok(flow)
-                }
+                Some(flow) => Ok(flow),
                 None => Err(EngineError::Interpreter(format!(
                     "Flow '{}' does not exist",
                     event.content_value
@@ -5,10 +5,13 @@ pub mod csml_bot;
 pub mod csml_flow;
 pub mod csml_result;
 pub mod data;
+pub mod diff;
 pub mod error_info;
 pub mod event;
 pub mod fn_args_type;
 pub mod hold;
+pub mod http_policy;
+pub mod http_transport;
 pub mod literal;
 pub mod memories;
 pub mod message;
@@ -29,6 +32,8 @@ pub use data::Data;
 pub use event::Event;
 pub use fn_args_type::ArgsType;
 pub use hold::{Hold, IndexInfo};
+pub use http_policy::HttpPolicy;
+pub use http_transport::{HttpFixture, HttpRequest, HttpResponse, HttpTransport, MockTransport};
 pub use literal::Literal;
 pub use memories::{Memory, MemoryType};
 pub use message::Message;
@@ -1,11 +1,14 @@
 use crate::data::{
     primitive::{PrimitiveObject, PrimitiveType},
-    Client, Hold, Interval, Literal,
+    http_transport::HttpTransport,
+    Client, Hold, HttpPolicy, Interval, Literal,
 };
 
 use crate::interpreter::{json_to_literal, memory_to_literal};
 
+use chrono::{DateTime, Utc};
 use nom::lib::std::collections::HashMap;
+use std::sync::{atomic::AtomicUsize, Arc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURE
@@ -25,6 +28,14 @@ pub struct Context {
     pub hold: Option<Hold>,
     pub step: String,
     pub flow: String,
+    // no restriction if there is no policy
+    pub http_policy: Option<HttpPolicy>,
+    // shared with the contexts of the functions called during the interaction
+    pub http_requests: Arc<AtomicUsize>,
+    // requests are sent with ureq if there is no transport
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    // current time of Time(), the system time if not set
+    pub now: Option<DateTime<Utc>>,
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -91,6 +102,10 @@ impl Context {
             hold,
             step: step.to_owned(),
             flow: flow.to_owned(),
+            http_policy: None,
+            http_requests: Arc::new(AtomicUsize::new(0)),
+            http_transport: None,
+            now: None,
         }
     }
 }
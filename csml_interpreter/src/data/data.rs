@@ -97,6 +97,10 @@ pub fn init_child_context(data: &Data) -> Context {
         hold: None,
         step: data.context.step.clone(),
         flow: data.context.flow.clone(),
+        http_policy: data.context.http_policy.clone(),
+        http_requests: data.context.http_requests.clone(),
+        http_transport: data.context.http_transport.clone(),
+        now: data.context.now,
     }
 }
 
@@ -0,0 +1,109 @@
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+// above this size, the lines that changed are not matched one by one
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, PartialEq)]
+pub enum DiffLine<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Diff two lists of lines with their longest common subsequence,
+ * after putting aside the lines they start and end with
+ */
+pub fn diff_lines<'a>(from: &[&'a str], to: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let suffix = from[prefix..]
+        .iter()
+        .rev()
+        .zip(to[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old = &from[prefix..from.len() - suffix];
+    let new = &to[prefix..to.len() - suffix];
+
+    let mut lines: Vec<DiffLine> = from[..prefix].iter().map(|line| DiffLine::Same(line)).collect();
+
+    if old.len() * new.len() > MAX_DIFF_CELLS {
+        lines.extend(old.iter().map(|line| DiffLine::Removed(line)));
+        lines.extend(new.iter().map(|line| DiffLine::Added(line)));
+    } else {
+        // lcs[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+        let width = new.len() + 1;
+        let mut lcs = vec![0_u32; (old.len() + 1) * width];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i * width + j] = if old[i] == new[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() && j < new.len() {
+            if old[i] == new[j] {
+                lines.push(DiffLine::Same(old[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                lines.push(DiffLine::Removed(old[i]));
+                i += 1;
+            } else {
+                lines.push(DiffLine::Added(new[j]));
+                j += 1;
+            }
+        }
+        lines.extend(old[i..].iter().map(|line| DiffLine::Removed(line)));
+        lines.extend(new[j..].iter().map(|line| DiffLine::Added(line)));
+    }
+
+    lines.extend(from[from.len() - suffix..].iter().map(|line| DiffLine::Same(line)));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_diff_lines() {
+        let from = vec!["a", "b", "c", "d"];
+        let to = vec!["a", "c", "e", "d"];
+
+        assert_eq!(
+            diff_lines(&from, &to),
+            vec![
+                DiffLine::Same("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Same("c"),
+                DiffLine::Added("e"),
+                DiffLine::Same("d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ok_diff_lines_too_long() {
+        let from: Vec<String> = (0..3000).map(|i| format!("a{}", i)).collect();
+        let to: Vec<String> = (0..3000).map(|i| format!("b{}", i)).collect();
+        let from: Vec<&str> = from.iter().map(|line| line.as_str()).collect();
+        let to: Vec<&str> = to.iter().map(|line| line.as_str()).collect();
+
+        let lines = diff_lines(&from, &to);
+        assert_eq!(lines.len(), 6000);
+        assert_eq!(lines[0], DiffLine::Removed("a0"));
+        assert_eq!(lines[3000], DiffLine::Added("b0"));
+    }
+}
@@ -2,7 +2,7 @@
 // DATA STRUCTURES
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Event {
     pub content_type: String,
     pub content_value: String,
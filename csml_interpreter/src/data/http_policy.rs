@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Error as IoError, ErrorKind, Result as IoResult};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Restrictions on the HTTP requests made by a bot with HTTP() and App().
+ *
+ * Hosts are matched without their port, either exactly ("api.example.com") or with
+ * their subdomains ("*.example.com"). Denied hosts win over allowed hosts.
+ */
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HttpPolicy {
+    // any host is allowed if there is no list
+    pub allow_hosts: Option<Vec<String>>,
+    pub deny_hosts: Vec<String>,
+    // loopback, private, link-local (cloud metadata endpoints)... addresses
+    pub allow_private_ips: bool,
+    pub max_response_bytes: Option<u64>,
+    // per interaction
+    pub max_requests: Option<usize>,
+    // hosts configured by the operator, like the one of fn_endpoint, that are never blocked
+    #[serde(skip)]
+    pub trusted_hosts: Vec<String>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn match_host(pattern: &str, host: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host.ends_with(&format!(".{}", domain)),
+        None => pattern == host,
+    }
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let [first, second, third, _] = ip.octets();
+
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        // "this network"
+        || first == 0
+        // shared address space (carrier-grade NAT)
+        || (first == 100 && (64..128).contains(&second))
+        // IETF protocol assignments
+        || (first == 192 && second == 0 && third == 0)
+        // benchmarking
+        || (first == 198 && (second == 18 || second == 19))
+        // reserved
+        || first >= 240
+}
+
+fn get_ipv4(high: u16, low: u16) -> Ipv4Addr {
+    Ipv4Addr::new((high >> 8) as u8, high as u8, (low >> 8) as u8, low as u8)
+}
+
+/**
+ * IPv4 address reached through an IPv6 one: IPv4-mapped (::ffff:a.b.c.d),
+ * IPv4-compatible (::a.b.c.d), NAT64 (64:ff9b::a.b.c.d) or 6to4 (2002:aabb:ccdd::)
+ */
+fn get_embedded_ipv4(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+    match ip.segments() {
+        [0, 0, 0, 0, 0, 0xffff, high, low]
+        | [0, 0, 0, 0, 0, 0, high, low]
+        | [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some(get_ipv4(high, low)),
+        [0x2002, high, low, ..] => Some(get_ipv4(high, low)),
+        _ => None,
+    }
+}
+
+fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // unique local
+        || (first & 0xfe00) == 0xfc00
+        // link-local
+        || (first & 0xffc0) == 0xfe80
+        // site-local (deprecated, but still routed by some networks)
+        || (first & 0xffc0) == 0xfec0
+        || matches!(get_embedded_ipv4(ip), Some(ipv4) if is_private_ipv4(&ipv4))
+}
+
+/**
+ * "example.com:443" => "example.com", "[::1]:80" => "::1"
+ */
+fn get_netloc_host(netloc: &str) -> &str {
+    let host = match netloc.rfind(':') {
+        Some(index) if !netloc[index..].contains(']') => &netloc[..index],
+        _ => netloc,
+    };
+
+    host.trim_start_matches('[').trim_end_matches(']')
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn is_private_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => is_private_ipv6(ip),
+    }
+}
+
+impl HttpPolicy {
+    /**
+     * Check that the host is allowed, and if it is an IP that it is not a private one
+     */
+    pub fn check_host(&self, host: &str) -> Result<(), String> {
+        let host = host.trim_end_matches('.').to_lowercase();
+
+        if self.is_trusted(&host) {
+            return Ok(());
+        }
+
+        if self.deny_hosts.iter().any(|pattern| match_host(pattern, &host)) {
+            return Err(format!("host '{}' is denied by the HTTP policy of this bot", host));
+        }
+
+        if let Some(allow_hosts) = &self.allow_hosts {
+            if !allow_hosts.iter().any(|pattern| match_host(pattern, &host)) {
+                return Err(format!("host '{}' is not allowed by the HTTP policy of this bot", host));
+            }
+        }
+
+        match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) if !self.allow_private_ips && is_private_ip(&ip) => Err(format!(
+                "private address '{}' is blocked by the HTTP policy of this bot",
+                ip
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_trusted(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_lowercase();
+
+        self.trusted_hosts.iter().any(|trusted| trusted.to_lowercase() == host)
+    }
+
+    pub fn check_request_count(&self, count: usize) -> Result<(), String> {
+        match self.max_requests {
+            Some(max) if count > max => Err(format!(
+                "the HTTP policy of this bot allows at most {} requests per interaction",
+                max
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/**
+ * Every connection goes through the resolver, redirections included:
+ * hosts are checked again and the resolved addresses must not be private.
+ */
+impl ureq::Resolver for HttpPolicy {
+    fn resolve(&self, netloc: &str) -> IoResult<Vec<SocketAddr>> {
+        let host = get_netloc_host(netloc);
+        self.check_host(host)
+            .map_err(|err| IoError::new(ErrorKind::PermissionDenied, err))?;
+
+        let addrs: Vec<SocketAddr> = netloc.to_socket_addrs()?.collect();
+
+        if !self.allow_private_ips && !self.is_trusted(host) {
+            if let Some(addr) = addrs.iter().find(|addr| is_private_ip(&addr.ip())) {
+                return Err(IoError::new(
+                    ErrorKind::PermissionDenied,
+                    format!(
+                        "host '{}' resolves to the private address '{}', blocked by the HTTP policy of this bot",
+                        host,
+                        addr.ip()
+                    ),
+                ));
+            }
+        }
+
+        Ok(addrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_check_host() {
+        let policy = HttpPolicy {
+            allow_hosts: Some(vec!["api.example.com".to_owned(), "*.csml.dev".to_owned()]),
+            deny_hosts: vec!["admin.csml.dev".to_owned()],
+            ..Default::default()
+        };
+
+        assert!(policy.check_host("api.example.com").is_ok());
+        assert!(policy.check_host("API.example.com.").is_ok());
+        assert!(policy.check_host("hooks.csml.dev").is_ok());
+        assert!(policy.check_host("csml.dev").is_err());
+        assert!(policy.check_host("admin.csml.dev").is_err());
+        assert!(policy.check_host("example.com").is_err());
+    }
+
+    #[test]
+    fn ko_check_private_ips() {
+        let policy = HttpPolicy::default();
+
+        for host in &[
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "[::1]",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            // multicast
+            "224.0.0.1",
+            // IETF protocol assignments
+            "192.0.0.8",
+            // benchmarking
+            "198.18.0.1",
+            "198.19.255.254",
+            // reserved
+            "240.0.0.1",
+            // IPv4-compatible
+            "::10.1.2.3",
+            // NAT64
+            "64:ff9b::169.254.169.254",
+            // 6to4
+            "2002:7f00:1::",
+            // site-local
+            "fec0::1",
+            // multicast
+            "ff02::1",
+        ] {
+            assert!(policy.check_host(host).is_err(), "{} is not blocked", host);
+        }
+        assert!(policy.check_host("93.184.216.34").is_ok());
+        assert!(policy.check_host("2606:2800:220:1:248:1893:25c8:1946").is_ok());
+        assert!(policy.check_host("64:ff9b::93.184.216.34").is_ok());
+        assert!(policy.check_host("2002:5db8:d822::1").is_ok());
+        assert!(policy.check_host("198.20.0.1").is_ok());
+
+        let policy = HttpPolicy {
+            allow_private_ips: true,
+            ..Default::default()
+        };
+        assert!(policy.check_host("127.0.0.1").is_ok());
+
+        let policy = HttpPolicy {
+            allow_hosts: Some(vec!["api.example.com".to_owned()]),
+            trusted_hosts: vec!["127.0.0.1".to_owned(), "functions.internal".to_owned()],
+            ..Default::default()
+        };
+        assert!(policy.check_host("127.0.0.1").is_ok());
+        assert!(policy.check_host("functions.internal").is_ok());
+        assert!(policy.check_host("10.1.2.3").is_err());
+    }
+
+    #[test]
+    fn ok_get_netloc_host() {
+        assert_eq!(get_netloc_host("example.com:443"), "example.com");
+        assert_eq!(get_netloc_host("[::1]:80"), "::1");
+        assert_eq!(get_netloc_host("127.0.0.1:8080"), "127.0.0.1");
+    }
+}
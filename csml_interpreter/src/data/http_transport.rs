@@ -0,0 +1,340 @@
+use crate::data::HttpPolicy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::Duration;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum HttpBody {
+    Empty,
+    Json(serde_json::Value),
+    Form(Vec<(String, String)>),
+    Raw(String),
+}
+
+/**
+ * Request built from an HTTP() object, once its url and query are resolved
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    // GET, POST, PUT, PATCH, DELETE
+    pub method: String,
+    pub url: String,
+    pub headers: HashMap<String, String>,
+    pub body: HttpBody,
+    pub timeout: Option<Duration>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    // lowercased names
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/**
+ * Sends the requests of HTTP() and App(). The interpreter uses UreqTransport unless
+ * another transport is set in the context, like a MockTransport in tests.
+ *
+ * The hosts and the number of requests are checked against the HTTP policy before
+ * the request is given to the transport, and the size of the response after.
+ */
+pub trait HttpTransport: Debug + Send + Sync {
+    fn send(&self, request: &HttpRequest, policy: Option<&HttpPolicy>) -> Result<HttpResponse, String>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UreqTransport;
+
+/**
+ * Canned response returned for the requests matching its method, url and body.
+ * Without a body, the fixture matches any body.
+ *
+ * {"method": "POST", "url": "https://api.example.com/orders", "body": {"id": 42}, "status": 201, "response": {"ok": true}}
+ */
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HttpFixture {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub body: Option<serde_json::Value>,
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    // JSON, or text if the content-type of the headers is not JSON
+    #[serde(default)]
+    pub response: serde_json::Value,
+}
+
+/**
+ * Transport answering with fixtures instead of sending the requests, so that bots calling
+ * APIs can be tested deterministically. Every request is recorded, and a request matching
+ * none of the fixtures fails.
+ */
+#[derive(Debug, Default)]
+pub struct MockTransport {
+    fixtures: Vec<HttpFixture>,
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn default_status() -> u16 {
+    200
+}
+
+fn too_large(max: u64) -> String {
+    format!("the response is larger than the {} bytes allowed by the HTTP policy of this bot", max)
+}
+
+/**
+ * Read the body of the response, up to max_bytes if there is a limit
+ */
+fn read_body(response: ureq::Response, max_bytes: Option<u64>) -> Result<Vec<u8>, String> {
+    let length = response
+        .header("content-length")
+        .and_then(|length| length.parse::<u64>().ok());
+
+    if let (Some(max), Some(length)) = (max_bytes, length) {
+        if length > max {
+            return Err(too_large(max));
+        }
+    }
+
+    let mut body = vec![];
+    response
+        .into_reader()
+        .take(max_bytes.map_or(u64::MAX, |max| max + 1))
+        .read_to_end(&mut body)
+        .map_err(|err| err.to_string())?;
+
+    Ok(body)
+}
+
+fn get_form_value(form: &[(String, String)]) -> serde_json::Value {
+    form.iter()
+        .map(|(key, value)| (key.to_owned(), serde_json::Value::String(value.to_owned())))
+        .collect::<serde_json::Map<String, serde_json::Value>>()
+        .into()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl HttpResponse {
+    pub fn check_size(&self, policy: Option<&HttpPolicy>) -> Result<(), String> {
+        match policy.and_then(|policy| policy.max_response_bytes) {
+            Some(max) if self.body.len() as u64 > max => Err(too_large(max)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        match self.headers.get("content-type") {
+            Some(content_type) => {
+                matches!(content_type.split(';').next(), Some(mime) if mime.trim().ends_with("json"))
+            }
+            None => false,
+        }
+    }
+}
+
+impl HttpTransport for UreqTransport {
+    fn send(&self, request: &HttpRequest, policy: Option<&HttpPolicy>) -> Result<HttpResponse, String> {
+        let mut agent = ureq::agent();
+        if let Some(policy) = policy {
+            agent.set_resolver(policy.clone());
+        }
+
+        let mut ureq_request = agent.request(&request.method, &request.url);
+        for (key, value) in request.headers.iter() {
+            ureq_request.set(key, value);
+        }
+        if let Some(timeout) = request.timeout {
+            ureq_request.timeout(timeout);
+        }
+
+        let response = match &request.body {
+            HttpBody::Empty => ureq_request.call(),
+            HttpBody::Json(body) => ureq_request.send_json(body.to_owned()),
+            HttpBody::Form(form) => {
+                let form: Vec<(&str, &str)> = form
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str()))
+                    .collect();
+
+                ureq_request.send_form(&form)
+            }
+            HttpBody::Raw(body) => ureq_request.send_string(body),
+        };
+
+        if let Some(err) = response.synthetic_error() {
+            return Err(err.body_text());
+        }
+
+        let status = response.status();
+        let mut headers = HashMap::new();
+        for name in response.headers_names() {
+            if let Some(value) = response.header(&name) {
+                headers.insert(name.to_lowercase(), value.to_owned());
+            }
+        }
+        let body = read_body(response, policy.and_then(|policy| policy.max_response_bytes))?;
+
+        Ok(HttpResponse { status, headers, body })
+    }
+}
+
+impl HttpFixture {
+    pub fn matches(&self, request: &HttpRequest) -> bool {
+        if !self.method.eq_ignore_ascii_case(&request.method) || self.url != request.url {
+            return false;
+        }
+
+        match (&self.body, &request.body) {
+            (None, _) => true,
+            (Some(expected), HttpBody::Json(body)) => expected == body,
+            (Some(expected), HttpBody::Form(form)) => *expected == get_form_value(form),
+            (Some(serde_json::Value::String(expected)), HttpBody::Raw(body)) => expected == body,
+            (Some(expected), HttpBody::Empty) => expected.is_null(),
+            _ => false,
+        }
+    }
+
+    pub fn get_response(&self) -> HttpResponse {
+        let mut headers: HashMap<String, String> = self
+            .headers
+            .iter()
+            .map(|(key, value)| (key.to_lowercase(), value.to_owned()))
+            .collect();
+
+        let body = match &self.response {
+            serde_json::Value::Null => vec![],
+            serde_json::Value::String(text) if headers.contains_key("content-type") => {
+                text.as_bytes().to_vec()
+            }
+            value => value.to_string().into_bytes(),
+        };
+        headers
+            .entry("content-type".to_owned())
+            .or_insert_with(|| "application/json".to_owned());
+
+        HttpResponse {
+            status: self.status,
+            headers,
+            body,
+        }
+    }
+}
+
+impl MockTransport {
+    pub fn new(fixtures: Vec<HttpFixture>) -> Self {
+        Self {
+            fixtures,
+            requests: Mutex::new(vec![]),
+        }
+    }
+
+    /**
+     * Fixtures from a JSON array, e.g. read from a file
+     */
+    pub fn from_json(fixtures: &str) -> Result<Self, String> {
+        serde_json::from_str(fixtures)
+            .map(Self::new)
+            .map_err(|err| format!("invalid HTTP fixtures: {}", err))
+    }
+
+    /**
+     * Every request received so far, matched or not
+     */
+    pub fn get_requests(&self) -> Vec<HttpRequest> {
+        self.requests.lock().unwrap().to_owned()
+    }
+}
+
+impl HttpTransport for MockTransport {
+    fn send(&self, request: &HttpRequest, _policy: Option<&HttpPolicy>) -> Result<HttpResponse, String> {
+        self.requests.lock().unwrap().push(request.to_owned());
+
+        match self.fixtures.iter().find(|fixture| fixture.matches(request)) {
+            Some(fixture) => Ok(fixture.get_response()),
+            None => Err(format!(
+                "no HTTP fixture matches the request {} {}",
+                request.method, request.url
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_request(method: &str, url: &str, body: HttpBody) -> HttpRequest {
+        HttpRequest {
+            method: method.to_owned(),
+            url: url.to_owned(),
+            headers: HashMap::new(),
+            body,
+            timeout: None,
+        }
+    }
+
+    #[test]
+    fn ok_mock_transport() {
+        let transport = MockTransport::from_json(
+            r#"[
+                {"method": "get", "url": "https://api.example.com/users?page=2", "response": {"users": []}},
+                {"method": "POST", "url": "https://api.example.com/users", "body": {"name": "bot"}, "status": 201, "response": {"id": 1}},
+                {"method": "POST", "url": "https://api.example.com/users", "status": 400, "headers": {"Content-Type": "text/plain"}, "response": "bad request"}
+            ]"#,
+        )
+        .unwrap();
+
+        let response = transport
+            .send(&get_request("GET", "https://api.example.com/users?page=2", HttpBody::Empty), None)
+            .unwrap();
+        assert_eq!((response.status, response.body), (200, br#"{"users":[]}"#.to_vec()));
+
+        let response = transport
+            .send(&get_request("POST", "https://api.example.com/users", HttpBody::Json(json!({"name": "bot"}))), None)
+            .unwrap();
+        assert_eq!((response.status, response.body), (201, br#"{"id":1}"#.to_vec()));
+
+        let response = transport
+            .send(&get_request("POST", "https://api.example.com/users", HttpBody::Raw("name=bot".to_owned())), None)
+            .unwrap();
+        assert!(!response.is_json());
+        assert_eq!((response.status, response.body), (400, b"bad request".to_vec()));
+
+        assert!(transport
+            .send(&get_request("GET", "https://api.example.com/users", HttpBody::Empty), None)
+            .is_err());
+        assert_eq!(transport.get_requests().len(), 4);
+    }
+
+    #[test]
+    fn ok_fixture_form_body() {
+        let fixture: HttpFixture = serde_json::from_value(json!({
+            "method": "POST", "url": "https://api.example.com/token", "body": {"grant_type": "password"}
+        }))
+        .unwrap();
+        let form = HttpBody::Form(vec![("grant_type".to_owned(), "password".to_owned())]);
+
+        assert!(fixture.matches(&get_request("POST", "https://api.example.com/token", form)));
+        assert!(!fixture.matches(&get_request("POST", "https://api.example.com/token", HttpBody::Empty)));
+    }
+}
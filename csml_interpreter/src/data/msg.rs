@@ -20,6 +20,9 @@ pub enum MSG {
         flow: Option<String>,
         step: Option<String>,
     },
+    Handoff {
+        reason: serde_json::Value,
+    },
     Error(Message),
 }
 
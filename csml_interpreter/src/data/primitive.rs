@@ -9,6 +9,8 @@ pub mod string;
 
 pub mod tools;
 pub mod tools_time;
+pub mod tools_number;
+pub mod tools_natural_time;
 pub mod tools_crypto;
 pub mod tools_jwt;
 
@@ -6,6 +6,7 @@ use crate::data::primitive::int::PrimitiveInt;
 use crate::data::primitive::object::PrimitiveObject;
 use crate::data::primitive::string::PrimitiveString;
 use crate::data::primitive::tools::check_division_by_zero_f64;
+use crate::data::primitive::tools_number;
 use crate::data::primitive::Right;
 use crate::data::primitive::{Primitive, PrimitiveType};
 use crate::data::{ast::Interval, message::Message, Data, Literal, MessageData, MSG};
@@ -80,6 +81,22 @@ lazy_static! {
             "to_float",
             (PrimitiveFloat::to_float as PrimitiveMethod, Right::Read),
         );
+        map.insert(
+            "format_number",
+            (PrimitiveFloat::format_number as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "format_currency",
+            (PrimitiveFloat::format_currency as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "format_percent",
+            (PrimitiveFloat::format_percent as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "plural",
+            (PrimitiveFloat::plural as PrimitiveMethod, Right::Read),
+        );
 
         map
     };
@@ -445,6 +462,42 @@ impl PrimitiveFloat {
 
         Ok(PrimitiveFloat::get_literal(float.value, interval))
     }
+
+    fn format_number(
+        float: &mut PrimitiveFloat,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_number(float.value, args, data, interval)
+    }
+
+    fn format_currency(
+        float: &mut PrimitiveFloat,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_currency(float.value, args, data, interval)
+    }
+
+    fn format_percent(
+        float: &mut PrimitiveFloat,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_percent(float.value, args, data, interval)
+    }
+
+    fn plural(
+        float: &mut PrimitiveFloat,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::plural(&float.value.to_string(), args, data, interval)
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
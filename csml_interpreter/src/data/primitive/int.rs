@@ -6,6 +6,7 @@ use crate::data::primitive::float::PrimitiveFloat;
 use crate::data::primitive::object::PrimitiveObject;
 use crate::data::primitive::string::PrimitiveString;
 use crate::data::primitive::tools::check_division_by_zero_i64;
+use crate::data::primitive::tools_number;
 use crate::data::primitive::Right;
 use crate::data::primitive::{Primitive, PrimitiveType};
 use crate::data::{ast::Interval, message::Message, Data, Literal, MessageData, MSG};
@@ -74,6 +75,22 @@ lazy_static! {
             "to_float",
             (PrimitiveInt::to_float as PrimitiveMethod, Right::Read),
         );
+        map.insert(
+            "format_number",
+            (PrimitiveInt::format_number as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "format_currency",
+            (PrimitiveInt::format_currency as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "format_percent",
+            (PrimitiveInt::format_percent as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "plural",
+            (PrimitiveInt::plural as PrimitiveMethod, Right::Read),
+        );
 
         map
     };
@@ -476,6 +493,42 @@ impl PrimitiveInt {
 
         Ok(PrimitiveFloat::get_literal(int.value as f64, interval))
     }
+
+    fn format_number(
+        int: &mut PrimitiveInt,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_number(int.value as f64, args, data, interval)
+    }
+
+    fn format_currency(
+        int: &mut PrimitiveInt,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_currency(int.value as f64, args, data, interval)
+    }
+
+    fn format_percent(
+        int: &mut PrimitiveInt,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::format_percent(int.value as f64, args, data, interval)
+    }
+
+    fn plural(
+        int: &mut PrimitiveInt,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_number::plural(&int.value.to_string(), args, data, interval)
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -5,23 +5,25 @@ use crate::data::{
     literal::ContentType,
     message::Message,
     primitive::{
-        tools_time, tools_crypto, tools_jwt, Data, MessageData, Primitive, PrimitiveArray, PrimitiveBoolean,
+        tools_time, tools_natural_time, tools_crypto, tools_jwt, Data, MessageData, Primitive, PrimitiveArray, PrimitiveBoolean,
         PrimitiveInt, PrimitiveNull, PrimitiveString, PrimitiveType, Right, MSG,
     },
     tokens::TYPES,
     Literal,
 };
 use crate::error_format::*;
+use crate::metrics;
 use crate::interpreter::{
-    builtins::http::http_request, json_to_rust::json_to_literal,
+    builtins::http::{http_request, HTTP_BUILTIN_DURATION, HTTP_BUILTIN_REQUESTS},
+    json_to_rust::json_to_literal,
     variable_handler::match_literals::match_obj,
 };
-use chrono::{DateTime, TimeZone, Utc, SecondsFormat};
+use chrono::{DateTime, TimeZone, Utc};
 use lazy_static::*;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
-use std::{collections::HashMap, sync::mpsc};
+use std::{collections::HashMap, sync::mpsc, time::Instant};
 
 ////////////////////////////////////////////////////////////////////////////////
 // DATA STRUCTURES
@@ -39,6 +41,26 @@ lazy_static! {
             "query",
             (PrimitiveObject::query as PrimitiveMethod, Right::Read),
         );
+        map.insert(
+            "timeout",
+            (PrimitiveObject::timeout as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "auth",
+            (PrimitiveObject::auth as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "form",
+            (PrimitiveObject::form as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "raw_body",
+            (PrimitiveObject::raw_body as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "full_response",
+            (PrimitiveObject::full_response as PrimitiveMethod, Right::Read),
+        );
         map.insert(
             "get",
             (PrimitiveObject::get_http as PrimitiveMethod, Right::Read),
@@ -92,6 +114,69 @@ lazy_static! {
             (PrimitiveObject::parse_date as PrimitiveMethod, Right::Read),
         );
 
+        map.insert(
+            "parse_natural",
+            (PrimitiveObject::parse_natural as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "with_timezone",
+            (PrimitiveObject::with_timezone as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "add_years",
+            (PrimitiveObject::add_years as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_months",
+            (PrimitiveObject::add_months as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_weeks",
+            (PrimitiveObject::add_weeks as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_days",
+            (PrimitiveObject::add_days as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_hours",
+            (PrimitiveObject::add_hours as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_minutes",
+            (PrimitiveObject::add_minutes as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_seconds",
+            (PrimitiveObject::add_seconds as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "add_milliseconds",
+            (PrimitiveObject::add_milliseconds as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "diff",
+            (PrimitiveObject::date_diff as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "start_of",
+            (PrimitiveObject::start_of as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "weekday",
+            (PrimitiveObject::weekday as PrimitiveMethod, Right::Read),
+        );
+
+        map.insert(
+            "iso_week",
+            (PrimitiveObject::iso_week as PrimitiveMethod, Right::Read),
+        );
+
         map
     };
 }
@@ -393,6 +478,217 @@ impl PrimitiveObject {
         Ok(result)
     }
 
+    fn timeout(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let timeout = match args.get("arg0") {
+            Some(literal) if args.len() == 1 => Literal::get_value::<i64>(
+                &literal.primitive,
+                &data.context.flow,
+                interval,
+                ERROR_HTTP_TIMEOUT.to_owned(),
+            )?,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    ERROR_HTTP_TIMEOUT.to_owned(),
+                ));
+            }
+        };
+
+        if *timeout <= 0 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                ERROR_HTTP_TIMEOUT.to_owned(),
+            ));
+        }
+
+        let mut object = object.to_owned();
+
+        object.value.insert(
+            "timeout".to_owned(),
+            PrimitiveInt::get_literal(*timeout, interval),
+        );
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn auth(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let mut values = vec![];
+        for index in 0..args.len() {
+            match args.get(&format!("arg{}", index)) {
+                Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveString => {
+                    values.push(literal.primitive.to_string())
+                }
+                _ => {
+                    return Err(gen_error_info(
+                        Position::new(interval, &data.context.flow,),
+                        ERROR_HTTP_AUTH.to_owned(),
+                    ));
+                }
+            }
+        }
+
+        let authorization = match values.as_slice() {
+            [token] => format!("Bearer {}", token),
+            [user, password] => {
+                format!("Basic {}", base64::encode(format!("{}:{}", user, password)))
+            }
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    ERROR_HTTP_AUTH.to_owned(),
+                ));
+            }
+        };
+
+        let mut object = object.to_owned();
+
+        set_http_header(&mut object, "authorization", &authorization, &data.context.flow, interval);
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn form(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let literal = match args.get("arg0") {
+            Some(literal) if args.len() == 1 => literal,
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    ERROR_HTTP_FORM.to_owned(),
+                ));
+            }
+        };
+
+        Literal::get_value::<HashMap<String, Literal>>(
+            &literal.primitive,
+            &data.context.flow,
+            interval,
+            ERROR_HTTP_FORM.to_owned(),
+        )?;
+
+        let mut object = object.to_owned();
+
+        object.value.remove("body");
+        object.value.remove("raw_body");
+        object.value.insert("form".to_owned(), literal.to_owned());
+        set_http_header(
+            &mut object,
+            "content-type",
+            "application/x-www-form-urlencoded",
+            &data.context.flow,
+            interval,
+        );
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn raw_body(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        let (body, content_type) = match (args.get("arg0"), args.get("arg1")) {
+            (Some(body), content_type) if args.len() <= 2 => (body, content_type),
+            _ => {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    ERROR_HTTP_RAW_BODY.to_owned(),
+                ));
+            }
+        };
+
+        if body.primitive.get_type() != PrimitiveType::PrimitiveString
+            || content_type.map_or(false, |content_type| {
+                content_type.primitive.get_type() != PrimitiveType::PrimitiveString
+            })
+        {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                ERROR_HTTP_RAW_BODY.to_owned(),
+            ));
+        }
+
+        let mut object = object.to_owned();
+
+        object.value.remove("body");
+        object.value.remove("form");
+        object.value.insert("raw_body".to_owned(), body.to_owned());
+        if let Some(content_type) = content_type {
+            set_http_header(
+                &mut object,
+                "content-type",
+                &content_type.primitive.to_string(),
+                &data.context.flow,
+                interval,
+            );
+        }
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
+    fn full_response(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        if !args.is_empty() {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                ERROR_HTTP_FULL_RESPONSE.to_owned(),
+            ));
+        }
+
+        let mut object = object.to_owned();
+
+        object.value.insert(
+            "full_response".to_owned(),
+            PrimitiveBoolean::get_literal(true, interval),
+        );
+
+        let mut result = PrimitiveObject::get_literal(&object.value, interval);
+
+        result.set_content_type("http");
+
+        Ok(result)
+    }
+
     fn get_http(
         object: &mut PrimitiveObject,
         args: &HashMap<String, Literal>,
@@ -417,6 +713,8 @@ impl PrimitiveObject {
         );
 
         object.value.remove("body");
+        object.value.remove("form");
+        object.value.remove("raw_body");
 
         let mut result = PrimitiveObject::get_literal(&object.value, interval);
 
@@ -433,7 +731,11 @@ impl PrimitiveObject {
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
         match args.get("arg0") {
-            Some(body) => object.value.insert("body".to_owned(), body.to_owned()),
+            Some(body) => {
+                object.value.remove("form");
+                object.value.remove("raw_body");
+                object.value.insert("body".to_owned(), body.to_owned())
+            }
             _ => object.value.remove("body"),
         };
 
@@ -459,7 +761,11 @@ impl PrimitiveObject {
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
         match args.get("arg0") {
-            Some(body) => object.value.insert("body".to_owned(), body.to_owned()),
+            Some(body) => {
+                object.value.remove("form");
+                object.value.remove("raw_body");
+                object.value.insert("body".to_owned(), body.to_owned())
+            }
             _ => object.value.remove("body"),
         };
 
@@ -485,7 +791,11 @@ impl PrimitiveObject {
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
         match args.get("arg0") {
-            Some(body) => object.value.insert("body".to_owned(), body.to_owned()),
+            Some(body) => {
+                object.value.remove("form");
+                object.value.remove("raw_body");
+                object.value.insert("body".to_owned(), body.to_owned())
+            }
             _ => object.value.remove("body"),
         };
 
@@ -511,7 +821,11 @@ impl PrimitiveObject {
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
         let body = match args.get("arg0") {
-            Some(res) => res.to_owned(),
+            Some(res) => {
+                object.value.remove("form");
+                object.value.remove("raw_body");
+                res.to_owned()
+            }
             _ => PrimitiveNull::get_literal(Interval::default()),
         };
 
@@ -548,17 +862,17 @@ impl PrimitiveObject {
         }
 
         if let Some(literal) = object.value.get("method") {
-            let function = match Literal::get_value::<String>(
+            let (method, verb) = match Literal::get_value::<String>(
                 &literal.primitive,
                 &data.context.flow,
                 interval,
                 ERROR_HTTP_UNKNOWN_METHOD.to_string(),
             ) {
-                Ok(delete) if delete == "delete" => ureq::delete,
-                Ok(put) if put == "put" => ureq::put,
-                Ok(patch) if patch == "patch" => ureq::patch,
-                Ok(post) if post == "post" => ureq::post,
-                Ok(get) if get == "get" => ureq::get,
+                Ok(delete) if delete == "delete" => ("delete", "DELETE"),
+                Ok(put) if put == "put" => ("put", "PUT"),
+                Ok(patch) if patch == "patch" => ("patch", "PATCH"),
+                Ok(post) if post == "post" => ("post", "POST"),
+                Ok(get) if get == "get" => ("get", "GET"),
                 _ => {
                     return Err(gen_error_info(
                         Position::new(interval, &data.context.flow,),
@@ -567,8 +881,15 @@ impl PrimitiveObject {
                 }
             };
 
-            let value = http_request(&object.value, function, &data.context.flow, interval)?;
-            return json_to_literal(&value, interval, &data.context.flow,);
+            let _span = tracing::info_span!("http_request", method).entered();
+            let start = Instant::now();
+            let value = http_request(&object.value, verb, &data.context, interval);
+
+            let status = if value.is_ok() { "success" } else { "error" };
+            metrics::inc_counter(&HTTP_BUILTIN_REQUESTS, &[("method", method), ("status", status)], 1.0);
+            metrics::observe_since(&HTTP_BUILTIN_DURATION, &[("method", method)], start);
+
+            return json_to_literal(&value?, interval, &data.context.flow,);
         }
 
         Err(gen_error_info(
@@ -582,22 +903,24 @@ impl PrimitiveObject {
     fn set_date_at(
         object: &mut PrimitiveObject,
         args: &HashMap<String, Literal>,
-        _data: &mut Data,
+        data: &mut Data,
         interval: Interval,
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
         let date = tools_time::get_date(args);
+        // the date is a local date in the timezone of the time object
+        let tz = tools_time::get_timezone(&object.value, data, interval)?;
 
-        let date = Utc.ymd(
+        let date = tz.ymd_opt(
             date[0] as i32, // year
             date[1] as u32, // month
             date[2] as u32 // day
-        ).and_hms_milli_opt(
+        ).single().and_then(|day| day.and_hms_milli_opt(
             date[3] as u32, // hour
             date[4] as u32, // min
             date[5] as u32, // sec
             date[6] as u32, // milli
-        );
+        ));
 
         match date {
             Some(date) => {
@@ -667,6 +990,16 @@ impl PrimitiveObject {
         }
     }
 
+    fn parse_natural(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_natural_time::parse_natural(&object.value, args, data, interval)
+    }
+
     fn date_format(
         object: &mut PrimitiveObject,
         args: &HashMap<String, Literal>,
@@ -674,48 +1007,137 @@ impl PrimitiveObject {
         interval: Interval,
         _content_type: &str,
     ) -> Result<Literal, ErrorInfo> {
-        let usage = "Time().format(format: String)";
+        tools_time::format_date(&object.value, args, data, interval)
+    }
 
-        let date: DateTime<Utc> = match object.value.get("milliseconds") {
-            Some(lit) if lit.primitive.get_type() == PrimitiveType::PrimitiveInt => {
-                let millis = Literal::get_value::<i64>(
-                    &lit.primitive,
-                    &data.context.flow,
-                    interval,
-                    "".to_string(),
-                )?;
+    fn with_timezone(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::with_timezone(&object.value, args, data, interval)
+    }
 
-                Utc.timestamp_millis(*millis)
-            },
-            _ => {
-                return Err(gen_error_info(
-                    Position::new(interval, &data.context.flow,),
-                    format!("usage: {}", usage),
-                ))
-            }
-        };
+    fn add_years(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "years")
+    }
 
-        let formatted_date = match args.len() {
-            0 => {
-                date.to_rfc3339_opts(SecondsFormat::Millis, true)
-            }
-            _ => {
-                let format_lit = match args.get("arg0") {
-                    Some(res) => res.to_owned(),
-                    _ => PrimitiveNull::get_literal(Interval::default()),
-                };
+    fn add_months(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "months")
+    }
 
-                let format = Literal::get_value::<String>(
-                    &format_lit.primitive,
-                    &data.context.flow,
-                    interval,
-                    "format parameter must be of type string".to_string(),
-                )?;
-                date.format(format).to_string()
-            }
-        };
+    fn add_weeks(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "weeks")
+    }
+
+    fn add_days(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "days")
+    }
+
+    fn add_hours(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "hours")
+    }
+
+    fn add_minutes(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "minutes")
+    }
+
+    fn add_seconds(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "seconds")
+    }
+
+    fn add_milliseconds(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::add_duration(&object.value, args, data, interval, "milliseconds")
+    }
+
+    fn date_diff(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::diff(&object.value, args, data, interval)
+    }
 
-        Ok(PrimitiveString::get_literal(&formatted_date, interval))
+    fn start_of(
+        object: &mut PrimitiveObject,
+        args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::start_of(&object.value, args, data, interval)
+    }
+
+    fn weekday(
+        object: &mut PrimitiveObject,
+        _args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::weekday(&object.value, data, interval)
+    }
+
+    fn iso_week(
+        object: &mut PrimitiveObject,
+        _args: &HashMap<String, Literal>,
+        data: &mut Data,
+        interval: Interval,
+        _content_type: &str,
+    ) -> Result<Literal, ErrorInfo> {
+        tools_time::iso_week(&object.value, data, interval)
     }
 }
 
@@ -1777,6 +2199,20 @@ impl PrimitiveObject {
 // PRIVATE FUNCTION
 ////////////////////////////////////////////////////////////////////////////////
 
+fn set_http_header(
+    object: &mut PrimitiveObject,
+    key: &str,
+    value: &str,
+    flow_name: &str,
+    interval: Interval,
+) {
+    let mut header = HashMap::new();
+    header.insert(key.to_owned(), PrimitiveString::get_literal(value, interval));
+    let literal = PrimitiveObject::get_literal(&header, interval);
+
+    insert_to_object(&header, object, "header", flow_name, &literal);
+}
+
 fn insert_to_object(
     src: &HashMap<String, Literal>,
     dst: &mut PrimitiveObject,
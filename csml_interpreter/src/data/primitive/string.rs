@@ -23,6 +23,11 @@ use std::{collections::HashMap, sync::mpsc};
 // DATA STRUCTURES
 ////////////////////////////////////////////////////////////////////////////////
 
+// longest string made by pad_start and pad_end, so that a bot can not exhaust the memory
+const MAX_PAD_LENGTH: i64 = 10_000;
+// longest string made by replace_all and replace_regex, unless the string itself is longer
+const MAX_REPLACE_LENGTH: usize = 100_000;
+
 type PrimitiveMethod = fn(
     string: &mut PrimitiveString,
     args: &HashMap<String, Literal>,
@@ -147,6 +152,54 @@ lazy_static! {
             "split",
             (PrimitiveString::split as PrimitiveMethod, Right::Read),
         );
+        map.insert(
+            "capture_regex",
+            (
+                PrimitiveString::capture_regex as PrimitiveMethod,
+                Right::Read,
+            ),
+        );
+        map.insert(
+            "find_all_regex",
+            (
+                PrimitiveString::find_all_regex as PrimitiveMethod,
+                Right::Read,
+            ),
+        );
+        map.insert(
+            "replace",
+            (PrimitiveString::replace as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "replace_all",
+            (PrimitiveString::replace_all as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "replace_regex",
+            (
+                PrimitiveString::replace_regex as PrimitiveMethod,
+                Right::Read,
+            ),
+        );
+        map.insert(
+            "trim",
+            (PrimitiveString::trim as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "pad_start",
+            (PrimitiveString::pad_start as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "pad_end",
+            (PrimitiveString::pad_end as PrimitiveMethod, Right::Read),
+        );
+        map.insert(
+            "split_regex",
+            (
+                PrimitiveString::split_regex as PrimitiveMethod,
+                Right::Read,
+            ),
+        );
 
         map.insert(
             "abs",
@@ -202,6 +255,134 @@ pub struct PrimitiveString {
     pub value: String,
 }
 
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_string_arg<'a>(
+    args: &'a HashMap<String, Literal>,
+    name: &str,
+    interval: Interval,
+    data: &Data,
+    error: &str,
+) -> Result<&'a String, ErrorInfo> {
+    match args.get(name) {
+        Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            Literal::get_value::<String>(
+                &res.primitive,
+                &data.context.flow,
+                interval,
+                error.to_owned(),
+            )
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            error.to_owned(),
+        )),
+    }
+}
+
+fn get_max_replace_length(string: &str) -> usize {
+    MAX_REPLACE_LENGTH.max(string.len())
+}
+
+fn get_regex_arg(
+    args: &HashMap<String, Literal>,
+    interval: Interval,
+    data: &Data,
+    error: &str,
+) -> Result<Regex, ErrorInfo> {
+    let value = get_string_arg(args, "arg0", interval, data, error)?;
+
+    Regex::new(value).map_err(|_| {
+        gen_error_info(
+            Position::new(interval, &data.context.flow),
+            ERROR_STRING_VALID_REGEX.to_owned(),
+        )
+    })
+}
+
+/**
+ * {"match": String, "groups": [String | Null], "named": {name: String | Null}}
+ * groups are the positional groups from 1, unmatched optional groups are null
+ */
+fn get_captures_literal(regex: &Regex, captures: &regex::Captures, interval: Interval) -> Literal {
+    let get_group = |group: Option<regex::Match>| match group {
+        Some(group) => PrimitiveString::get_literal(group.as_str(), interval),
+        None => PrimitiveNull::get_literal(interval),
+    };
+
+    let groups: Vec<Literal> = captures.iter().skip(1).map(get_group).collect();
+
+    let mut named = HashMap::new();
+    for name in regex.capture_names().flatten() {
+        named.insert(name.to_owned(), get_group(captures.name(name)));
+    }
+
+    let mut object = HashMap::new();
+    object.insert("match".to_owned(), get_group(captures.get(0)));
+    object.insert("groups".to_owned(), PrimitiveArray::get_literal(&groups, interval));
+    object.insert("named".to_owned(), PrimitiveObject::get_literal(&named, interval));
+
+    PrimitiveObject::get_literal(&object, interval)
+}
+
+/**
+ * Pad to the given length in chars, repeating the pad string like String.padStart in JS
+ */
+fn get_padding(
+    string: &PrimitiveString,
+    args: &HashMap<String, Literal>,
+    interval: Interval,
+    data: &Data,
+    usage: &str,
+) -> Result<String, ErrorInfo> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!("usage: {}", usage),
+        ));
+    }
+
+    let length = match args.get("arg0") {
+        Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveInt => {
+            *Literal::get_value::<i64>(
+                &res.primitive,
+                &data.context.flow,
+                interval,
+                ERROR_STRING_PAD.to_owned(),
+            )?
+        }
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_STRING_PAD.to_owned(),
+            ))
+        }
+    };
+    if length > MAX_PAD_LENGTH {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            ERROR_STRING_PAD.to_owned(),
+        ));
+    }
+
+    let pad = match args.get("arg1") {
+        Some(_) => get_string_arg(args, "arg1", interval, data, ERROR_STRING_PAD)?.to_owned(),
+        None => " ".to_owned(),
+    };
+    if pad.is_empty() {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            ERROR_STRING_PAD.to_owned(),
+        ));
+    }
+
+    let missing = (length.max(0) as usize).saturating_sub(string.value.chars().count());
+
+    Ok(pad.chars().cycle().take(missing).collect())
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // METHOD FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
@@ -1064,6 +1245,255 @@ impl PrimitiveString {
 
         Ok(PrimitiveArray::get_literal(&vector, interval))
     }
+
+    fn capture_regex(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "capture_regex(value: string) => object";
+
+        if args.len() != 1 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let regex = get_regex_arg(args, interval, data, ERROR_STRING_CAPTURE_REGEX)?;
+
+        match regex.captures(&string.value) {
+            Some(captures) => Ok(get_captures_literal(&regex, &captures, interval)),
+            None => Ok(PrimitiveNull::get_literal(interval)),
+        }
+    }
+
+    fn find_all_regex(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "find_all_regex(value: string) => array";
+
+        if args.len() != 1 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let regex = get_regex_arg(args, interval, data, ERROR_STRING_FIND_ALL_REGEX)?;
+
+        let vector: Vec<Literal> = regex
+            .captures_iter(&string.value)
+            .map(|captures| get_captures_literal(&regex, &captures, interval))
+            .collect();
+
+        Ok(PrimitiveArray::get_literal(&vector, interval))
+    }
+
+    fn replace(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "replace(old: string, new: string) => string";
+
+        if args.len() != 2 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let old = get_string_arg(args, "arg0", interval, data, ERROR_STRING_REPLACE)?;
+        let new = get_string_arg(args, "arg1", interval, data, ERROR_STRING_REPLACE)?;
+        if old.is_empty() {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_STRING_REPLACE.to_owned(),
+            ));
+        }
+
+        let result = string.value.replacen(old.as_str(), new, 1);
+
+        Ok(PrimitiveString::get_literal(&result, interval))
+    }
+
+    fn replace_all(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "replace_all(old: string, new: string) => string";
+
+        if args.len() != 2 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let old = get_string_arg(args, "arg0", interval, data, ERROR_STRING_REPLACE_ALL)?;
+        let new = get_string_arg(args, "arg1", interval, data, ERROR_STRING_REPLACE_ALL)?;
+        if old.is_empty() {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_STRING_REPLACE_ALL.to_owned(),
+            ));
+        }
+
+        if new.len() > old.len() {
+            let count = string.value.matches(old.as_str()).count();
+            let length = string.value.len() + count * (new.len() - old.len());
+
+            if length > get_max_replace_length(&string.value) {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    ERROR_STRING_REPLACE_LENGTH.to_owned(),
+                ));
+            }
+        }
+
+        let result = string.value.replace(old.as_str(), new);
+
+        Ok(PrimitiveString::get_literal(&result, interval))
+    }
+
+    fn replace_regex(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "replace_regex(regex: string, replacement: string) => string";
+
+        if args.len() != 2 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let regex = get_regex_arg(args, interval, data, ERROR_STRING_REPLACE_REGEX)?;
+        // the replacement can use the groups of the match: $1, ${name}
+        let replacement = get_string_arg(args, "arg1", interval, data, ERROR_STRING_REPLACE_REGEX)?;
+
+        let max_length = get_max_replace_length(&string.value);
+        let mut result = String::new();
+        let mut last = 0;
+
+        for captures in regex.captures_iter(&string.value) {
+            let found = captures.get(0).expect("group 0 is always the whole match");
+
+            result.push_str(&string.value[last..found.start()]);
+            captures.expand(replacement, &mut result);
+            last = found.end();
+
+            if result.len() > max_length {
+                return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    ERROR_STRING_REPLACE_LENGTH.to_owned(),
+                ));
+            }
+        }
+        result.push_str(&string.value[last..]);
+
+        Ok(PrimitiveString::get_literal(&result, interval))
+    }
+
+    fn trim(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "trim() => string";
+
+        if !args.is_empty() {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        Ok(PrimitiveString::get_literal(string.value.trim(), interval))
+    }
+
+    fn pad_start(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "pad_start(length: int, pad: Optional<string>) => string";
+
+        let mut result = get_padding(string, args, interval, data, usage)?;
+        result.push_str(&string.value);
+
+        Ok(PrimitiveString::get_literal(&result, interval))
+    }
+
+    fn pad_end(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "pad_end(length: int, pad: Optional<string>) => string";
+
+        let mut result = string.value.to_owned();
+        result.push_str(&get_padding(string, args, interval, data, usage)?);
+
+        Ok(PrimitiveString::get_literal(&result, interval))
+    }
+
+    fn split_regex(
+        string: &mut PrimitiveString,
+        args: &HashMap<String, Literal>,
+        interval: Interval,
+        data: &mut Data,
+        _msg_data: &mut MessageData,
+        _sender: &Option<mpsc::Sender<MSG>>,
+    ) -> Result<Literal, ErrorInfo> {
+        let usage = "split_regex(separator: string) => array";
+
+        if args.len() != 1 {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow,),
+                format!("usage: {}", usage),
+            ));
+        }
+
+        let regex = get_regex_arg(args, interval, data, ERROR_STRING_SPLIT_REGEX)?;
+
+        let vector: Vec<Literal> = regex
+            .split(&string.value)
+            .map(|result| PrimitiveString::get_literal(result, interval))
+            .collect();
+
+        Ok(PrimitiveArray::get_literal(&vector, interval))
+    }
 }
 
 impl PrimitiveString {
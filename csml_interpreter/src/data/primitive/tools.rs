@@ -67,3 +67,23 @@ pub fn check_division_by_zero_f64(lhs: f64, rhs: f64) -> Result<f64, String> {
 
     Ok(lhs)
 }
+
+/**
+ * Language of a locale, lowercased: fr, fr-FR and fr_FR are the same language
+ */
+pub fn get_language(locale: &str) -> String {
+    locale.split(['-', '_']).next().unwrap_or_default().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_get_language() {
+        assert_eq!(get_language("fr"), "fr");
+        assert_eq!(get_language("fr-FR"), "fr");
+        assert_eq!(get_language("FR_fr"), "fr");
+        assert_eq!(get_language(""), "");
+    }
+}
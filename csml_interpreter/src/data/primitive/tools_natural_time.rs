@@ -0,0 +1,845 @@
+use crate::error_format::*;
+use crate::data::{
+    Literal, primitive::PrimitiveType,
+    ast::Interval,
+    primitive::{
+        tools::get_language, tools_time, Data, PrimitiveObject, PrimitiveString
+    },
+    error_info::ErrorInfo,
+    position::Position
+};
+use std::{collections::HashMap};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Modifier {
+    Next,
+    Last,
+    This,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Word {
+    Filler,
+    At,
+    Now,
+    // days from today: tomorrow is 1, yesterday is -1
+    Day(i64),
+    // 0 is Monday
+    Weekday(u32),
+    // 1 is January
+    Month(u32),
+    // units are the ones of tools_time::add_to_date
+    Unit(&'static str),
+    Number(i64),
+    Modifier(Modifier),
+    In,
+    Ago,
+    AgoPrefix,
+    Noon,
+    Midnight,
+    Am,
+    Pm,
+}
+
+struct Lang {
+    words: &'static [(&'static str, Word)],
+    // 01/02 is the 1st of February if true, January 2 otherwise
+    day_first: bool,
+}
+
+const WORDS_EN: &[(&str, Word)] = &[
+    ("the", Word::Filler),
+    ("on", Word::Filler),
+    ("of", Word::Filler),
+    ("and", Word::Filler),
+    ("at", Word::At),
+    ("now", Word::Now),
+    ("right now", Word::Now),
+    ("today", Word::Day(0)),
+    ("tomorrow", Word::Day(1)),
+    ("day after tomorrow", Word::Day(2)),
+    ("yesterday", Word::Day(-1)),
+    ("day before yesterday", Word::Day(-2)),
+    ("monday", Word::Weekday(0)),
+    ("mon", Word::Weekday(0)),
+    ("tuesday", Word::Weekday(1)),
+    ("tue", Word::Weekday(1)),
+    ("tues", Word::Weekday(1)),
+    ("wednesday", Word::Weekday(2)),
+    ("wed", Word::Weekday(2)),
+    ("thursday", Word::Weekday(3)),
+    ("thu", Word::Weekday(3)),
+    ("thurs", Word::Weekday(3)),
+    ("friday", Word::Weekday(4)),
+    ("fri", Word::Weekday(4)),
+    ("saturday", Word::Weekday(5)),
+    ("sat", Word::Weekday(5)),
+    ("sunday", Word::Weekday(6)),
+    ("sun", Word::Weekday(6)),
+    ("january", Word::Month(1)),
+    ("jan", Word::Month(1)),
+    ("february", Word::Month(2)),
+    ("feb", Word::Month(2)),
+    ("march", Word::Month(3)),
+    ("mar", Word::Month(3)),
+    ("april", Word::Month(4)),
+    ("apr", Word::Month(4)),
+    ("may", Word::Month(5)),
+    ("june", Word::Month(6)),
+    ("jun", Word::Month(6)),
+    ("july", Word::Month(7)),
+    ("jul", Word::Month(7)),
+    ("august", Word::Month(8)),
+    ("aug", Word::Month(8)),
+    ("september", Word::Month(9)),
+    ("sep", Word::Month(9)),
+    ("sept", Word::Month(9)),
+    ("october", Word::Month(10)),
+    ("oct", Word::Month(10)),
+    ("november", Word::Month(11)),
+    ("nov", Word::Month(11)),
+    ("december", Word::Month(12)),
+    ("dec", Word::Month(12)),
+    ("year", Word::Unit("years")),
+    ("years", Word::Unit("years")),
+    ("month", Word::Unit("months")),
+    ("months", Word::Unit("months")),
+    ("week", Word::Unit("weeks")),
+    ("weeks", Word::Unit("weeks")),
+    ("day", Word::Unit("days")),
+    ("days", Word::Unit("days")),
+    ("hour", Word::Unit("hours")),
+    ("hours", Word::Unit("hours")),
+    ("hr", Word::Unit("hours")),
+    ("hrs", Word::Unit("hours")),
+    ("h", Word::Unit("hours")),
+    ("minute", Word::Unit("minutes")),
+    ("minutes", Word::Unit("minutes")),
+    ("min", Word::Unit("minutes")),
+    ("mins", Word::Unit("minutes")),
+    ("second", Word::Unit("seconds")),
+    ("seconds", Word::Unit("seconds")),
+    ("sec", Word::Unit("seconds")),
+    ("secs", Word::Unit("seconds")),
+    ("a", Word::Number(1)),
+    ("an", Word::Number(1)),
+    ("one", Word::Number(1)),
+    ("two", Word::Number(2)),
+    ("three", Word::Number(3)),
+    ("four", Word::Number(4)),
+    ("five", Word::Number(5)),
+    ("six", Word::Number(6)),
+    ("seven", Word::Number(7)),
+    ("eight", Word::Number(8)),
+    ("nine", Word::Number(9)),
+    ("ten", Word::Number(10)),
+    ("eleven", Word::Number(11)),
+    ("twelve", Word::Number(12)),
+    ("next", Word::Modifier(Modifier::Next)),
+    ("coming", Word::Modifier(Modifier::Next)),
+    ("last", Word::Modifier(Modifier::Last)),
+    ("previous", Word::Modifier(Modifier::Last)),
+    ("this", Word::Modifier(Modifier::This)),
+    ("in", Word::In),
+    ("from now", Word::In),
+    ("later", Word::In),
+    ("ago", Word::Ago),
+    ("noon", Word::Noon),
+    ("midday", Word::Noon),
+    ("midnight", Word::Midnight),
+    ("am", Word::Am),
+    ("in the morning", Word::Am),
+    ("pm", Word::Pm),
+    ("in the afternoon", Word::Pm),
+    ("in the evening", Word::Pm),
+];
+
+const WORDS_FR: &[(&str, Word)] = &[
+    ("le", Word::Filler),
+    ("la", Word::Filler),
+    ("l", Word::Filler),
+    ("de", Word::Filler),
+    ("du", Word::Filler),
+    ("et", Word::Filler),
+    ("a", Word::At),
+    ("vers", Word::At),
+    ("maintenant", Word::Now),
+    ("tout de suite", Word::Now),
+    ("aujourd hui", Word::Day(0)),
+    ("demain", Word::Day(1)),
+    ("apres demain", Word::Day(2)),
+    ("hier", Word::Day(-1)),
+    ("avant hier", Word::Day(-2)),
+    ("lundi", Word::Weekday(0)),
+    ("lun", Word::Weekday(0)),
+    ("mardi", Word::Weekday(1)),
+    ("mar", Word::Weekday(1)),
+    ("mercredi", Word::Weekday(2)),
+    ("mer", Word::Weekday(2)),
+    ("jeudi", Word::Weekday(3)),
+    ("jeu", Word::Weekday(3)),
+    ("vendredi", Word::Weekday(4)),
+    ("ven", Word::Weekday(4)),
+    ("samedi", Word::Weekday(5)),
+    ("sam", Word::Weekday(5)),
+    ("dimanche", Word::Weekday(6)),
+    ("dim", Word::Weekday(6)),
+    ("janvier", Word::Month(1)),
+    ("janv", Word::Month(1)),
+    ("fevrier", Word::Month(2)),
+    ("fevr", Word::Month(2)),
+    ("mars", Word::Month(3)),
+    ("avril", Word::Month(4)),
+    ("avr", Word::Month(4)),
+    ("mai", Word::Month(5)),
+    ("juin", Word::Month(6)),
+    ("juillet", Word::Month(7)),
+    ("juil", Word::Month(7)),
+    ("aout", Word::Month(8)),
+    ("septembre", Word::Month(9)),
+    ("octobre", Word::Month(10)),
+    ("oct", Word::Month(10)),
+    ("novembre", Word::Month(11)),
+    ("nov", Word::Month(11)),
+    ("decembre", Word::Month(12)),
+    ("dec", Word::Month(12)),
+    ("an", Word::Unit("years")),
+    ("ans", Word::Unit("years")),
+    ("annee", Word::Unit("years")),
+    ("annees", Word::Unit("years")),
+    ("mois", Word::Unit("months")),
+    ("semaine", Word::Unit("weeks")),
+    ("semaines", Word::Unit("weeks")),
+    ("jour", Word::Unit("days")),
+    ("jours", Word::Unit("days")),
+    ("heure", Word::Unit("hours")),
+    ("heures", Word::Unit("hours")),
+    ("h", Word::Unit("hours")),
+    ("minute", Word::Unit("minutes")),
+    ("minutes", Word::Unit("minutes")),
+    ("min", Word::Unit("minutes")),
+    ("seconde", Word::Unit("seconds")),
+    ("secondes", Word::Unit("seconds")),
+    ("sec", Word::Unit("seconds")),
+    ("un", Word::Number(1)),
+    ("une", Word::Number(1)),
+    ("deux", Word::Number(2)),
+    ("trois", Word::Number(3)),
+    ("quatre", Word::Number(4)),
+    ("cinq", Word::Number(5)),
+    ("six", Word::Number(6)),
+    ("sept", Word::Number(7)),
+    ("huit", Word::Number(8)),
+    ("neuf", Word::Number(9)),
+    ("dix", Word::Number(10)),
+    ("onze", Word::Number(11)),
+    ("douze", Word::Number(12)),
+    ("prochain", Word::Modifier(Modifier::Next)),
+    ("prochaine", Word::Modifier(Modifier::Next)),
+    ("suivant", Word::Modifier(Modifier::Next)),
+    ("suivante", Word::Modifier(Modifier::Next)),
+    ("dernier", Word::Modifier(Modifier::Last)),
+    ("derniere", Word::Modifier(Modifier::Last)),
+    ("passe", Word::Modifier(Modifier::Last)),
+    ("passee", Word::Modifier(Modifier::Last)),
+    ("ce", Word::Modifier(Modifier::This)),
+    ("cet", Word::Modifier(Modifier::This)),
+    ("cette", Word::Modifier(Modifier::This)),
+    ("dans", Word::In),
+    ("plus tard", Word::In),
+    ("il y a", Word::AgoPrefix),
+    ("midi", Word::Noon),
+    ("minuit", Word::Midnight),
+    ("du matin", Word::Am),
+    ("apres midi", Word::Pm),
+    ("l apres midi", Word::Pm),
+    ("de l apres midi", Word::Pm),
+    ("du soir", Word::Pm),
+];
+
+const LANGS: &[(&str, Lang)] = &[
+    ("en", Lang { words: WORDS_EN, day_first: false }),
+    ("fr", Lang { words: WORDS_FR, day_first: true }),
+];
+
+/**
+ * What the user said, before it is resolved against the reference time
+ */
+#[derive(Debug, Default)]
+struct Expression {
+    matched: bool,
+    day: Option<i64>,
+    weekday: Option<(u32, Option<Modifier>)>,
+    period: Option<(&'static str, Modifier)>,
+    // year, month, day
+    date: Option<(Option<i32>, u32, u32)>,
+    // hour, minute
+    time: Option<(u32, u32)>,
+    pm: Option<bool>,
+    durations: Vec<(i64, &'static str)>,
+    direction: Option<i64>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_lang(lang: &str) -> Option<&'static Lang> {
+    let language = get_language(lang);
+
+    LANGS
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|(_, lang)| lang)
+}
+
+/**
+ * Lowercase words without accents nor punctuation, "Après-demain à 15h !" is
+ * ["apres", "demain", "a", "15h"]. Dates and times such as 2021-08-13 or 15:30 are kept whole.
+ */
+fn tokenize(text: &str) -> Vec<String> {
+    let mut normalized = String::new();
+    for c in text.to_lowercase().chars() {
+        match c {
+            'à' | 'â' | 'ä' => normalized.push('a'),
+            'é' | 'è' | 'ê' | 'ë' => normalized.push('e'),
+            'î' | 'ï' => normalized.push('i'),
+            'ô' | 'ö' => normalized.push('o'),
+            'ù' | 'û' | 'ü' => normalized.push('u'),
+            'ç' => normalized.push('c'),
+            '\'' | '’' | ',' | '!' | '?' | ';' => normalized.push(' '),
+            c => normalized.push(c),
+        }
+    }
+
+    normalized
+        .split_whitespace()
+        .flat_map(|token| {
+            let token = token.trim_end_matches('.');
+            if token.chars().any(|c| c.is_ascii_digit()) {
+                vec![token.to_owned()]
+            } else {
+                token
+                    .split('-')
+                    .filter(|word| !word.is_empty())
+                    .map(|word| word.to_owned())
+                    .collect()
+            }
+        })
+        .filter(|token| !token.is_empty())
+        .collect()
+}
+
+/**
+ * The longest phrase of the language starting at tokens[index], with its number of tokens
+ */
+fn match_word(lang: &Lang, tokens: &[String], index: usize) -> Option<(Word, usize)> {
+    let mut found: Option<(Word, usize)> = None;
+
+    for (phrase, word) in lang.words.iter() {
+        let words: Vec<&str> = phrase.split(' ').collect();
+        let len = words.len();
+        if index + len > tokens.len() || matches!(found, Some((_, found_len)) if found_len >= len) {
+            continue;
+        }
+
+        if words.iter().zip(tokens[index..index + len].iter()).all(|(a, b)| a == b) {
+            found = Some((*word, len));
+        }
+    }
+
+    found
+}
+
+/**
+ * 13, 13th, 1st, 1er
+ */
+fn parse_number(token: &str) -> Option<i64> {
+    let digits = ["st", "nd", "rd", "th", "er", "e"]
+        .iter()
+        .find_map(|suffix| token.strip_suffix(suffix))
+        .unwrap_or(token);
+
+    if digits.is_empty() || digits.len() > 9 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    digits.parse::<i64>().ok()
+}
+
+/**
+ * 15h, 15h30, 15:30, 3pm, 3:30am
+ */
+fn parse_clock(token: &str) -> Option<((u32, u32), Option<bool>)> {
+    let (token, pm) = match token {
+        token if token.ends_with("am") => (&token[..token.len() - 2], Some(false)),
+        token if token.ends_with("pm") => (&token[..token.len() - 2], Some(true)),
+        token => (token, None),
+    };
+
+    let (hour, minute) = match token.find(&['h', ':'][..]) {
+        Some(index) => (&token[..index], &token[index + 1..]),
+        None if pm.is_some() => (token, ""),
+        None => return None,
+    };
+
+    let all_digits = |value: &str| value.chars().all(|c| c.is_ascii_digit());
+    if hour.is_empty() || hour.len() > 2 || minute.len() > 2 || !all_digits(hour) || !all_digits(minute) {
+        return None;
+    }
+    // 15: is not a time
+    if minute.is_empty() && token.ends_with(':') {
+        return None;
+    }
+
+    let minute = match minute {
+        "" => 0,
+        minute => minute.parse().ok()?,
+    };
+
+    Some(((hour.parse().ok()?, minute), pm))
+}
+
+/**
+ * 2021-08-13, 13/08/2021, 13/08 (08/13 in English)
+ */
+fn parse_numeric_date(token: &str, lang: &Lang) -> Option<(Option<i32>, u32, u32)> {
+    if let Ok(date) = NaiveDate::parse_from_str(token, "%Y-%m-%d") {
+        return Some((Some(date.year()), date.month(), date.day()));
+    }
+
+    let parts: Vec<&str> = token.split('/').collect();
+    if parts.len() < 2 || parts.len() > 3 {
+        return None;
+    }
+    let numbers: Vec<u32> = parts
+        .iter()
+        .map(|part| match part.chars().all(|c| c.is_ascii_digit()) && part.len() <= 4 {
+            true => part.parse::<u32>().ok(),
+            false => None,
+        })
+        .collect::<Option<Vec<u32>>>()?;
+
+    let (day, month) = match lang.day_first {
+        true => (numbers[0], numbers[1]),
+        false => (numbers[1], numbers[0]),
+    };
+    let year = match numbers.get(2) {
+        Some(year) if *year < 100 => Some(2000 + *year as i32),
+        Some(year) => Some(*year as i32),
+        None => None,
+    };
+
+    Some((year, month, day))
+}
+
+fn set_once<T>(field: &mut Option<T>, value: T, text: &str) -> Result<(), String> {
+    if field.is_some() {
+        return Err(format!("'{}' has more than one date or time", text));
+    }
+    *field = Some(value);
+
+    Ok(())
+}
+
+fn set_time(expression: &mut Expression, time: (u32, u32), pm: Option<bool>, text: &str) -> Result<(), String> {
+    set_once(&mut expression.time, time, text)?;
+    if let Some(pm) = pm {
+        set_once(&mut expression.pm, pm, text)?;
+    }
+
+    Ok(())
+}
+
+fn parse_expression(text: &str, lang: &Lang) -> Result<Expression, String> {
+    let tokens = tokenize(text);
+    let mut expression = Expression::default();
+    let mut modifier: Option<Modifier> = None;
+    let mut at = false;
+    let mut index = 0;
+
+    while index < tokens.len() {
+        let token = &tokens[index];
+
+        if let Some((time, pm)) = parse_clock(token) {
+            set_time(&mut expression, time, pm, text)?;
+            expression.matched = true;
+            index += 1;
+            at = false;
+            continue;
+        }
+        if let Some(date) = parse_numeric_date(token, lang) {
+            set_once(&mut expression.date, date, text)?;
+            expression.matched = true;
+            index += 1;
+            continue;
+        }
+
+        let (word, len) = match (parse_number(token), match_word(lang, &tokens, index)) {
+            (Some(number), _) => (Word::Number(number), 1),
+            (None, Some(word)) => word,
+            (None, None) => return Err(format!("'{}' is not understood in '{}'", token, text)),
+        };
+        index += len;
+        let next = match_word(lang, &tokens, index).map(|(word, _)| word);
+        let next_number = tokens.get(index).and_then(|token| parse_number(token));
+
+        match word {
+            Word::Filler => continue,
+            Word::At => {
+                at = true;
+                continue;
+            }
+            Word::Now => {}
+            Word::Day(day) => set_once(&mut expression.day, day, text)?,
+            Word::Weekday(weekday) => {
+                let weekday_modifier = match next {
+                    Some(Word::Modifier(next)) => {
+                        index += 1;
+                        Some(next)
+                    }
+                    _ => modifier.take(),
+                };
+                set_once(&mut expression.weekday, (weekday, weekday_modifier), text)?;
+            }
+            Word::Month(month) => {
+                // august 13 (2021)
+                let day = match next_number {
+                    Some(day) if day <= 31 => {
+                        index += 1;
+                        day as u32
+                    }
+                    _ => return Err(format!("'{}' has a month without a day", text)),
+                };
+                let year = match tokens.get(index).and_then(|token| parse_number(token)) {
+                    Some(year) if year >= 1000 => {
+                        index += 1;
+                        Some(year as i32)
+                    }
+                    _ => None,
+                };
+                set_once(&mut expression.date, (year, month, day), text)?;
+            }
+            Word::Unit(unit) => {
+                // next week, la semaine prochaine
+                let period_modifier = match (next, modifier.take()) {
+                    (Some(Word::Modifier(next)), _) => {
+                        index += 1;
+                        next
+                    }
+                    (_, Some(modifier)) => modifier,
+                    _ => return Err(format!("'{}' has a unit without a number", text)),
+                };
+                set_once(&mut expression.period, (unit, period_modifier), text)?;
+            }
+            Word::Number(number) => match next {
+                Some(Word::Unit(unit)) if !at => {
+                    index += 1;
+                    expression.durations.push((number, unit));
+                }
+                // 13 august (2021)
+                Some(Word::Month(month)) if (1..=31).contains(&number) => {
+                    index += 1;
+                    let year = match tokens.get(index).and_then(|token| parse_number(token)) {
+                        Some(year) if year >= 1000 => {
+                            index += 1;
+                            Some(year as i32)
+                        }
+                        _ => None,
+                    };
+                    set_once(&mut expression.date, (year, month, number as u32), text)?;
+                }
+                // 3 pm, a 15 h
+                Some(Word::Am) | Some(Word::Pm) | Some(Word::Unit("hours")) if number <= 24 => {
+                    let pm = match next {
+                        Some(Word::Am) => Some(false),
+                        Some(Word::Pm) => Some(true),
+                        _ => None,
+                    };
+                    index += 1;
+                    set_time(&mut expression, (number as u32, 0), pm, text)?;
+                }
+                // at 3
+                _ if at && number <= 24 => set_time(&mut expression, (number as u32, 0), None, text)?,
+                _ => return Err(format!("'{}' is not understood in '{}'", number, text)),
+            },
+            Word::Modifier(word_modifier) => {
+                set_once(&mut modifier, word_modifier, text)?;
+                continue;
+            }
+            Word::In => set_once(&mut expression.direction, 1, text)?,
+            Word::Ago | Word::AgoPrefix => set_once(&mut expression.direction, -1, text)?,
+            Word::Noon => set_time(&mut expression, (12, 0), None, text)?,
+            Word::Midnight => set_time(&mut expression, (0, 0), None, text)?,
+            Word::Am => set_once(&mut expression.pm, false, text)?,
+            Word::Pm => set_once(&mut expression.pm, true, text)?,
+        }
+
+        expression.matched = true;
+        at = false;
+    }
+
+    if modifier.is_some() {
+        return Err(format!("'{}' is not understood", text));
+    }
+    if !expression.matched {
+        return Err(format!("'{}' is not a date", text));
+    }
+    if expression.direction.is_some() == expression.durations.is_empty() {
+        return Err(format!("'{}' has a duration without direction", text));
+    }
+
+    Ok(expression)
+}
+
+fn get_day(expression: &Expression, reference: &DateTime<Tz>) -> Result<Option<NaiveDate>, String> {
+    let today = reference.naive_local().date();
+    let out_of_range = || "the date is out of the range of supported dates".to_owned();
+
+    let mut days = vec![];
+
+    if let Some((year, month, day)) = expression.date {
+        let date = match year {
+            Some(year) => NaiveDate::from_ymd_opt(year, month, day),
+            // the next one, today included
+            None => match NaiveDate::from_ymd_opt(today.year(), month, day) {
+                Some(date) if date < today => NaiveDate::from_ymd_opt(today.year() + 1, month, day),
+                None if month == 2 && day == 29 => (1..=4)
+                    .find_map(|years| NaiveDate::from_ymd_opt(today.year() + years, month, day)),
+                date => date,
+            }
+        };
+
+        match date {
+            Some(date) => days.push(date),
+            None => return Err(format!("{:02}/{:02} is not a valid date", day, month)),
+        }
+    }
+
+    if let Some(day) = expression.day {
+        days.push(today.checked_add_signed(Duration::days(day)).ok_or_else(out_of_range)?);
+    }
+
+    if let Some((weekday, modifier)) = expression.weekday {
+        let current = today.weekday().num_days_from_monday() as i64;
+        let weekday = weekday as i64;
+        let delta = match modifier {
+            // the next one, today excluded
+            None | Some(Modifier::Next) => match (weekday - current).rem_euclid(7) {
+                0 => 7,
+                delta => delta,
+            },
+            Some(Modifier::Last) => match (current - weekday).rem_euclid(7) {
+                0 => -7,
+                delta => -delta,
+            },
+            Some(Modifier::This) => weekday - current,
+        };
+
+        // "friday 13 august" is a date, the week day is only a reminder
+        if expression.date.is_none() {
+            days.push(today + Duration::days(delta));
+        }
+    }
+
+    if let Some((unit, modifier)) = expression.period {
+        let value = match modifier {
+            Modifier::Next => 1,
+            Modifier::Last => -1,
+            Modifier::This => 0,
+        };
+        let start = match unit {
+            "years" | "months" | "weeks" | "days" => {
+                tools_time::get_start_of(reference, unit.trim_end_matches('s')).flatten()
+            }
+            _ => return Err(format!("'{}' is too precise, use a duration instead", unit)),
+        };
+        let date = start
+            .and_then(|start| tools_time::add_to_date(&start, value, unit))
+            .ok_or_else(out_of_range)?;
+
+        days.push(date.naive_local().date());
+    }
+
+    match days.as_slice() {
+        [] => Ok(None),
+        [day] => Ok(Some(*day)),
+        _ => Err("the text has more than one date".to_owned()),
+    }
+}
+
+fn get_clock(expression: &Expression) -> Result<Option<NaiveTime>, String> {
+    let (mut hour, minute) = match expression.time {
+        Some(time) => time,
+        None if expression.pm.is_some() => return Err("am or pm is missing an hour".to_owned()),
+        None => return Ok(None),
+    };
+
+    match expression.pm {
+        Some(_) if hour > 12 || hour == 0 => {
+            return Err(format!("{}:{:02} is not a valid 12-hour time", hour, minute))
+        }
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+
+    match NaiveTime::from_hms_opt(hour, minute, 0) {
+        Some(time) => Ok(Some(time)),
+        None => Err(format!("{}:{:02} is not a valid time", hour, minute)),
+    }
+}
+
+/**
+ * Dates without a time are at midnight, times without a date are the next one
+ * (today or tomorrow), and durations are added at the end ("tomorrow in 2 hours")
+ */
+fn resolve(expression: &Expression, reference: &DateTime<Tz>) -> Result<DateTime<Tz>, String> {
+    let out_of_range = || "the date is out of the range of supported dates".to_owned();
+    let tz = reference.timezone();
+    let day = get_day(expression, reference)?;
+    let time = get_clock(expression)?;
+
+    let mut date = match (day, time) {
+        (Some(day), time) => {
+            let local = day.and_time(time.unwrap_or_else(|| NaiveTime::from_hms(0, 0, 0)));
+            tools_time::from_local(&tz, &local).ok_or_else(out_of_range)?
+        }
+        (None, Some(time)) => {
+            let local = reference.naive_local().date().and_time(time);
+            let date = tools_time::from_local(&tz, &local).ok_or_else(out_of_range)?;
+
+            match date < *reference {
+                true => tools_time::add_to_date(&date, 1, "days").ok_or_else(out_of_range)?,
+                false => date,
+            }
+        }
+        (None, None) => *reference,
+    };
+
+    let direction = expression.direction.unwrap_or(1);
+    for (value, unit) in expression.durations.iter() {
+        date = value
+            .checked_mul(direction)
+            .and_then(|value| tools_time::add_to_date(&date, value, unit))
+            .ok_or_else(out_of_range)?;
+    }
+
+    Ok(date)
+}
+
+fn get_text(
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+    usage: &str,
+) -> Result<String, ErrorInfo> {
+    match args.get("arg0") {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveObject => {
+            let object = Literal::get_value::<HashMap<String, Literal>>(
+                &literal.primitive,
+                &data.context.flow,
+                interval,
+                usage.to_owned(),
+            )?;
+
+            match object.get("text") {
+                Some(text) if text.primitive.get_type() == PrimitiveType::PrimitiveString => {
+                    let text = Literal::get_value::<String>(
+                        &text.primitive,
+                        &data.context.flow,
+                        interval,
+                        usage.to_owned(),
+                    )?;
+
+                    Ok(text.to_owned())
+                }
+                _ => Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    usage.to_owned(),
+                )),
+            }
+        }
+        _ => tools_time::get_date_string(args, 0, data, interval, usage),
+    }
+}
+
+fn get_reference(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+    usage: &str,
+) -> Result<DateTime<Tz>, ErrorInfo> {
+    match args.get("arg2") {
+        Some(literal) if literal.content_type == "time" => {
+            let reference = Literal::get_value::<HashMap<String, Literal>>(
+                &literal.primitive,
+                &data.context.flow,
+                interval,
+                usage.to_owned(),
+            )?
+            .clone();
+
+            tools_time::get_time(&reference, data, interval)
+        }
+        Some(_) => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            usage.to_owned(),
+        )),
+        None => tools_time::get_time(object, data, interval),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Time().parse_natural(text, lang, reference) parses dates typed by users such as
+ * "tomorrow at 3pm", "next friday", "in 2 hours", "demain à 15h" or "vendredi prochain".
+ * They are relative to reference (the time the method is called on by default) and in its timezone.
+ *
+ * Text that is not understood is not a flow error: it returns {"error": String, "text": String}
+ * so that the bot can ask the user again.
+ */
+pub fn parse_natural(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "usage: Time().parse_natural(text: String, lang: String = \"en\", reference: Time = Time()), \
+    lang being 'en' or 'fr'";
+
+    let text = get_text(args, data, interval, usage)?;
+    let lang = match args.get("arg1") {
+        Some(_) => tools_time::get_date_string(args, 1, data, interval, usage)?,
+        None => "en".to_owned(),
+    };
+    let lang = match get_lang(&lang) {
+        Some(lang) => lang,
+        None => return Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            format!("unsupported lang '{}', {}", lang, usage),
+        )),
+    };
+    let reference = get_reference(object, args, data, interval, usage)?;
+
+    match parse_expression(&text, lang).and_then(|expression| resolve(&expression, &reference)) {
+        Ok(date) => Ok(tools_time::time_to_literal(&date, interval)),
+        Err(error) => {
+            let mut object = HashMap::new();
+            object.insert("error".to_owned(), PrimitiveString::get_literal(&error, interval));
+            object.insert("text".to_owned(), PrimitiveString::get_literal(&text, interval));
+
+            Ok(PrimitiveObject::get_literal(&object, interval))
+        }
+    }
+}
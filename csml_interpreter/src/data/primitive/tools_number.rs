@@ -0,0 +1,687 @@
+use crate::data::{
+    ast::Interval, error_info::ErrorInfo, position::Position, primitive::PrimitiveString,
+    primitive::PrimitiveType, primitive::tools::get_language, Data, Literal,
+};
+use crate::error_format::*;
+use lazy_static::*;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Number symbols, patterns and plural rules of a locale, from CLDR.
+ * Patterns use # and 0 for digits, "," for the group separator, "." for the decimal
+ * separator and ¤ for the currency symbol; "positive;negative" when the negative
+ * pattern is not just the positive one with a minus sign.
+ */
+#[derive(Debug, Deserialize)]
+struct NumberLocale {
+    decimal: String,
+    group: String,
+    // digits of the integer part needed before grouping, after the first 3
+    min_grouping: usize,
+    decimal_format: String,
+    percent_format: String,
+    currency_format: String,
+    currency_symbols: HashMap<String, String>,
+    // rules of each plural category, the category is "other" if none matches
+    plurals: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Currency {
+    digits: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct NumberData {
+    locales: HashMap<String, NumberLocale>,
+    currencies: HashMap<String, Currency>,
+}
+
+lazy_static! {
+    static ref NUMBER_DATA: NumberData = serde_json::from_str(include_str!("cldr/numbers.json"))
+        .expect("invalid CLDR number data");
+}
+
+const PLURAL_CATEGORIES: &[&str] = &["zero", "one", "two", "few", "many"];
+
+/**
+ * Operands of the CLDR plural rules: the absolute value n, its integer digits i,
+ * its visible fraction digits f (v of them) and without trailing zeros t (w of them)
+ */
+struct PluralOperands {
+    n: f64,
+    i: f64,
+    v: f64,
+    w: f64,
+    f: f64,
+    t: f64,
+}
+
+/**
+ * ICU message being formatted: {name}, {name, number}, {name, plural, ...}, {name, select, ...}
+ */
+struct MessageFormat<'a> {
+    chars: Vec<char>,
+    index: usize,
+    values: &'a serde_json::Map<String, Value>,
+    locale: &'static NumberLocale,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_locale(locale: &str) -> Result<&'static NumberLocale, String> {
+    let language = get_language(locale);
+
+    match NUMBER_DATA.locales.get(&language) {
+        Some(locale) => Ok(locale),
+        None => {
+            let mut locales: Vec<&str> = NUMBER_DATA.locales.keys().map(|name| name.as_str()).collect();
+            locales.sort_unstable();
+
+            Err(format!(
+                "unknown locale '{}', supported locales are: {}",
+                locale,
+                locales.join(", ")
+            ))
+        }
+    }
+}
+
+fn get_locale_arg(
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+    error: &str,
+) -> Result<&'static NumberLocale, ErrorInfo> {
+    let locale = match args.get("arg0") {
+        Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            Literal::get_value::<String>(&res.primitive, &data.context.flow, interval, error.to_owned())?
+        }
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                error.to_owned(),
+            ))
+        }
+    };
+
+    get_locale(locale).map_err(|err| gen_error_info(Position::new(interval, &data.context.flow), err))
+}
+
+fn get_decimals_arg(
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+    error: &str,
+) -> Result<Option<usize>, ErrorInfo> {
+    match args.get("arg1") {
+        Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveInt => {
+            let decimals =
+                *Literal::get_value::<i64>(&res.primitive, &data.context.flow, interval, error.to_owned())?;
+
+            match decimals {
+                0..=20 => Ok(Some(decimals as usize)),
+                _ => Err(gen_error_info(
+                    Position::new(interval, &data.context.flow),
+                    error.to_owned(),
+                )),
+            }
+        }
+        Some(_) => Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            error.to_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+fn check_args_len(
+    args: &HashMap<String, Literal>,
+    min: usize,
+    max: usize,
+    data: &Data,
+    interval: Interval,
+    usage: &str,
+) -> Result<(), ErrorInfo> {
+    match args.len() {
+        len if len >= min && len <= max => Ok(()),
+        _ => Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!("usage: {}", usage),
+        )),
+    }
+}
+
+/**
+ * Digits of the absolute value with the separators of the locale, rounded half to even
+ * to max_fraction digits and keeping at least min_fraction digits
+ */
+fn format_digits(value: f64, min_fraction: usize, max_fraction: usize, locale: &NumberLocale) -> String {
+    let rounded = format!("{:.*}", max_fraction, value.abs());
+    let (integer, fraction) = match rounded.find('.') {
+        Some(index) => (&rounded[..index], &rounded[index + 1..]),
+        None => (rounded.as_str(), ""),
+    };
+
+    let mut fraction = fraction.to_owned();
+    while fraction.len() > min_fraction && fraction.ends_with('0') {
+        fraction.pop();
+    }
+
+    let mut result = String::new();
+    let grouping = integer.len() >= 3 + locale.min_grouping;
+    for (index, digit) in integer.chars().enumerate() {
+        if grouping && index > 0 && (integer.len() - index) % 3 == 0 {
+            result.push_str(&locale.group);
+        }
+        result.push(digit);
+    }
+
+    if !fraction.is_empty() {
+        result.push_str(&locale.decimal);
+        result.push_str(&fraction);
+    }
+
+    result
+}
+
+/**
+ * Number of fraction digits of a pattern: min is the count of 0, max of 0 and #
+ */
+fn get_pattern_fraction(pattern: &str) -> (usize, usize) {
+    let positive = pattern.split(';').next().unwrap_or_default();
+
+    match positive.find('.') {
+        Some(index) => {
+            let fraction: Vec<char> = positive[index + 1..]
+                .chars()
+                .take_while(|c| *c == '0' || *c == '#')
+                .collect();
+
+            (
+                fraction.iter().filter(|c| **c == '0').count(),
+                fraction.len(),
+            )
+        }
+        None => (0, 0),
+    }
+}
+
+fn apply_pattern(pattern: &str, value: f64, min_fraction: usize, max_fraction: usize, locale: &NumberLocale, symbol: &str) -> String {
+    let digits = format_digits(value, min_fraction, max_fraction, locale);
+    // -0.001 rounded to 0.00 is not negative
+    let negative = value < 0.0 && digits.chars().any(|c| c.is_ascii_digit() && c != '0');
+
+    let mut patterns = pattern.split(';');
+    let positive = patterns.next().unwrap_or_default();
+    let (pattern, sign) = match (negative, patterns.next()) {
+        (true, Some(negative)) => (negative, ""),
+        (true, None) => (positive, "-"),
+        (false, _) => (positive, ""),
+    };
+
+    let is_number = |c: char| c == '#' || c == '0' || c == ',' || c == '.';
+    let start = pattern.find(is_number).unwrap_or(pattern.len());
+    let end = pattern.rfind(is_number).map_or(start, |index| index + 1);
+
+    format!(
+        "{}{}{}{}",
+        sign,
+        pattern[..start].replace('¤', symbol),
+        digits,
+        pattern[end..].replace('¤', symbol)
+    )
+}
+
+fn format_decimal(value: f64, decimals: Option<usize>, locale: &NumberLocale) -> String {
+    let (min_fraction, max_fraction) = match decimals {
+        Some(decimals) => (decimals, decimals),
+        None => get_pattern_fraction(&locale.decimal_format),
+    };
+
+    apply_pattern(&locale.decimal_format, value, min_fraction, max_fraction, locale, "")
+}
+
+fn get_operands(number: &str) -> PluralOperands {
+    let number = number.trim_start_matches('-');
+    let (integer, fraction) = match number.find('.') {
+        Some(index) => (&number[..index], &number[index + 1..]),
+        None => (number, ""),
+    };
+    let trimmed = fraction.trim_end_matches('0');
+
+    PluralOperands {
+        n: number.parse().unwrap_or_default(),
+        i: integer.parse().unwrap_or_default(),
+        v: fraction.len() as f64,
+        w: trimmed.len() as f64,
+        f: fraction.parse().unwrap_or_default(),
+        t: trimmed.parse().unwrap_or_default(),
+    }
+}
+
+/**
+ * Relation of a CLDR plural rule, e.g. "i % 10 = 2..4" or "i % 100 != 12..14"
+ */
+fn matches_relation(relation: &str, operands: &PluralOperands) -> bool {
+    let (expression, ranges, equal) = match relation.find("!=") {
+        Some(index) => (&relation[..index], &relation[index + 2..], false),
+        None => match relation.find('=') {
+            Some(index) => (&relation[..index], &relation[index + 1..], true),
+            None => return false,
+        },
+    };
+
+    let mut expression = expression.split('%');
+    let value = match expression.next().map(|operand| operand.trim()) {
+        Some("n") => operands.n,
+        Some("i") => operands.i,
+        Some("v") => operands.v,
+        Some("w") => operands.w,
+        Some("f") => operands.f,
+        Some("t") => operands.t,
+        // numbers are never formatted with an exponent
+        Some("e") | Some("c") => 0.0,
+        _ => return false,
+    };
+    let value = match expression.next().and_then(|modulo| modulo.trim().parse::<f64>().ok()) {
+        Some(modulo) => value % modulo,
+        None => value,
+    };
+
+    let in_ranges = ranges.split(',').any(|range| {
+        let mut bounds = range.trim().splitn(2, "..");
+        let start = bounds.next().and_then(|bound| bound.trim().parse::<f64>().ok());
+        let end = bounds.next().and_then(|bound| bound.trim().parse::<f64>().ok()).or(start);
+
+        match (start, end) {
+            (Some(start), Some(end)) => value.fract() == 0.0 && value >= start && value <= end,
+            _ => false,
+        }
+    });
+
+    in_ranges == equal
+}
+
+fn get_plural_category(number: &str, locale: &NumberLocale) -> &'static str {
+    let operands = get_operands(number);
+
+    PLURAL_CATEGORIES
+        .iter()
+        .find(|category| match locale.plurals.get(**category) {
+            Some(rule) => rule.split(" or ").any(|condition| {
+                condition
+                    .split(" and ")
+                    .all(|relation| matches_relation(relation.trim(), &operands))
+            }),
+            None => false,
+        })
+        .copied()
+        .unwrap_or("other")
+}
+
+fn get_number(name: &str, value: &Value) -> Result<f64, String> {
+    match value {
+        Value::Number(number) => Ok(number.as_f64().unwrap_or_default()),
+        Value::String(string) => string
+            .parse::<f64>()
+            .map_err(|_| format!("'{}' must be a number, found '{}'", name, string)),
+        Value::Null => Err(format!("missing value '{}'", name)),
+        value => Err(format!("'{}' must be a number, found {}", name, value)),
+    }
+}
+
+/**
+ * Format of an f64 used by the plural rules: 3.0 is "3" and 1.5 is "1.5"
+ */
+fn get_number_string(number: f64) -> String {
+    number.to_string()
+}
+
+impl<'a> MessageFormat<'a> {
+    fn skip_whitespace(&mut self) {
+        while matches!(self.chars.get(self.index), Some(c) if c.is_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn read_until(&mut self, stop: fn(char) -> bool) -> String {
+        let start = self.index;
+        while matches!(self.chars.get(self.index), Some(c) if !stop(*c)) {
+            self.index += 1;
+        }
+
+        self.chars[start..self.index].iter().collect::<String>().trim().to_owned()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.chars.get(self.index) {
+            Some(c) if *c == expected => {
+                self.index += 1;
+                Ok(())
+            }
+            _ => Err(format!("expected '{}' at position {}", expected, self.index)),
+        }
+    }
+
+    /**
+     * Text until the end of the message, or until the '}' closing an option if nested.
+     * In a plural option, # is the number.
+     */
+    fn format(&mut self, number: Option<f64>, nested: bool) -> Result<String, String> {
+        let mut result = String::new();
+
+        while let Some(c) = self.chars.get(self.index).copied() {
+            match (c, number) {
+                ('}', _) if nested => return Ok(result),
+                ('{', _) => {
+                    self.index += 1;
+                    result.push_str(&self.format_argument()?);
+                }
+                ('#', Some(number)) => {
+                    self.index += 1;
+                    result.push_str(&format_decimal(number, None, self.locale));
+                }
+                (c, _) => {
+                    self.index += 1;
+                    result.push(c);
+                }
+            }
+        }
+
+        match nested {
+            true => Err("missing '}' at the end of the message".to_owned()),
+            false => Ok(result),
+        }
+    }
+
+    fn format_argument(&mut self) -> Result<String, String> {
+        let name = self.read_until(|c| c == ',' || c == '}');
+        let value = self.values.get(&name).cloned().unwrap_or(Value::Null);
+
+        self.skip_whitespace();
+        if self.chars.get(self.index) == Some(&'}') {
+            self.index += 1;
+
+            return match value {
+                Value::String(string) => Ok(string),
+                Value::Number(_) => Ok(format_decimal(get_number(&name, &value)?, None, self.locale)),
+                Value::Null => Err(format!("missing value '{}'", name)),
+                value => Ok(value.to_string()),
+            };
+        }
+
+        self.expect(',')?;
+        let kind = self.read_until(|c| c == ',' || c == '}');
+        match kind.as_str() {
+            "number" => {
+                self.expect('}')?;
+                Ok(format_decimal(get_number(&name, &value)?, None, self.locale))
+            }
+            "plural" => {
+                self.expect(',')?;
+                let number = get_number(&name, &value)?;
+                let (options, offset) = self.parse_options(Some(number))?;
+                let category = get_plural_category(&get_number_string(number - offset), self.locale);
+
+                // an exact match like =0 is chosen before the plural category
+                let option = options
+                    .iter()
+                    .find(|(key, _)| key.strip_prefix('=').and_then(|exact| exact.parse::<f64>().ok()) == Some(number))
+                    .or_else(|| options.iter().find(|(key, _)| key == category))
+                    .or_else(|| options.iter().find(|(key, _)| key == "other"));
+
+                match option {
+                    Some((_, text)) => Ok(text.to_owned()),
+                    None => Err(format!("missing 'other' option for '{}'", name)),
+                }
+            }
+            "select" => {
+                self.expect(',')?;
+                let selected = match &value {
+                    Value::String(string) => string.to_owned(),
+                    Value::Null => return Err(format!("missing value '{}'", name)),
+                    value => value.to_string(),
+                };
+                let (options, _) = self.parse_options(None)?;
+
+                let option = options
+                    .iter()
+                    .find(|(key, _)| *key == selected)
+                    .or_else(|| options.iter().find(|(key, _)| key == "other"));
+
+                match option {
+                    Some((_, text)) => Ok(text.to_owned()),
+                    None => Err(format!("missing 'other' option for '{}'", name)),
+                }
+            }
+            kind => Err(format!("unknown argument type '{}' for '{}'", kind, name)),
+        }
+    }
+
+    /**
+     * Options "key {text}" until the '}' closing the argument, with the offset of a plural.
+     * The text of every option is formatted, # being the number minus the offset.
+     */
+    fn parse_options(&mut self, number: Option<f64>) -> Result<(Vec<(String, String)>, f64), String> {
+        let mut options = vec![];
+        let mut offset = 0.0;
+
+        loop {
+            self.skip_whitespace();
+            match self.chars.get(self.index) {
+                Some('}') => {
+                    self.index += 1;
+                    return Ok((options, offset));
+                }
+                Some(_) => {}
+                None => return Err("missing '}' at the end of the message".to_owned()),
+            }
+
+            let key = self.read_until(|c| c == '{' || c == '}' || c.is_whitespace());
+            if let Some(value) = key.strip_prefix("offset:") {
+                offset = value
+                    .parse::<f64>()
+                    .map_err(|_| format!("invalid offset '{}'", value))?;
+                continue;
+            }
+
+            self.expect('{')?;
+            let text = self.format(number.map(|number| number - offset), true)?;
+            self.expect('}')?;
+            options.push((key, text));
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * number.format_number(locale, decimals): "1 234,5" in French. Without decimals,
+ * up to 3 fraction digits are kept, like the CLDR decimal format.
+ */
+pub fn format_number(
+    value: f64,
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "format_number(locale: String, decimals: Optional<Int>) => String";
+    check_args_len(args, 1, 2, data, interval, usage)?;
+
+    let locale = get_locale_arg(args, data, interval, ERROR_NUMBER_FORMAT)?;
+    let decimals = get_decimals_arg(args, data, interval, ERROR_NUMBER_FORMAT)?;
+
+    Ok(PrimitiveString::get_literal(&format_decimal(value, decimals, locale), interval))
+}
+
+/**
+ * number.format_currency(locale, currency): "1 234,50 €" in French for EUR, with the
+ * number of decimals of the ISO 4217 currency
+ */
+pub fn format_currency(
+    value: f64,
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "format_currency(locale: String, currency: String) => String";
+    check_args_len(args, 2, 2, data, interval, usage)?;
+
+    let locale = get_locale_arg(args, data, interval, ERROR_NUMBER_FORMAT_CURRENCY)?;
+    let currency = match args.get("arg1") {
+        Some(res) if res.primitive.get_type() == PrimitiveType::PrimitiveString => Literal::get_value::<String>(
+            &res.primitive,
+            &data.context.flow,
+            interval,
+            ERROR_NUMBER_FORMAT_CURRENCY.to_owned(),
+        )?
+        .to_uppercase(),
+        _ => {
+            return Err(gen_error_info(
+                Position::new(interval, &data.context.flow),
+                ERROR_NUMBER_FORMAT_CURRENCY.to_owned(),
+            ))
+        }
+    };
+    if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(gen_error_info(
+            Position::new(interval, &data.context.flow),
+            format!("'{}' is not an ISO 4217 currency code", currency),
+        ));
+    }
+
+    // unknown currencies are shown with their code and 2 decimals
+    let symbol = locale.currency_symbols.get(&currency).unwrap_or(&currency);
+    let digits = NUMBER_DATA
+        .currencies
+        .get(&currency)
+        .map_or(2, |currency| currency.digits);
+
+    let result = apply_pattern(&locale.currency_format, value, digits, digits, locale, symbol);
+
+    Ok(PrimitiveString::get_literal(&result, interval))
+}
+
+/**
+ * number.format_percent(locale, decimals): 0.256 is "26 %" in French, "25,6 %" with 1 decimal
+ */
+pub fn format_percent(
+    value: f64,
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "format_percent(locale: String, decimals: Optional<Int>) => String";
+    check_args_len(args, 1, 2, data, interval, usage)?;
+
+    let locale = get_locale_arg(args, data, interval, ERROR_NUMBER_FORMAT_PERCENT)?;
+    let decimals = get_decimals_arg(args, data, interval, ERROR_NUMBER_FORMAT_PERCENT)?.unwrap_or(0);
+
+    let result = apply_pattern(&locale.percent_format, value * 100.0, decimals, decimals, locale, "");
+
+    Ok(PrimitiveString::get_literal(&result, interval))
+}
+
+/**
+ * number.plural(locale): CLDR plural category of the number, one of zero, one, two,
+ * few, many and other. The number is given as CSML writes it: the float 1.0 is "1",
+ * so "one" in English, while the string "1.0" of a message is "other".
+ */
+pub fn plural(
+    number: &str,
+    args: &HashMap<String, Literal>,
+    data: &Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "plural(locale: String) => String";
+    check_args_len(args, 1, 1, data, interval, usage)?;
+
+    let locale = get_locale_arg(args, data, interval, ERROR_NUMBER_PLURAL)?;
+
+    Ok(PrimitiveString::get_literal(get_plural_category(number, locale), interval))
+}
+
+/**
+ * ICU message with the values of the object, e.g.
+ * "{count, plural, =0 {no pizza} one {# pizza} other {# pizzas}} for {name}".
+ * Numbers and # are formatted for the locale. Apostrophes are not escape characters.
+ */
+pub fn format_message(message: &str, values: &serde_json::Map<String, Value>, locale: &str) -> Result<String, String> {
+    let mut message = MessageFormat {
+        chars: message.chars().collect(),
+        index: 0,
+        values,
+        locale: get_locale(locale)?,
+    };
+
+    message.format(None, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn locale(name: &str) -> &'static NumberLocale {
+        get_locale(name).unwrap()
+    }
+
+    #[test]
+    fn ok_format_digits() {
+        assert_eq!(format_decimal(1234.5, Some(2), locale("fr")), "1\u{202f}234,50");
+        assert_eq!(format_decimal(1234.5678, None, locale("en")), "1,234.568");
+        assert_eq!(format_decimal(-1234567.0, None, locale("de")), "-1.234.567");
+        // Spanish and Polish only group numbers of 5 digits or more
+        assert_eq!(format_decimal(1234.0, None, locale("es")), "1234");
+        assert_eq!(format_decimal(12345.0, None, locale("es")), "12.345");
+        assert_eq!(format_decimal(-0.001, Some(2), locale("en")), "0.00");
+    }
+
+    #[test]
+    fn ok_plural_category() {
+        let categories = |name: &str, numbers: &[&str]| -> Vec<&'static str> {
+            numbers.iter().map(|number| get_plural_category(number, locale(name))).collect()
+        };
+
+        assert_eq!(categories("en", &["0", "1", "1.0", "2"]), vec!["other", "one", "other", "other"]);
+        assert_eq!(categories("fr", &["0", "1", "1.5", "2", "1000000"]), vec!["one", "one", "one", "other", "many"]);
+        assert_eq!(
+            categories("ru", &["1", "3", "5", "11", "21", "22", "1.5"]),
+            vec!["one", "few", "many", "many", "one", "few", "other"]
+        );
+        assert_eq!(categories("pl", &["1", "2", "5", "12", "22"]), vec!["one", "few", "many", "many", "few"]);
+        assert_eq!(categories("ja", &["1"]), vec!["other"]);
+    }
+
+    #[test]
+    fn ok_format_message() {
+        let message = "{name} a {count, plural, =0 {aucune pizza} one {# pizza} other {# pizzas}} ({size, select, large {grande} other {normale}})";
+        let format = |values: Value| format_message(message, values.as_object().unwrap(), "fr-FR");
+
+        assert_eq!(format(json!({"name": "Tom", "count": 0, "size": "large"})).unwrap(), "Tom a aucune pizza (grande)");
+        assert_eq!(format(json!({"name": "Tom", "count": 1, "size": "small"})).unwrap(), "Tom a 1 pizza (normale)");
+        assert_eq!(format(json!({"name": "Tom", "count": 1500, "size": "large"})).unwrap(), "Tom a 1\u{202f}500 pizzas (grande)");
+        assert!(format(json!({"name": "Tom", "size": "large"})).is_err());
+
+        let values = json!({"guests": 3});
+        assert_eq!(
+            format_message("{guests, plural, offset:1 =1 {you} one {you and # guest} other {you and # guests}}", values.as_object().unwrap(), "en").unwrap(),
+            "you and 2 guests"
+        );
+        assert!(format_message("{guests, plural, one {# guest}}", values.as_object().unwrap(), "en").is_err());
+        assert!(format_message("{guests, plural, other {#}", values.as_object().unwrap(), "en").is_err());
+        assert!(format_message("{guests}", values.as_object().unwrap(), "xx").is_err());
+    }
+}
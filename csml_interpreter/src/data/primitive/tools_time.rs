@@ -3,19 +3,80 @@ use crate::data::{
     Literal, primitive::PrimitiveType,
     ast::Interval,
     primitive::{
-        Data, PrimitiveInt, PrimitiveObject
+        Data, PrimitiveInt, PrimitiveObject, PrimitiveString, tools::get_language
     },
     error_info::ErrorInfo,
     position::Position
 };
 use std::{collections::HashMap};
-use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone,
+    Timelike, Utc,
+};
+use chrono_tz::Tz;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURES
+////////////////////////////////////////////////////////////////////////////////
+
+struct LocaleNames {
+    weekdays: [&'static str; 7],
+    short_weekdays: [&'static str; 7],
+    months: [&'static str; 12],
+    short_months: [&'static str; 12],
+}
+
+// week days start on Monday
+const LOCALES: &[(&str, LocaleNames)] = &[
+    ("en", LocaleNames {
+        weekdays: ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"],
+        short_weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        months: ["January", "February", "March", "April", "May", "June", "July", "August", "September", "October", "November", "December"],
+        short_months: ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"],
+    }),
+    ("fr", LocaleNames {
+        weekdays: ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"],
+        short_weekdays: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        months: ["janvier", "février", "mars", "avril", "mai", "juin", "juillet", "août", "septembre", "octobre", "novembre", "décembre"],
+        short_months: ["janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.", "nov.", "déc."],
+    }),
+    ("de", LocaleNames {
+        weekdays: ["Montag", "Dienstag", "Mittwoch", "Donnerstag", "Freitag", "Samstag", "Sonntag"],
+        short_weekdays: ["Mo.", "Di.", "Mi.", "Do.", "Fr.", "Sa.", "So."],
+        months: ["Januar", "Februar", "März", "April", "Mai", "Juni", "Juli", "August", "September", "Oktober", "November", "Dezember"],
+        short_months: ["Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez."],
+    }),
+    ("es", LocaleNames {
+        weekdays: ["lunes", "martes", "miércoles", "jueves", "viernes", "sábado", "domingo"],
+        short_weekdays: ["lun.", "mar.", "mié.", "jue.", "vie.", "sáb.", "dom."],
+        months: ["enero", "febrero", "marzo", "abril", "mayo", "junio", "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre"],
+        short_months: ["ene.", "feb.", "mar.", "abr.", "may.", "jun.", "jul.", "ago.", "sept.", "oct.", "nov.", "dic."],
+    }),
+    ("it", LocaleNames {
+        weekdays: ["lunedì", "martedì", "mercoledì", "giovedì", "venerdì", "sabato", "domenica"],
+        short_weekdays: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+        months: ["gennaio", "febbraio", "marzo", "aprile", "maggio", "giugno", "luglio", "agosto", "settembre", "ottobre", "novembre", "dicembre"],
+        short_months: ["gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic"],
+    }),
+    ("pt", LocaleNames {
+        weekdays: ["segunda-feira", "terça-feira", "quarta-feira", "quinta-feira", "sexta-feira", "sábado", "domingo"],
+        short_weekdays: ["seg.", "ter.", "qua.", "qui.", "sex.", "sáb.", "dom."],
+        months: ["janeiro", "fevereiro", "março", "abril", "maio", "junho", "julho", "agosto", "setembro", "outubro", "novembro", "dezembro"],
+        short_months: ["jan.", "fev.", "mar.", "abr.", "mai.", "jun.", "jul.", "ago.", "set.", "out.", "nov.", "dez."],
+    }),
+    ("nl", LocaleNames {
+        weekdays: ["maandag", "dinsdag", "woensdag", "donderdag", "vrijdag", "zaterdag", "zondag"],
+        short_weekdays: ["ma", "di", "wo", "do", "vr", "za", "zo"],
+        months: ["januari", "februari", "maart", "april", "mei", "juni", "juli", "augustus", "september", "oktober", "november", "december"],
+        short_months: ["jan.", "feb.", "mrt.", "apr.", "mei", "jun.", "jul.", "aug.", "sep.", "okt.", "nov.", "dec."],
+    }),
+];
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-fn get_date_string(
+pub fn get_date_string(
     args: &HashMap<String, Literal>,
     index: usize,
     data: &mut Data,
@@ -40,10 +101,465 @@ fn get_date_string(
     }
 }
 
+fn get_int_arg(
+    args: &HashMap<String, Literal>,
+    index: usize,
+    data: &mut Data,
+    interval: Interval,
+    error: &str,
+) -> Result<i64, ErrorInfo> {
+    match args.get(&format!("arg{}", index)) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveInt => {
+            let value = Literal::get_value::<i64>(
+                &literal.primitive,
+                &data.context.flow,
+                literal.interval,
+                error.to_owned(),
+            )?;
+
+            Ok(*value)
+        }
+        _ => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            error.to_owned(),
+        ))
+    }
+}
+
+fn parse_timezone(name: &str, data: &mut Data, interval: Interval) -> Result<Tz, ErrorInfo> {
+    match name.parse::<Tz>() {
+        Ok(tz) => Ok(tz),
+        Err(_) => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            format!("unknown timezone '{}', expect an IANA timezone such as 'Europe/Paris'", name),
+        ))
+    }
+}
+
+/**
+ * Local times skipped by a DST change are moved forward by the change,
+ * local times repeated by a DST change are the earliest of the two
+ */
+pub fn from_local(tz: &Tz, date: &NaiveDateTime) -> Option<DateTime<Tz>> {
+    match tz.from_local_datetime(date) {
+        LocalResult::Single(date) => Some(date),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => match tz.from_local_datetime(&(*date + Duration::hours(1))) {
+            LocalResult::Single(date) => Some(date),
+            LocalResult::Ambiguous(earliest, _) => Some(earliest),
+            LocalResult::None => None,
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let next_month = match month {
+        12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+    };
+
+    match next_month {
+        Some(date) => date.pred().day(),
+        None => 31,
+    }
+}
+
+/**
+ * The day of month is kept if possible, otherwise it is the last day of the new month
+ * (January 31 + 1 month = February 28 or 29)
+ */
+fn add_months(date: &DateTime<Tz>, months: i64) -> Option<DateTime<Tz>> {
+    let local = date.naive_local();
+    let total = (local.year() as i64 * 12 + local.month0() as i64).checked_add(months)?;
+    let year = total.div_euclid(12);
+    let month = total.rem_euclid(12) as u32 + 1;
+    if year > i32::MAX as i64 || year < i32::MIN as i64 {
+        return None;
+    }
+
+    let day = local.day().min(days_in_month(year as i32, month));
+    let local = NaiveDate::from_ymd_opt(year as i32, month, day)?.and_time(local.time());
+
+    from_local(&date.timezone(), &local)
+}
+
+/**
+ * Days and weeks are added to the local date, so that the local time stays the same
+ * across DST changes. Hours, minutes, seconds and milliseconds are exact durations.
+ */
+pub fn add_to_date(date: &DateTime<Tz>, value: i64, unit: &str) -> Option<DateTime<Tz>> {
+    let add_local_days = |days: i64| {
+        let millis = days.checked_mul(86_400_000)?;
+        let local = date.naive_local().checked_add_signed(Duration::milliseconds(millis))?;
+
+        from_local(&date.timezone(), &local)
+    };
+    let add_millis = |millis: Option<i64>| date.checked_add_signed(Duration::milliseconds(millis?));
+
+    match unit {
+        "years" => add_months(date, value.checked_mul(12)?),
+        "months" => add_months(date, value),
+        "weeks" => add_local_days(value.checked_mul(7)?),
+        "days" => add_local_days(value),
+        "hours" => add_millis(value.checked_mul(3_600_000)),
+        "minutes" => add_millis(value.checked_mul(60_000)),
+        "seconds" => add_millis(value.checked_mul(1_000)),
+        _ => add_millis(Some(value)),
+    }
+}
+
+fn months_between(from: &DateTime<Tz>, to: &DateTime<Tz>) -> i64 {
+    let (start, end, sign) = match from <= to {
+        true => (from, to, 1),
+        false => (to, from, -1),
+    };
+    let start_local = start.naive_local();
+    let end_local = end.naive_local();
+
+    let mut months = (end_local.year() as i64 - start_local.year() as i64) * 12
+        + end_local.month() as i64
+        - start_local.month() as i64;
+    // the last month is not complete yet
+    if months > 0 && !matches!(add_months(start, months), Some(date) if date <= *end) {
+        months -= 1;
+    }
+
+    sign * months
+}
+
+pub fn get_start_of(date: &DateTime<Tz>, unit: &str) -> Option<Option<DateTime<Tz>>> {
+    let local = date.naive_local();
+    let day = local.date();
+
+    let start = match unit {
+        "year" => NaiveDate::from_ymd_opt(day.year(), 1, 1)?.and_hms(0, 0, 0),
+        "month" => NaiveDate::from_ymd_opt(day.year(), day.month(), 1)?.and_hms(0, 0, 0),
+        "week" => {
+            let monday = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+            monday.and_hms(0, 0, 0)
+        }
+        "day" => day.and_hms(0, 0, 0),
+        "hour" => day.and_hms(local.hour(), 0, 0),
+        "minute" => day.and_hms(local.hour(), local.minute(), 0),
+        "second" => day.and_hms(local.hour(), local.minute(), local.second()),
+        _ => return None,
+    };
+
+    Some(from_local(&date.timezone(), &start))
+}
+
+fn get_locale(locale: &str) -> Option<&'static LocaleNames> {
+    let language = get_language(locale);
+
+    LOCALES
+        .iter()
+        .find(|(name, _)| *name == language)
+        .map(|(_, names)| names)
+}
+
+/**
+ * Replace the names of week days and months (%A, %a, %B, %b, %h) with the ones of the locale,
+ * chrono only knows the English names
+ */
+fn localize_format(format: &str, date: &DateTime<Tz>, names: &LocaleNames) -> String {
+    let weekday = date.weekday().num_days_from_monday() as usize;
+    let month = date.month0() as usize;
+
+    let mut localized = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            localized.push(c);
+            continue;
+        }
+
+        let mut specifier = String::from("%");
+        while let Some(&modifier) = chars.peek() {
+            if modifier != '-' && modifier != '_' && modifier != '0' {
+                break;
+            }
+            specifier.push(modifier);
+            chars.next();
+        }
+
+        match chars.next() {
+            Some('A') => localized.push_str(names.weekdays[weekday]),
+            Some('a') => localized.push_str(names.short_weekdays[weekday]),
+            Some('B') => localized.push_str(names.months[month]),
+            Some('b') | Some('h') => localized.push_str(names.short_months[month]),
+            Some(other) => {
+                localized.push_str(&specifier);
+                localized.push(other);
+            }
+            None => localized.push_str(&specifier),
+        }
+    }
+
+    localized
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 // PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
+/**
+ * A time object holds its date in UTC `milliseconds`, and optionally the `timezone`
+ * used to compute and format local dates (UTC if there is none)
+ */
+pub fn get_time(
+    object: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<DateTime<Tz>, ErrorInfo> {
+    let usage = "invalid value, use 'Time()' built-in to create a valid 'time' object";
+
+    let millis = match object.get("milliseconds") {
+        Some(lit) if lit.primitive.get_type() == PrimitiveType::PrimitiveInt => {
+            *Literal::get_value::<i64>(
+                &lit.primitive,
+                &data.context.flow,
+                interval,
+                usage.to_owned(),
+            )?
+        }
+        _ => return Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            usage.to_owned(),
+        ))
+    };
+
+    let tz = get_timezone(object, data, interval)?;
+
+    Ok(tz.timestamp_millis(millis))
+}
+
+pub fn get_timezone(
+    object: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Tz, ErrorInfo> {
+    match object.get("timezone") {
+        Some(lit) if lit.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            let name = Literal::get_value::<String>(
+                &lit.primitive,
+                &data.context.flow,
+                interval,
+                "timezone must be of type string".to_owned(),
+            )?.to_owned();
+
+            parse_timezone(&name, data, interval)
+        }
+        _ => Ok(Tz::UTC),
+    }
+}
+
+pub fn time_to_literal(date: &DateTime<Tz>, interval: Interval) -> Literal {
+    let mut object = HashMap::new();
+
+    object.insert(
+        "milliseconds".to_owned(),
+        PrimitiveInt::get_literal(date.timestamp_millis(), interval)
+    );
+    if date.timezone() != Tz::UTC {
+        object.insert(
+            "timezone".to_owned(),
+            PrimitiveString::get_literal(date.timezone().name(), interval)
+        );
+    }
+
+    let mut lit = PrimitiveObject::get_literal(&object, interval);
+    lit.set_content_type("time");
+
+    lit
+}
+
+pub fn with_timezone(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "usage: Time().with_timezone(timezone: String), timezone being an IANA timezone such as 'Europe/Paris'";
+
+    let name = get_date_string(args, 0, data, interval, usage)?;
+    let tz = parse_timezone(&name, data, interval)?;
+    let date = get_time(object, data, interval)?;
+
+    Ok(time_to_literal(&date.with_timezone(&tz), interval))
+}
+
+pub fn add_duration(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+    unit: &str,
+) -> Result<Literal, ErrorInfo> {
+    let usage = format!("usage: Time().add_{}(value: Integer)", unit);
+
+    let value = get_int_arg(args, 0, data, interval, &usage)?;
+    let date = get_time(object, data, interval)?;
+
+    match add_to_date(&date, value, unit) {
+        Some(date) => Ok(time_to_literal(&date, interval)),
+        None => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            format!("add_{}({}) is out of the range of supported dates", unit, value),
+        ))
+    }
+}
+
+/**
+ * time.diff(other, unit) is the time elapsed from other to time, rounded toward zero.
+ * Months and years are calendar months in the timezone of time.
+ */
+pub fn diff(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "usage: Time().diff(other: Time, unit: String), unit being one of years, months, weeks, days, hours, minutes, seconds or milliseconds";
+
+    let other = match args.get("arg0") {
+        Some(lit) if lit.content_type == "time" => {
+            Literal::get_value::<HashMap<String, Literal>>(
+                &lit.primitive,
+                &data.context.flow,
+                interval,
+                usage.to_owned(),
+            )?.to_owned()
+        }
+        _ => return Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            usage.to_owned(),
+        ))
+    };
+    let unit = match args.get("arg1") {
+        Some(_) => get_date_string(args, 1, data, interval, usage)?,
+        None => "milliseconds".to_owned(),
+    };
+
+    let date = get_time(object, data, interval)?;
+    let other = get_time(&other, data, interval)?.with_timezone(&date.timezone());
+    let millis = date.timestamp_millis() - other.timestamp_millis();
+
+    let diff = match unit.as_str() {
+        "years" => months_between(&other, &date) / 12,
+        "months" => months_between(&other, &date),
+        "weeks" => millis / 604_800_000,
+        "days" => millis / 86_400_000,
+        "hours" => millis / 3_600_000,
+        "minutes" => millis / 60_000,
+        "seconds" => millis / 1_000,
+        "milliseconds" => millis,
+        _ => return Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            usage.to_owned(),
+        ))
+    };
+
+    Ok(PrimitiveInt::get_literal(diff, interval))
+}
+
+/**
+ * Weeks start on Monday, as ISO weeks
+ */
+pub fn start_of(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "usage: Time().start_of(unit: String), unit being one of year, month, week, day, hour, minute or second";
+
+    let unit = get_date_string(args, 0, data, interval, usage)?;
+    let date = get_time(object, data, interval)?;
+
+    match get_start_of(&date, &unit) {
+        Some(Some(date)) => Ok(time_to_literal(&date, interval)),
+        Some(None) => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            "start_of: date out of the range of supported dates".to_owned(),
+        )),
+        None => Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            usage.to_owned(),
+        ))
+    }
+}
+
+/**
+ * ISO day of the week, from 1 (Monday) to 7 (Sunday)
+ */
+pub fn weekday(
+    object: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let date = get_time(object, data, interval)?;
+
+    Ok(PrimitiveInt::get_literal(date.weekday().number_from_monday() as i64, interval))
+}
+
+pub fn iso_week(
+    object: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let date = get_time(object, data, interval)?;
+
+    Ok(PrimitiveInt::get_literal(date.iso_week().week() as i64, interval))
+}
+
+/**
+ * Without a format, the date is formatted as RFC 3339 with the offset of its timezone.
+ * An optional locale (en, fr, de, es, it, pt or nl) translates the names of week days and months.
+ */
+pub fn format_date(
+    object: &HashMap<String, Literal>,
+    args: &HashMap<String, Literal>,
+    data: &mut Data,
+    interval: Interval,
+) -> Result<Literal, ErrorInfo> {
+    let usage = "Time().format(format: String, locale: String)";
+
+    let date = match get_time(object, data, interval) {
+        Ok(date) => date,
+        Err(_) => return Err(gen_error_info(
+            Position::new(interval, &data.context.flow,),
+            format!("usage: {}", usage),
+        ))
+    };
+
+    if args.is_empty() {
+        let formatted = date.to_rfc3339_opts(SecondsFormat::Millis, true);
+        return Ok(PrimitiveString::get_literal(&formatted, interval));
+    }
+
+    let format = get_date_string(args, 0, data, interval, "format parameter must be of type string")?;
+    let format = match args.get("arg1") {
+        Some(_) => {
+            let locale = get_date_string(args, 1, data, interval, "locale parameter must be of type string")?;
+
+            match get_locale(&locale) {
+                Some(names) => localize_format(&format, &date, names),
+                None => return Err(gen_error_info(
+                    Position::new(interval, &data.context.flow,),
+                    format!(
+                        "unknown locale '{}', supported locales are: {}",
+                        locale,
+                        LOCALES.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", ")
+                    ),
+                ))
+            }
+        }
+        None => format,
+    };
+
+    Ok(PrimitiveString::get_literal(&date.format(&format).to_string(), interval))
+}
+
 pub fn get_date(args: &HashMap<String, Literal>) -> [i64; 7] {
 
     let mut date: [i64; 7] = [0; 7];
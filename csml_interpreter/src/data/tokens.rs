@@ -151,12 +151,14 @@ pub const FILE: &str = "File";
 pub const DEBUG: &str = "Debug";
 pub const UUID: &str = "UUID";
 pub const TIME: &str = "Time";
+pub const FORMAT_MESSAGE: &str = "FormatMessage";
+pub const HANDOFF: &str = "Handoff";
 
 pub const OBJECT: &str = "Object";
 
 pub const BUILT_IN: &[&str] = &[
     ONE_OF, SHUFFLE, LENGTH, FIND, RANDOM, FLOOR, FN, APP, HTTP, OBJECT, DEBUG, UUID, BASE64, HEX,
-    JWT, CRYPTO, TIME
+    JWT, CRYPTO, TIME, FORMAT_MESSAGE, HANDOFF
 ];
 
 pub const FROM_FILE: &str = "FromFile";
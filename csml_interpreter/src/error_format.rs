@@ -139,7 +139,10 @@ pub const ERROR_HTTP: &str =
 pub const ERROR_HTTP_GET_VALUE: &str =
 "not found in HTTP object. Use the HTTP() builtin to construct the correct object to make HTTP calls";
 pub const ERROR_HTTP_QUERY_VALUES: &str =
-    "must have a value of type String. Example: {key: \"value\"}";
+    "must have a value of type String, Int, Float, Boolean or an Array of them. Example: {key: \"value\", ids: [1, 2]}";
+pub const ERROR_HTTP_URL: &str = "is not a valid url";
+pub const ERROR_FORMAT_MESSAGE: &str =
+    "FormatMessage builtin expects a message of type String, values of type Object and an optional locale of type String. Example: FormatMessage(\"{count, plural, one {# pizza} other {# pizzas}}\", {\"count\": 2}, \"en\")";
 pub const ERROR_BUILTIN_UNKNOWN: &str = "Unknown builtin";
 
 // ### native Components
@@ -160,6 +163,14 @@ pub const ERROR_BOOLEAN_UNKNOWN_METHOD: &str = "is not a method of Boolean";
 // #### NUMBER
 pub const ERROR_NUMBER_POW: &str =
     "[pow] takes one parameter of type int or float usage: number.pow(42)";
+pub const ERROR_NUMBER_FORMAT: &str =
+    "[format_number] takes a locale of type String and an optional number of decimals of type Int. Usage: number.format_number(\"fr\", 2)";
+pub const ERROR_NUMBER_FORMAT_CURRENCY: &str =
+    "[format_currency] takes a locale and an ISO 4217 currency code of type String. Usage: number.format_currency(\"fr\", \"EUR\")";
+pub const ERROR_NUMBER_FORMAT_PERCENT: &str =
+    "[format_percent] takes a locale of type String and an optional number of decimals of type Int. Usage: number.format_percent(\"fr\", 1)";
+pub const ERROR_NUMBER_PLURAL: &str =
+    "[plural] takes a locale of type String and returns the plural category of the number. Usage: number.plural(\"fr\")";
 
 // #### Float
 pub const ERROR_FLOAT_UNKNOWN_METHOD: &str = "is not a method of Float";
@@ -192,6 +203,22 @@ pub const ERROR_STRING_SPLIT: &str =
     "[split] takes one parameter of type String. Usage: string.split(\"separator\")";
 pub const ERROR_STRING_MATCH_REGEX: &str =
     "[match_regex] takes one parameter of type String. Usage: string.match_regex(\"regex\")";
+pub const ERROR_STRING_CAPTURE_REGEX: &str =
+    "[capture_regex] takes one parameter of type String. Usage: string.capture_regex(\"(?P<id>[0-9]+)\")";
+pub const ERROR_STRING_FIND_ALL_REGEX: &str =
+    "[find_all_regex] takes one parameter of type String. Usage: string.find_all_regex(\"[0-9]+\")";
+pub const ERROR_STRING_REPLACE: &str =
+    "[replace] takes two parameters of type String, the first one not empty. Usage: string.replace(\"old\", \"new\")";
+pub const ERROR_STRING_REPLACE_ALL: &str =
+    "[replace_all] takes two parameters of type String, the first one not empty. Usage: string.replace_all(\"old\", \"new\")";
+pub const ERROR_STRING_REPLACE_REGEX: &str =
+    "[replace_regex] takes two parameters of type String. Usage: string.replace_regex(\"([0-9]+)\", \"#$1\")";
+pub const ERROR_STRING_REPLACE_LENGTH: &str =
+    "[replace_all] and [replace_regex] can not make a string longer than 100000 bytes";
+pub const ERROR_STRING_SPLIT_REGEX: &str =
+    "[split_regex] takes one parameter of type String. Usage: string.split_regex(\"[,;]\")";
+pub const ERROR_STRING_PAD: &str =
+    "[pad_start] and [pad_end] take a length of type Int, at most 10000, and an optional non empty String. Usage: string.pad_start(5, \"0\")";
 pub const ERROR_STRING_POW: &str =
     "[pow] takes one parameter of type Float or Int. Usage: string.pow(number)";
 pub const ERROR_STRING_COS: &str = "[cos] the string must be of numeric type in order to use cos. Verify first with 'string.is_number() == true' ";
@@ -268,6 +295,16 @@ pub const ERROR_HTTP_SET: &str =
     "[set] takes one parameter of type Object. Usage: HTTP(...).set( {\"key\": 42} )";
 pub const ERROR_HTTP_QUERY: &str =
     "[query] takes one parameter of type Object. Usage: HTTP(...).query( {\"key\": 42} )";
+pub const ERROR_HTTP_TIMEOUT: &str =
+    "[timeout] takes one parameter of type Int, the timeout in milliseconds. Usage: HTTP(...).timeout(5000)";
+pub const ERROR_HTTP_AUTH: &str =
+    "[auth] takes a user and a password of type String for basic auth, or a bearer token. Usage: HTTP(...).auth(\"user\", \"password\")";
+pub const ERROR_HTTP_FORM: &str =
+    "[form] takes one parameter of type Object. Usage: HTTP(...).form( {\"key\": \"value\"} )";
+pub const ERROR_HTTP_RAW_BODY: &str =
+    "[raw_body] takes a body and an optional content type of type String. Usage: HTTP(...).raw_body(\"<xml/>\", \"application/xml\")";
+pub const ERROR_HTTP_FULL_RESPONSE: &str =
+    "[full_response] takes no parameter. Usage: HTTP(...).full_response().get().send()";
 
 pub const ERROR_HTTP_SEND: &str = "[send] HTTP Object is bad formatted read doc for correct usage";
 pub const ERROR_HTTP_UNKNOWN_METHOD: &str = "is not a method of HTTP";
@@ -47,6 +47,8 @@ pub fn match_builtin(
     msg_data: &mut MessageData,
     sender: &Option<mpsc::Sender<MSG>>,
 ) -> Result<Literal, ErrorInfo> {
+    let _span = tracing::debug_span!("builtin", name).entered();
+
     match name {
         HTTP => http(args, &data.context.flow, interval),
         BASE64 => base64(args, &data.context.flow, interval),
@@ -62,7 +64,9 @@ pub fn match_builtin(
         UUID => uuid_command(args, &data.context.flow, interval),
         JWT => jwt(args, &data.context.flow, interval),
         CRYPTO => crypto(args, &data.context.flow, interval),
-        TIME => time(args, &data.context.flow, interval),
+        TIME => time(args, &data.context, interval),
+        FORMAT_MESSAGE => format_message(args, &data.context.flow, interval),
+        HANDOFF => handoff(args, interval, sender),
 
         //old builtin
         _object => object(args, &data.context.flow, interval),
@@ -4,11 +4,12 @@ use crate::data::primitive::{PrimitiveObject, PrimitiveString, PrimitiveType};
 use crate::data::{ast::Interval, ApiInfo, ArgsType, Client, Data, Literal, MessageData, MSG};
 use crate::error_format::*;
 use crate::interpreter::{
-    builtins::{http::http_request, tools::*},
+    builtins::{http::http_request_with_policy, tools::*},
     json_to_rust::interpolate,
 };
 
 use std::{collections::HashMap, env, sync::mpsc};
+use url::Url;
 
 fn format_body(args: &ArgsType, flow_name: &str, interval: Interval, client: Client) -> Result<Literal, ErrorInfo> {
     let mut map: HashMap<String, Literal> = HashMap::new();
@@ -110,7 +111,17 @@ pub fn api(
     http.insert("query".to_owned(), lit_query);
     http.insert("body".to_owned(), body);
 
-    match http_request(&http, ureq::post, &data.context.flow, interval) {
+    // fn_endpoint is set by the operator, often on localhost or on a private network,
+    // so its host is never blocked by the HTTP policy of the bot
+    let policy = data.context.http_policy.as_ref().map(|policy| {
+        let mut policy = policy.to_owned();
+        if let Some(host) = Url::parse(&url).ok().and_then(|url| url.host_str().map(|host| host.to_owned())) {
+            policy.trusted_hosts.push(host);
+        }
+        policy
+    });
+
+    match http_request_with_policy(&http, "POST", &data.context, policy.as_ref(), interval) {
         Ok(value) => match value.get("data") {
             Some(value) => interpolate(value, interval, data, msg_data, sender),
             None => {
@@ -1,5 +1,5 @@
 use crate::data::position::Position;
-use crate::data::primitive::{object::PrimitiveObject, PrimitiveType};
+use crate::data::primitive::{object::PrimitiveObject, tools_number, PrimitiveString, PrimitiveType};
 use crate::data::{ast::Interval, ArgsType, Literal};
 use crate::error_format::*;
 use std::collections::HashMap;
@@ -54,3 +54,34 @@ pub fn hex(args: ArgsType, flow_name: &str ,interval: Interval) -> Result<Litera
         )),
     }
 }
+
+pub fn format_message(args: ArgsType, flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
+    let error = || gen_error_info(Position::new(interval, flow_name), ERROR_FORMAT_MESSAGE.to_owned());
+
+    let message = match args.get("message", 0) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            literal.primitive.to_string()
+        }
+        _ => return Err(error()),
+    };
+    let values = match args.get("values", 1).map(|literal| literal.primitive.to_json()) {
+        Some(serde_json::Value::Object(values)) => values,
+        None => serde_json::Map::new(),
+        Some(_) => return Err(error()),
+    };
+    let locale = match args.get("locale", 2) {
+        Some(literal) if literal.primitive.get_type() == PrimitiveType::PrimitiveString => {
+            literal.primitive.to_string()
+        }
+        None => "en".to_owned(),
+        Some(_) => return Err(error()),
+    };
+
+    match tools_number::format_message(&message, &values, &locale) {
+        Ok(result) => Ok(PrimitiveString::get_literal(&result, interval)),
+        Err(err) => Err(gen_error_info(
+            Position::new(interval, flow_name),
+            format!("FormatMessage: {}", err),
+        )),
+    }
+}
@@ -1,10 +1,11 @@
 use crate::data::position::Position;
 use crate::data::primitive::{
-    PrimitiveArray, PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveString,
+    PrimitiveArray, PrimitiveBoolean, PrimitiveFloat, PrimitiveInt, PrimitiveNull, PrimitiveString,
 };
+use std::sync::mpsc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::data::{ast::Interval, ArgsType, Literal, MSG};
 use crate::error_format::*;
 use uuid::v1::{Context, Timestamp};
 use uuid::Uuid;
@@ -213,3 +214,18 @@ pub fn uuid_command(args: ArgsType, flow_name: &str, interval: Interval) -> Resu
         )),
     }
 }
+
+pub fn handoff(
+    args: ArgsType,
+    interval: Interval,
+    sender: &Option<mpsc::Sender<MSG>>,
+) -> Result<Literal, ErrorInfo> {
+    let reason = match args.get("reason", 0) {
+        Some(literal) => literal.primitive.to_json(),
+        None => serde_json::Value::Null,
+    };
+
+    MSG::send(sender, MSG::Handoff { reason });
+
+    Ok(PrimitiveNull::get_literal(interval))
+}
@@ -1,10 +1,22 @@
 use crate::data::error_info::ErrorInfo;
 use crate::data::position::Position;
 use crate::data::primitive::{object::PrimitiveObject, string::PrimitiveString, PrimitiveType};
-use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::data::http_transport::{HttpBody, HttpRequest, HttpResponse, HttpTransport, UreqTransport};
+use crate::data::{ast::Interval, ArgsType, Context, HttpPolicy, Literal};
 use crate::error_format::*;
-use std::collections::HashMap;
-use std::env;
+use crate::metrics::Metric;
+use std::{collections::HashMap, sync::atomic::Ordering, time::Duration};
+use url::Url;
+
+pub const HTTP_BUILTIN_REQUESTS: Metric = Metric {
+    name: "csml_http_builtin_requests_total",
+    help: "Number of requests sent with the HTTP builtin",
+};
+
+pub const HTTP_BUILTIN_DURATION: Metric = Metric {
+    name: "csml_http_builtin_duration_seconds",
+    help: "Duration of the requests sent with the HTTP builtin",
+};
 
 ////////////////////////////////////////////////////////////////////////////////
 /// PRIVATE FUNCTIONS
@@ -27,70 +39,248 @@ fn get_value<'lifetime, T: 'static>(
     }
 }
 
+/**
+ * Query values are percent-encoded, arrays are repeated parameters (ids=1&ids=2),
+ * null values are skipped, and parameters already in the url are kept
+ */
+fn get_query_pairs(
+    query: &HashMap<String, Literal>,
+    flow_name: &str,
+    interval: Interval,
+) -> Result<Vec<(String, String)>, ErrorInfo> {
+    let mut keys: Vec<&String> = query.keys().collect();
+    keys.sort();
+
+    let mut pairs = vec![];
+    for key in keys {
+        let literal = &query[key];
+        let values = match literal.primitive.get_type() {
+            PrimitiveType::PrimitiveArray => Literal::get_value::<Vec<Literal>>(
+                &literal.primitive,
+                flow_name,
+                interval,
+                format!("'{}' {}", key, ERROR_HTTP_QUERY_VALUES),
+            )?
+            .iter()
+            .collect(),
+            _ => vec![literal],
+        };
+
+        for value in values {
+            match value.primitive.get_type() {
+                PrimitiveType::PrimitiveNull => {}
+                PrimitiveType::PrimitiveString
+                | PrimitiveType::PrimitiveInt
+                | PrimitiveType::PrimitiveFloat
+                | PrimitiveType::PrimitiveBoolean => {
+                    pairs.push((key.to_owned(), value.primitive.to_string()))
+                }
+                _ => {
+                    return Err(gen_error_info(
+                        Position::new(interval, flow_name),
+                        format!("'{}' {}", key, ERROR_HTTP_QUERY_VALUES),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
 fn get_url(object: &HashMap<String, Literal>, flow_name: &str, interval: Interval) -> Result<String, ErrorInfo> {
-    let url = &mut get_value::<String>("url", object, flow_name,interval, ERROR_HTTP_GET_VALUE)?.to_owned();
+    let url = get_value::<String>("url", object, flow_name,interval, ERROR_HTTP_GET_VALUE)?;
     let query =
         get_value::<HashMap<String, Literal>>("query", object, flow_name,interval, ERROR_HTTP_GET_VALUE)?;
 
-    if !query.is_empty() {
-        let length = query.len();
+    let pairs = get_query_pairs(query, flow_name, interval)?;
+    if pairs.is_empty() {
+        return Ok(url.to_owned());
+    }
 
-        url.push_str("?");
+    let mut url = match Url::parse(url) {
+        Ok(url) => url,
+        Err(err) => {
+            return Err(gen_error_info(
+                Position::new(interval, flow_name),
+                format!("'{}' {}: {}", url, ERROR_HTTP_URL, err),
+            ))
+        }
+    };
+    url.query_pairs_mut().extend_pairs(pairs);
 
-        for (index, key) in query.keys().enumerate() {
-            let value = get_value::<String>(key, query, flow_name, interval, ERROR_HTTP_QUERY_VALUES)?;
+    Ok(url.to_string())
+}
 
-            url.push_str(key);
-            url.push_str("=");
-            url.push_str(value);
+fn check_policy(
+    url: &str,
+    policy: &HttpPolicy,
+    context: &Context,
+    interval: Interval,
+) -> Result<(), ErrorInfo> {
+    let count = context.http_requests.fetch_add(1, Ordering::SeqCst) + 1;
+    policy
+        .check_request_count(count)
+        .map_err(|err| gen_error_info(Position::new(interval, &context.flow), err))?;
 
-            if index + 1 < length {
-                url.push_str("&");
-            }
-        }
+    let host = match Url::parse(url) {
+        Ok(url) => url.host_str().map(|host| host.to_owned()),
+        Err(_) => None,
+    };
+    match host {
+        Some(host) => policy
+            .check_host(&host)
+            .map_err(|err| gen_error_info(Position::new(interval, &context.flow), err)),
+        None => Err(gen_error_info(
+            Position::new(interval, &context.flow),
+            format!("'{}' {}", url, ERROR_HTTP_URL),
+        )),
     }
+}
+
+/**
+ * The body is parsed when the response is JSON and kept as text otherwise,
+ * an empty body is null
+ */
+fn get_full_response(response: HttpResponse) -> serde_json::Value {
+    let is_json = response.is_json();
+
+    let body = String::from_utf8_lossy(&response.body).to_string();
+    let body = match body {
+        body if body.is_empty() => serde_json::Value::Null,
+        body if is_json => {
+            serde_json::from_str(&body).unwrap_or(serde_json::Value::String(body))
+        }
+        body => serde_json::Value::String(body),
+    };
 
-    Ok(url.to_owned())
+    serde_json::json!({
+        "status": response.status,
+        "headers": response.headers,
+        "body": body,
+    })
 }
 
-////////////////////////////////////////////////////////////////////////////////
-/// PUBLIC FUNCTIONS
-////////////////////////////////////////////////////////////////////////////////
+fn get_body(
+    object: &HashMap<String, Literal>,
+    flow_name: &str,
+    interval: Interval,
+) -> Result<HttpBody, ErrorInfo> {
+    match (object.get("form"), object.get("raw_body"), object.get("body")) {
+        (Some(_), _, _) => {
+            let form = get_value::<HashMap<String, Literal>>("form", object, flow_name, interval, ERROR_HTTP_GET_VALUE)?;
+            let mut form: Vec<(String, String)> = form
+                .iter()
+                .map(|(key, value)| match value.primitive.get_type() {
+                    PrimitiveType::PrimitiveNull => (key.to_owned(), String::new()),
+                    _ => (key.to_owned(), value.primitive.to_string()),
+                })
+                .collect();
+            form.sort();
 
-pub fn http_request(
+            Ok(HttpBody::Form(form))
+        }
+        (None, Some(_), _) => {
+            let body = get_value::<String>("raw_body", object, flow_name, interval, ERROR_HTTP_GET_VALUE)?;
+
+            Ok(HttpBody::Raw(body.to_owned()))
+        }
+        (None, None, Some(body)) => Ok(HttpBody::Json(body.primitive.to_json())),
+        (None, None, None) => Ok(HttpBody::Empty),
+    }
+}
+
+fn get_request(
     object: &HashMap<String, Literal>,
-    function: fn(&str) -> ureq::Request,
+    method: &str,
     flow_name: &str,
     interval: Interval,
-) -> Result<serde_json::Value, ErrorInfo> {
+) -> Result<HttpRequest, ErrorInfo> {
     let url = get_url(object, flow_name, interval)?;
 
     let header =
         get_value::<HashMap<String, Literal>>("header", object, flow_name, interval, ERROR_HTTP_GET_VALUE)?;
-
-    let mut request = function(&url);
-
+    let mut headers = HashMap::new();
     for key in header.keys() {
         let value = get_value::<String>(key, header, flow_name, interval, ERROR_HTTP_GET_VALUE)?;
 
-        request.set(key, value);
+        headers.insert(key.to_owned(), value.to_owned());
     }
 
-    let response = match object.get("body") {
-        Some(body) => request.send_json(body.primitive.to_json()),
-        None => request.call(),
-    };
+    let timeout = match object.get("timeout") {
+        Some(timeout) => {
+            let timeout = Literal::get_value::<i64>(
+                &timeout.primitive,
+                flow_name,
+                interval,
+                ERROR_HTTP_TIMEOUT.to_owned(),
+            )?;
 
-    if let Some(err) = response.synthetic_error() {
-        if let Ok(var) = env::var("DEBUG") {
-            if var == "true" {
-                eprintln!("FN request failed: {:?}", err.body_text());
-            }
+            Some(Duration::from_millis(*timeout as u64))
         }
-        return Err(gen_error_info(Position::new(interval, flow_name), err.body_text()));
+        None => None,
+    };
+
+    Ok(HttpRequest {
+        method: method.to_owned(),
+        url,
+        headers,
+        body: get_body(object, flow_name, interval)?,
+        timeout,
+    })
+}
+
+////////////////////////////////////////////////////////////////////////////////
+/// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Send the request described by an HTTP object with the given method (GET, POST...),
+ * within the HTTP policy of the context if there is one, and with the HTTP transport
+ * of the context if one is set
+ */
+pub fn http_request(
+    object: &HashMap<String, Literal>,
+    method: &str,
+    context: &Context,
+    interval: Interval,
+) -> Result<serde_json::Value, ErrorInfo> {
+    http_request_with_policy(object, method, context, context.http_policy.as_ref(), interval)
+}
+
+/**
+ * Same as http_request, within the given HTTP policy instead of the one of the context
+ */
+pub fn http_request_with_policy(
+    object: &HashMap<String, Literal>,
+    method: &str,
+    context: &Context,
+    policy: Option<&HttpPolicy>,
+    interval: Interval,
+) -> Result<serde_json::Value, ErrorInfo> {
+    let flow_name: &str = &context.flow;
+    let request = get_request(object, method, flow_name, interval)?;
+
+    if let Some(policy) = policy {
+        check_policy(&request.url, policy, context, interval)?;
+    }
+
+    let response = match &context.http_transport {
+        Some(transport) => transport.send(&request, policy),
+        None => UreqTransport.send(&request, policy),
+    };
+    let response = response
+        .and_then(|response| response.check_size(policy).map(|_| response))
+        .map_err(|err| {
+            tracing::warn!(error = %err, "HTTP request failed");
+            gen_error_info(Position::new(interval, flow_name), err)
+        })?;
+
+    if object.contains_key("full_response") {
+        return Ok(get_full_response(response));
     }
 
-    match response.into_json() {
+    match serde_json::from_slice(&response.body) {
         Ok(value) => Ok(value),
         Err(_) => Err(gen_error_info(
             Position::new(interval, flow_name),
@@ -1,6 +1,6 @@
 use crate::data::error_info::ErrorInfo;
 use crate::data::primitive::{PrimitiveObject, PrimitiveInt};
-use crate::data::{ast::Interval, ArgsType, Literal};
+use crate::data::{ast::Interval, ArgsType, Context, Literal};
 use std::{collections::HashMap};
 use chrono::{Utc};
 
@@ -8,9 +8,9 @@ use chrono::{Utc};
 /// PUBLIC FUNCTIONS
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn time(_args: ArgsType, _flow_name: &str, interval: Interval) -> Result<Literal, ErrorInfo> {
+pub fn time(_args: ArgsType, context: &Context, interval: Interval) -> Result<Literal, ErrorInfo> {
     let mut time: HashMap<String, Literal> = HashMap::new();
-    let date = Utc::now();
+    let date = context.now.unwrap_or_else(Utc::now);
 
     time.insert(
         "milliseconds".to_owned(),
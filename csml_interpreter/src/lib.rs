@@ -2,7 +2,9 @@ pub mod data;
 pub mod error_format;
 pub mod interpreter;
 pub mod linter;
+pub mod metrics;
 pub mod parser;
+pub mod test_runner;
 
 pub use interpreter::components::load_components;
 pub use parser::step_checksum::get_step;
@@ -24,7 +26,7 @@ use linter::{linter::lint_bot, FlowToValidate};
 use parser::ExitCondition;
 
 use std::collections::HashMap;
-use std::sync::mpsc;
+use std::sync::{atomic::Ordering, mpsc};
 
 ////////////////////////////////////////////////////////////////////////////////
 // PRIVATE FUNCTIONS
@@ -178,6 +180,8 @@ pub fn interpret(
     let mut step = context.step.to_owned();
 
     let mut step_count = 0;
+    // the HTTP policy limits the number of requests per interaction
+    context.http_requests.store(0, Ordering::SeqCst);
 
     let mut step_vars = match &context.hold {
         Some(hold) => get_hashmap_from_mem(&hold.step_vars, &flow),
@@ -233,6 +237,9 @@ pub fn interpret(
             &native,
         );
 
+        let span = tracing::info_span!("step", flow = %flow, step = %step);
+        let _enter = span.enter();
+
         msg_data = msg_data + execute_step(&step, &ast, &mut data, &sender);
         flow = data.context.flow.to_string();
         step = data.context.step.to_string();
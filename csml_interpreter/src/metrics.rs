@@ -0,0 +1,153 @@
+/**
+ * Process-wide counters and histograms, rendered in the Prometheus text format.
+ * Metrics are registered the first time they are updated.
+ */
+use lazy_static::*;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Instant;
+
+const BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+type Labels = Vec<(String, String)>;
+
+pub struct Metric {
+    pub name: &'static str,
+    pub help: &'static str,
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Default)]
+struct Registry {
+    help: BTreeMap<&'static str, &'static str>,
+    counters: BTreeMap<&'static str, BTreeMap<Labels, f64>>,
+    histograms: BTreeMap<&'static str, BTreeMap<Labels, Histogram>>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Mutex<Registry> = Mutex::new(Registry::default());
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    labels
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+fn format_labels(labels: &[(String, String)], extra: Option<(&str, &str)>) -> String {
+    let mut all: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+        .collect();
+
+    if let Some((key, value)) = extra {
+        all.push(format!("{}=\"{}\"", key, value));
+    }
+
+    match all.is_empty() {
+        true => String::new(),
+        false => format!("{{{}}}", all.join(",")),
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn inc_counter(metric: &Metric, labels: &[(&str, &str)], value: f64) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.help.insert(metric.name, metric.help);
+
+        let counter = registry
+            .counters
+            .entry(metric.name)
+            .or_default()
+            .entry(to_labels(labels))
+            .or_insert(0.0);
+        *counter += value;
+    }
+}
+
+pub fn observe(metric: &Metric, labels: &[(&str, &str)], value: f64) {
+    if let Ok(mut registry) = REGISTRY.lock() {
+        registry.help.insert(metric.name, metric.help);
+
+        let histogram = registry
+            .histograms
+            .entry(metric.name)
+            .or_default()
+            .entry(to_labels(labels))
+            .or_default();
+
+        for (index, bucket) in BUCKETS.iter().enumerate() {
+            if value <= *bucket {
+                histogram.buckets[index] += 1;
+            }
+        }
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+}
+
+/**
+ * Observe the number of seconds elapsed since `start`
+ */
+pub fn observe_since(metric: &Metric, labels: &[(&str, &str)], start: Instant) {
+    observe(metric, labels, start.elapsed().as_secs_f64());
+}
+
+pub fn render() -> String {
+    let registry = match REGISTRY.lock() {
+        Ok(registry) => registry,
+        Err(_) => return String::new(),
+    };
+    let mut text = String::new();
+
+    for (name, values) in registry.counters.iter() {
+        writeln!(text, "# HELP {} {}", name, registry.help[name]).ok();
+        writeln!(text, "# TYPE {} counter", name).ok();
+
+        for (labels, value) in values.iter() {
+            writeln!(text, "{}{} {}", name, format_labels(labels, None), value).ok();
+        }
+    }
+
+    for (name, values) in registry.histograms.iter() {
+        writeln!(text, "# HELP {} {}", name, registry.help[name]).ok();
+        writeln!(text, "# TYPE {} histogram", name).ok();
+
+        for (labels, histogram) in values.iter() {
+            for (bucket, count) in BUCKETS.iter().zip(histogram.buckets.iter()) {
+                let le = bucket.to_string();
+                let labels = format_labels(labels, Some(("le", &le)));
+                writeln!(text, "{}_bucket{} {}", name, labels, count).ok();
+            }
+
+            let labels_inf = format_labels(labels, Some(("le", "+Inf")));
+            writeln!(text, "{}_bucket{} {}", name, labels_inf, histogram.count).ok();
+            writeln!(text, "{}_sum{} {}", name, format_labels(labels, None), histogram.sum).ok();
+            writeln!(text, "{}_count{} {}", name, format_labels(labels, None), histogram.count).ok();
+        }
+    }
+
+    text
+}
@@ -0,0 +1,371 @@
+pub mod conversation;
+pub mod test_file;
+
+pub use conversation::{get_json_event, Conversation};
+pub use test_file::{Scenario, TestFile, TestInstruction};
+
+use crate::data::diff::{diff_lines, DiffLine};
+use crate::data::{CsmlBot, Message, MockTransport};
+use crate::{load_components, validate_bot};
+
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestFailure {
+    pub line: usize,
+    pub message: String,
+    // expected and actual values, line by line
+    pub diff: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioResult {
+    pub name: String,
+    pub line: usize,
+    // the first expectation that failed, the scenario stops there
+    pub failure: Option<TestFailure>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    // test files that could not be read or parsed, and bots with errors
+    pub errors: usize,
+}
+
+/**
+ * A scenario being run: its conversation with the bot, and the messages
+ * of the last event that were not checked yet
+ */
+struct ScenarioRun<'a> {
+    conversation: Conversation<'a>,
+    messages: VecDeque<Message>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn failure(line: usize, message: &str) -> TestFailure {
+    TestFailure {
+        line,
+        message: message.to_owned(),
+        diff: None,
+    }
+}
+
+fn failure_with_diff(line: usize, message: &str, expected: &Value, actual: &Value) -> TestFailure {
+    TestFailure {
+        line,
+        message: message.to_owned(),
+        diff: Some(get_diff(expected, actual)),
+    }
+}
+
+impl<'a> ScenarioRun<'a> {
+    fn new(bot: &'a CsmlBot, test_file: &TestFile) -> Self {
+        let mut conversation = Conversation::new(bot, test_file.metadata.to_owned());
+        conversation.http_transport = Some(Arc::new(MockTransport::new(test_file.fixtures.to_owned())));
+        conversation.now = test_file.now;
+
+        Self {
+            conversation,
+            messages: VecDeque::new(),
+        }
+    }
+
+    fn check_unexpected_messages(&mut self, line: usize) -> Result<(), TestFailure> {
+        match self.messages.pop_front() {
+            Some(mut message) => Err(failure_with_diff(
+                line,
+                "unexpected message",
+                &Value::Null,
+                &message.message_to_json(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn run(&mut self, line: usize, instruction: &TestInstruction) -> Result<(), TestFailure> {
+        match instruction {
+            TestInstruction::Event(event) => {
+                self.check_unexpected_messages(line)?;
+                let messages = self.conversation.send_event(event.to_owned());
+                self.messages.extend(messages);
+
+                Ok(())
+            }
+            TestInstruction::Say(expected) => {
+                let expected = match expected {
+                    Value::String(text) => serde_json::json!({
+                        "content_type": "text",
+                        "content": {"text": text},
+                    }),
+                    expected => expected.to_owned(),
+                };
+
+                match self.messages.pop_front() {
+                    Some(mut message) => {
+                        let actual = message.message_to_json();
+                        match actual == expected {
+                            true => Ok(()),
+                            false => Err(failure_with_diff(line, "unexpected message", &expected, &actual)),
+                        }
+                    }
+                    None => Err(failure_with_diff(line, "missing message", &expected, &Value::Null)),
+                }
+            }
+            TestInstruction::Error(expected) => match self.messages.pop_front() {
+                Some(message) if message.content_type == "error" => {
+                    let actual = message.content["error"].as_str().unwrap_or_default();
+                    match actual.contains(expected.as_str()) {
+                        true => Ok(()),
+                        false => Err(failure_with_diff(
+                            line,
+                            "unexpected error",
+                            &Value::String(expected.to_owned()),
+                            &Value::String(actual.to_owned()),
+                        )),
+                    }
+                }
+                Some(mut message) => Err(failure_with_diff(
+                    line,
+                    "expected an error",
+                    &Value::String(expected.to_owned()),
+                    &message.message_to_json(),
+                )),
+                None => Err(failure(line, &format!("missing error '{}'", expected))),
+            },
+            TestInstruction::Memory(key, expected) => {
+                let actual = self.conversation.memories.get(key).cloned().unwrap_or(Value::Null);
+                match actual == *expected {
+                    true => Ok(()),
+                    false => Err(failure_with_diff(
+                        line,
+                        &format!("unexpected value of memory '{}'", key),
+                        expected,
+                        &actual,
+                    )),
+                }
+            }
+            TestInstruction::Flow(expected) => match self.conversation.flow == *expected {
+                true => Ok(()),
+                false => Err(failure(
+                    line,
+                    &format!("expected flow '{}', found '{}'", expected, self.conversation.flow),
+                )),
+            },
+            TestInstruction::Step(expected) => match self.conversation.step == *expected {
+                true => Ok(()),
+                false => Err(failure(
+                    line,
+                    &format!("expected step '{}', found '{}'", expected, self.conversation.step),
+                )),
+            },
+        }
+    }
+}
+
+fn get_bot(test_file: &TestFile) -> Result<CsmlBot, String> {
+    let native_components = load_components().map_err(|err| err.format_error())?;
+    let bot = CsmlBot::new(
+        "test",
+        "test",
+        None,
+        test_file.flows.to_owned(),
+        Some(native_components),
+        None,
+        &test_file.default_flow,
+        None,
+        test_file.env.to_owned(),
+    );
+
+    match validate_bot(&bot).errors {
+        Some(errors) if !errors.is_empty() => {
+            let errors: Vec<String> = errors.iter().map(|err| err.format_error()).collect();
+            Err(format!("{}: invalid bot\n{}", test_file.path.display(), errors.join("\n")))
+        }
+        _ => Ok(bot),
+    }
+}
+
+fn find_test_files(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if path.is_file() {
+        files.push(path.to_owned());
+        return Ok(());
+    }
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect();
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            find_test_files(&entry, files)?;
+        } else if entry.to_string_lossy().ends_with(".test.csml") {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+fn write_failure(output: &mut dyn Write, failure: &TestFailure) -> io::Result<()> {
+    writeln!(output, "      line {}: {}", failure.line, failure.message)?;
+    if let Some(diff) = &failure.diff {
+        for line in diff.lines() {
+            writeln!(output, "        {}", line)?;
+        }
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Line diff of the pretty JSON of two values, from their longest common subsequence
+ */
+pub fn get_diff(expected: &Value, actual: &Value) -> String {
+    let expected = serde_json::to_string_pretty(expected).unwrap_or_default();
+    let actual = serde_json::to_string_pretty(actual).unwrap_or_default();
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    diff_lines(&expected, &actual)
+        .iter()
+        .map(|line| match line {
+            DiffLine::Same(text) => format!("  {}", text),
+            DiffLine::Removed(text) => format!("- {}", text),
+            DiffLine::Added(text) => format!("+ {}", text),
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+impl TestSummary {
+    /**
+     * 0 if every scenario passed, 1 if some failed, 2 if some test files are invalid
+     */
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            summary if summary.errors > 0 => 2,
+            summary if summary.failed > 0 => 1,
+            _ => 0,
+        }
+    }
+}
+
+/**
+ * Run each scenario of the test file from the start of the default flow, with no memories.
+ * HTTP requests are answered by the fixtures of the test file.
+ */
+pub fn run_test_file(test_file: &TestFile) -> Result<Vec<ScenarioResult>, String> {
+    let bot = get_bot(test_file)?;
+
+    let results = test_file
+        .scenarios
+        .iter()
+        .map(|scenario| {
+            let mut run = ScenarioRun::new(&bot, test_file);
+            let end = scenario
+                .instructions
+                .last()
+                .map_or(scenario.line, |(line, _)| *line);
+
+            let failure = scenario
+                .instructions
+                .iter()
+                .try_for_each(|(line, instruction)| run.run(*line, instruction))
+                .and_then(|_| run.check_unexpected_messages(end))
+                .err();
+
+            ScenarioResult {
+                name: scenario.name.to_owned(),
+                line: scenario.line,
+                failure,
+            }
+        })
+        .collect();
+
+    Ok(results)
+}
+
+/**
+ * Run the given test files, and the *.test.csml files of the given directories,
+ * writing a report of the results to the output
+ */
+pub fn run_tests(paths: &[PathBuf], output: &mut dyn Write) -> io::Result<TestSummary> {
+    let mut summary = TestSummary::default();
+    let mut files = vec![];
+
+    for path in paths {
+        if let Err(err) = find_test_files(path, &mut files) {
+            writeln!(output, "error: {}: {}", path.display(), err)?;
+            summary.errors += 1;
+        }
+    }
+
+    for path in files {
+        writeln!(output, "{}", path.display())?;
+
+        let results = match TestFile::read(&path).and_then(|test_file| run_test_file(&test_file)) {
+            Ok(results) => results,
+            Err(err) => {
+                writeln!(output, "  error: {}", err)?;
+                summary.errors += 1;
+                continue;
+            }
+        };
+
+        for result in results.iter() {
+            match &result.failure {
+                None => {
+                    writeln!(output, "  ok      {}", result.name)?;
+                    summary.passed += 1;
+                }
+                Some(failure) => {
+                    writeln!(output, "  FAILED  {} (line {})", result.name, result.line)?;
+                    write_failure(output, failure)?;
+                    summary.failed += 1;
+                }
+            }
+        }
+    }
+
+    writeln!(
+        output,
+        "\n{} passed, {} failed, {} errors",
+        summary.passed, summary.failed, summary.errors
+    )?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_get_diff() {
+        let diff = get_diff(
+            &serde_json::json!({"a": 1, "b": 2}),
+            &serde_json::json!({"a": 1, "b": 3}),
+        );
+
+        assert_eq!(diff, "  {\n    \"a\": 1,\n-   \"b\": 2\n+   \"b\": 3\n  }");
+    }
+}
@@ -0,0 +1,194 @@
+use crate::data::ast::ForgetMemory;
+use crate::data::context::{get_hashmap_from_json, get_hashmap_from_mem};
+use crate::data::{Context, CsmlBot, Event, Hold, HttpTransport, Message, MSG};
+use crate::interpret;
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Conversation of a user with a bot, run in process without the engine and its database:
+ * the position, hold and memories are kept in memory between events.
+ */
+pub struct Conversation<'a> {
+    pub bot: &'a CsmlBot,
+    pub metadata: Value,
+    // requests are sent with ureq if there is no transport
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    pub now: Option<DateTime<Utc>>,
+    // keep the memories when a conversation ends, like the engine does
+    pub keep_memories: bool,
+    pub flow: String,
+    pub step: String,
+    pub hold: Option<Hold>,
+    pub ended: bool,
+    pub memories: Map<String, Value>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * Flow triggered by the event, like the engine does: flow_trigger events match a flow
+ * by id or name, other events match the first flow with the event as a command
+ */
+fn get_triggered_flow(bot: &CsmlBot, event: &Event) -> Option<String> {
+    let value = event.content_value.to_lowercase();
+
+    bot.flows
+        .iter()
+        .find(|flow| match event.content_type.as_str() {
+            "flow_trigger" => flow.id.to_lowercase() == value || flow.name.to_lowercase() == value,
+            _ => flow.commands.iter().any(|command| command.to_lowercase() == value),
+        })
+        .map(|flow| flow.name.to_owned())
+}
+
+fn get_content_value(content_type: &str, content: &Value) -> Result<String, String> {
+    let key = match content_type {
+        "text" => "text",
+        "payload" => "payload",
+        "flow_trigger" => "flow_id",
+        "file" | "audio" | "video" | "image" | "url" => "url",
+        content_type => return Err(format!("{} is not a valid content_type", content_type)),
+    };
+
+    match content[key].as_str() {
+        Some(value) => Ok(value.to_owned()),
+        None => Err(format!("no {} in the content of the event", key)),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+/**
+ * "hello" is a text event, otherwise {"content_type": String, "content": Object}
+ * like the payload of the requests to the engine
+ */
+pub fn get_json_event(value: &Value) -> Result<Event, String> {
+    match value {
+        Value::String(text) => Ok(Event::new("text", text, serde_json::json!({ "text": text }))),
+        Value::Object(event) => {
+            let content_type = match event.get("content_type").and_then(|value| value.as_str()) {
+                Some(content_type) => content_type.to_owned(),
+                None => return Err("no content_type in event".to_owned()),
+            };
+            let content = event.get("content").cloned().unwrap_or(Value::Null);
+            let content_value = get_content_value(&content_type, &content)?;
+
+            Ok(Event::new(&content_type, &content_value, content))
+        }
+        value => Err(format!("'{}' is not a valid event", value)),
+    }
+}
+
+impl<'a> Conversation<'a> {
+    pub fn new(bot: &'a CsmlBot, metadata: Value) -> Self {
+        Self {
+            bot,
+            metadata,
+            http_transport: None,
+            now: None,
+            keep_memories: true,
+            flow: bot.default_flow.to_owned(),
+            step: "start".to_owned(),
+            hold: None,
+            ended: false,
+            memories: Map::new(),
+        }
+    }
+
+    /**
+     * Start again from the default flow, forgetting the memories
+     */
+    pub fn reset(&mut self) {
+        self.flow = self.bot.default_flow.to_owned();
+        self.step = "start".to_owned();
+        self.hold = None;
+        self.ended = false;
+        self.memories.clear();
+    }
+
+    /**
+     * Interpret the event from the current position, and return the messages of the bot
+     */
+    pub fn send_event(&mut self, event: Event) -> Vec<Message> {
+        if let Some(flow) = get_triggered_flow(self.bot, &event) {
+            self.flow = flow;
+            self.step = "start".to_owned();
+            self.hold = None;
+        } else if self.ended {
+            self.flow = self.bot.default_flow.to_owned();
+            self.step = "start".to_owned();
+            self.hold = None;
+            if !self.keep_memories {
+                self.memories.clear();
+            }
+        }
+        self.ended = false;
+
+        let mut context = Context::new(
+            get_hashmap_from_mem(&Value::Object(self.memories.to_owned()), &self.flow),
+            get_hashmap_from_json(&self.metadata, &self.flow),
+            None,
+            self.hold.take(),
+            &self.step,
+            &self.flow,
+        );
+        context.http_transport = self.http_transport.clone();
+        context.now = self.now;
+
+        let (sender, receiver) = mpsc::channel::<MSG>();
+        let bot = self.bot.to_owned();
+        thread::spawn(move || {
+            interpret(bot, context, event, Some(sender));
+        });
+
+        let mut messages = vec![];
+        for received in receiver {
+            match received {
+                MSG::Remember(memory) => {
+                    self.memories.insert(memory.key, memory.value);
+                }
+                MSG::Forget(ForgetMemory::ALL) => self.memories.clear(),
+                MSG::Forget(ForgetMemory::SINGLE(memory)) => {
+                    self.memories.remove(&memory.ident);
+                }
+                MSG::Forget(ForgetMemory::LIST(memories)) => {
+                    for memory in memories.iter() {
+                        self.memories.remove(&memory.ident);
+                    }
+                }
+                MSG::Message(message) => messages.push(message),
+                MSG::Hold(hold) => self.hold = Some(hold),
+                MSG::Next { flow, step } => {
+                    match (flow, step) {
+                        (Some(flow), step) => {
+                            self.flow = flow;
+                            self.step = step.unwrap_or_else(|| "start".to_owned());
+                        }
+                        (None, Some(step)) => self.step = step,
+                        (None, None) => self.step = "end".to_owned(),
+                    }
+                    self.ended = self.step == "end";
+                }
+                MSG::Handoff { .. } => (),
+                MSG::Error(message) => {
+                    messages.push(message);
+                    self.ended = true;
+                }
+            }
+        }
+
+        messages
+    }
+}
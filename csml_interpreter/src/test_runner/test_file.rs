@@ -0,0 +1,286 @@
+use crate::data::{csml_flow::CsmlFlow, Event, HttpFixture};
+use crate::test_runner::conversation::get_json_event;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+////////////////////////////////////////////////////////////////////////////////
+// DATA STRUCTURE
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestInstruction {
+    // send an event to the bot
+    Event(Event),
+    // next message of the bot, a string for a text message
+    Say(Value),
+    // next message of the bot is an error containing this text
+    Error(String),
+    // value of a memory, null if it is not set
+    Memory(String, Value),
+    Flow(String),
+    Step(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    pub name: String,
+    pub line: usize,
+    pub instructions: Vec<(usize, TestInstruction)>,
+}
+
+/**
+ * A .csml test file: the flows of the bot to test and its scenarios.
+ *
+ * // paths are relative to the test file, the first flow is the default flow
+ * @flow "flows/default.csml"
+ * @flow "flows/order.csml" ["order", "pizza"]
+ * @fixtures "fixtures.json"
+ * @now "2021-06-01T10:00:00Z"
+ * @env {"api_url": "https://api.example.com"}
+ * @metadata {"firstname": "Tom"}
+ *
+ * scenario "orders a pizza":
+ *     event "order"
+ *     say "What do you want?"
+ *     event {"content_type": "payload", "content": {"payload": "pizza"}}
+ *     say {"content_type": "question", "content": {...}}
+ *     error "is not a valid"
+ *     memory order = "pizza"
+ *     flow "order"
+ *     step "end"
+ */
+#[derive(Debug, Clone)]
+pub struct TestFile {
+    pub path: PathBuf,
+    pub flows: Vec<CsmlFlow>,
+    pub default_flow: String,
+    pub fixtures: Vec<HttpFixture>,
+    pub now: Option<DateTime<Utc>>,
+    pub env: Option<Value>,
+    pub metadata: Value,
+    pub scenarios: Vec<Scenario>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PRIVATE FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+fn get_values(text: &str) -> Result<Vec<Value>, String> {
+    serde_json::Deserializer::from_str(text)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(|err| format!("invalid value '{}': {}", text, err))
+}
+
+fn get_value(text: &str) -> Result<Value, String> {
+    match get_values(text)?.as_slice() {
+        [value] => Ok(value.to_owned()),
+        _ => Err(format!("expected a single value, found '{}'", text)),
+    }
+}
+
+fn get_string(text: &str) -> Result<String, String> {
+    match get_value(text)? {
+        Value::String(string) => Ok(string),
+        _ => Err(format!("expected a string, found '{}'", text)),
+    }
+}
+
+fn get_event(text: &str) -> Result<Event, String> {
+    get_json_event(&get_value(text)?)
+}
+
+fn get_flow(dir: &Path, args: &str) -> Result<CsmlFlow, String> {
+    let (path, commands) = match get_values(args)?.as_slice() {
+        [Value::String(path)] => (path.to_owned(), vec![]),
+        [Value::String(path), Value::Array(commands)] => (
+            path.to_owned(),
+            commands
+                .iter()
+                .filter_map(|command| command.as_str().map(|command| command.to_owned()))
+                .collect(),
+        ),
+        _ => return Err("expected @flow \"path\" [\"command\", ...]".to_owned()),
+    };
+
+    let path = dir.join(path);
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("can not read flow {}: {}", path.display(), err))?;
+    let name = match path.file_stem().and_then(|name| name.to_str()) {
+        Some(name) => name.to_owned(),
+        None => return Err(format!("invalid flow path {}", path.display())),
+    };
+
+    Ok(CsmlFlow::new(&name, &name, &content, commands))
+}
+
+fn get_fixtures(dir: &Path, args: &str) -> Result<Vec<HttpFixture>, String> {
+    let path = dir.join(get_string(args)?);
+    let content = fs::read_to_string(&path)
+        .map_err(|err| format!("can not read fixtures {}: {}", path.display(), err))?;
+
+    serde_json::from_str(&content).map_err(|err| format!("invalid fixtures {}: {}", path.display(), err))
+}
+
+fn get_instruction(text: &str) -> Result<TestInstruction, String> {
+    let (keyword, args) = match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim()),
+        None => (text, ""),
+    };
+
+    match keyword {
+        "event" => Ok(TestInstruction::Event(get_event(args)?)),
+        "say" => Ok(TestInstruction::Say(get_value(args)?)),
+        "error" => Ok(TestInstruction::Error(get_string(args)?)),
+        "memory" => match args.find('=') {
+            Some(index) => Ok(TestInstruction::Memory(
+                args[..index].trim().to_owned(),
+                get_value(args[index + 1..].trim())?,
+            )),
+            None => Err("expected memory key = value".to_owned()),
+        },
+        "flow" => Ok(TestInstruction::Flow(get_string(args)?)),
+        "step" => Ok(TestInstruction::Step(get_string(args)?)),
+        keyword => Err(format!("unknown instruction '{}'", keyword)),
+    }
+}
+
+fn parse_line(test_file: &mut TestFile, dir: &Path, line: &str, index: usize) -> Result<(), String> {
+    let text = line.trim();
+
+    if let Some(name) = text.strip_prefix("scenario ") {
+        let name = name.trim().trim_end_matches(':');
+        test_file.scenarios.push(Scenario {
+            name: get_string(name)?,
+            line: index,
+            instructions: vec![],
+        });
+
+        return Ok(());
+    }
+
+    if let Some(directive) = text.strip_prefix('@') {
+        if !test_file.scenarios.is_empty() {
+            return Err("directives must be before the scenarios".to_owned());
+        }
+
+        let (name, args) = match directive.find(char::is_whitespace) {
+            Some(index) => (&directive[..index], directive[index..].trim()),
+            None => (directive, ""),
+        };
+
+        return match name {
+            "flow" => {
+                let flow = get_flow(dir, args)?;
+                if test_file.default_flow.is_empty() {
+                    test_file.default_flow = flow.name.to_owned();
+                }
+                test_file.flows.push(flow);
+                Ok(())
+            }
+            "default_flow" => {
+                test_file.default_flow = get_string(args)?;
+                Ok(())
+            }
+            "fixtures" => {
+                test_file.fixtures.append(&mut get_fixtures(dir, args)?);
+                Ok(())
+            }
+            "now" => {
+                let now = get_string(args)?;
+                let now = DateTime::parse_from_rfc3339(&now)
+                    .map_err(|_| format!("'{}' is not a valid RFC 3339 date", now))?;
+                test_file.now = Some(now.with_timezone(&Utc));
+                Ok(())
+            }
+            "env" => {
+                test_file.env = Some(get_value(args)?);
+                Ok(())
+            }
+            "metadata" => {
+                test_file.metadata = get_value(args)?;
+                Ok(())
+            }
+            name => Err(format!("unknown directive '@{}'", name)),
+        };
+    }
+
+    match test_file.scenarios.last_mut() {
+        Some(scenario) => {
+            let instruction = get_instruction(text)?;
+            scenario.instructions.push((index, instruction));
+            Ok(())
+        }
+        None => Err("instructions must be in a scenario".to_owned()),
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// PUBLIC FUNCTIONS
+////////////////////////////////////////////////////////////////////////////////
+
+impl TestFile {
+    pub fn parse(path: &Path, content: &str) -> Result<Self, String> {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let mut test_file = TestFile {
+            path: path.to_owned(),
+            flows: vec![],
+            default_flow: String::new(),
+            fixtures: vec![],
+            now: None,
+            env: None,
+            metadata: Value::Object(serde_json::Map::new()),
+            scenarios: vec![],
+        };
+
+        for (index, line) in content.lines().enumerate() {
+            let text = line.trim();
+            if text.is_empty() || text.starts_with("//") {
+                continue;
+            }
+
+            parse_line(&mut test_file, dir, line, index + 1)
+                .map_err(|err| format!("{}:{}: {}", path.display(), index + 1, err))?;
+        }
+
+        if test_file.flows.is_empty() {
+            return Err(format!("{}: no @flow to test", path.display()));
+        }
+
+        Ok(test_file)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|err| format!("can not read {}: {}", path.display(), err))?;
+
+        Self::parse(path, &content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ok_get_instruction() {
+        assert_eq!(
+            get_instruction("event \"hello\"").unwrap(),
+            TestInstruction::Event(Event::new("text", "hello", serde_json::json!({"text": "hello"})))
+        );
+        assert_eq!(
+            get_instruction(r#"event {"content_type": "payload", "content": {"payload": "pizza"}}"#).unwrap(),
+            TestInstruction::Event(Event::new("payload", "pizza", serde_json::json!({"payload": "pizza"})))
+        );
+        assert_eq!(
+            get_instruction("memory order = {\"size\": 2}").unwrap(),
+            TestInstruction::Memory("order".to_owned(), serde_json::json!({"size": 2}))
+        );
+        assert!(get_instruction("event {\"content\": {}}").is_err());
+        assert!(get_instruction("say \"a\" \"b\"").is_err());
+        assert!(get_instruction("wait 1").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::csml_bot::CsmlBot;
+use csml_interpreter::data::csml_flow::CsmlFlow;
+use csml_interpreter::data::event::Event;
+use csml_interpreter::data::MSG;
+use csml_interpreter::{interpret, load_components};
+use std::collections::HashMap;
+use std::sync::mpsc;
+
+use crate::support::tools::read_file;
+
+fn get_handoff_reasons(step: &str) -> Vec<serde_json::Value> {
+    let content = read_file("CSML/basic_test/built-in/handoff.csml".to_owned()).unwrap();
+    let flow = CsmlFlow::new("id", "flow", &content, Vec::default());
+    let bot = CsmlBot::new(
+        "id",
+        "bot",
+        None,
+        vec![flow],
+        Some(load_components().unwrap()),
+        None,
+        "flow",
+        None,
+        None,
+    );
+
+    let (sender, receiver) = mpsc::channel::<MSG>();
+    interpret(
+        bot,
+        Context::new(HashMap::new(), HashMap::new(), None, None, step, "flow"),
+        Event::new("payload", "", serde_json::json!({})),
+        Some(sender),
+    );
+
+    receiver
+        .try_iter()
+        .filter_map(|msg| match msg {
+            MSG::Handoff { reason } => Some(reason),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn ok_handoff() {
+    assert_eq!(
+        get_handoff_reasons("start"),
+        vec![serde_json::json!("billing question")]
+    );
+}
+
+#[test]
+fn ok_handoff_no_reason() {
+    assert_eq!(get_handoff_reasons("no_reason"), vec![serde_json::Value::Null]);
+}
@@ -2,12 +2,17 @@ mod support;
 
 use csml_interpreter::data::context::Context;
 use csml_interpreter::data::event::Event;
+use csml_interpreter::data::{ApiInfo, Client, HttpPolicy, HttpRequest, MockTransport};
 use std::collections::HashMap;
 
 use crate::support::tools::format_message;
 use crate::support::tools::message_to_json_value;
 
 use serde_json::Value;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::{mpsc, Arc};
+use std::thread;
 
 #[test]
 fn http_http_0() {
@@ -516,3 +521,286 @@ fn http_post_0() {
 
     assert_eq!(v1, v2)
 }
+
+/**
+ * Answer the first request sent to the returned url with response,
+ * and send the request (read until it ends with request_end) to the receiver
+ */
+fn serve_once(path: &str, response: &'static str, request_end: &'static str) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}{}", listener.local_addr().unwrap(), path);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        // the body may come in a second packet
+        let mut request = String::new();
+        let mut buffer = vec![0; 4096];
+        while !request.ends_with(request_end) {
+            let len = stream.read(&mut buffer).unwrap();
+            if len == 0 {
+                break;
+            }
+            request.push_str(&String::from_utf8_lossy(&buffer[..len]));
+        }
+        sender.send(request).unwrap();
+
+        stream.write_all(response.as_bytes()).unwrap();
+    });
+
+    (url, receiver)
+}
+
+#[test]
+fn http_options_0() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {
+                "content":{
+                    "body": {},
+                    "header":{
+                        "accept":"application/json,text/*",
+                        "authorization":"Basic dXNlcjpwYXNz",
+                        "content-type":"application/json",
+                        "User-Agent": "csml/v1"
+                    },
+                    "method":"get",
+                    "query":{},
+                    "timeout": 5000,
+                    "url":"https://clevy.io"
+                },
+                "content_type":"http"
+            },
+            {
+                "content":{
+                    "form": {"hello": "world", "count": 2},
+                    "header":{
+                        "accept":"application/json,text/*",
+                        "authorization":"Bearer token",
+                        "content-type":"application/x-www-form-urlencoded",
+                        "User-Agent": "csml/v1"
+                    },
+                    "method":"post",
+                    "query":{},
+                    "timeout": 5000,
+                    "url":"https://clevy.io"
+                },
+                "content_type":"http"
+            },
+            {
+                "content":{
+                    "raw_body": "<hello/>",
+                    "header":{
+                        "accept":"application/json,text/*",
+                        "authorization":"Basic dXNlcjpwYXNz",
+                        "content-type":"application/xml",
+                        "User-Agent": "csml/v1"
+                    },
+                    "method":"put",
+                    "query":{},
+                    "timeout": 5000,
+                    "url":"https://clevy.io"
+                },
+                "content_type":"http"
+            }
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "options_0", "flow"),
+        "CSML/basic_test/stdlib/http.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn http_full_response_0() {
+    let (url, receiver) = serve_once(
+        "/hello",
+        "HTTP/1.1 404 Not Found\r\ncontent-type: text/plain\r\nx-request-id: 42\r\ncontent-length: 9\r\n\r\nnot found",
+        "hello",
+    );
+
+    let msg = format_message(
+        Event::new("payload", &url, serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "full_response_0", "flow"),
+        "CSML/basic_test/stdlib/http.csml",
+    );
+
+    let request = receiver.recv().unwrap();
+    assert!(request.starts_with("POST /hello"));
+    assert!(request.contains("authorization: Bearer token"));
+    assert!(request.contains("content-type: text/plain"));
+    assert!(request.ends_with("\r\n\r\nhello"));
+
+    let value: Value = message_to_json_value(msg);
+    let response = &value["messages"][0]["content"];
+
+    assert_eq!(response["status"], 404);
+    assert_eq!(response["headers"]["x-request-id"], "42");
+    assert_eq!(response["body"], "not found");
+}
+
+#[test]
+fn http_query_1() {
+    let (url, receiver) = serve_once(
+        "/search",
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 11\r\n\r\n{\"ok\":true}",
+        "\r\n\r\n",
+    );
+
+    let msg = format_message(
+        Event::new("payload", &url, serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "query_1", "flow"),
+        "CSML/basic_test/stdlib/http.csml",
+    );
+
+    let request = receiver.recv().unwrap();
+    assert!(request.starts_with(
+        "GET /search?lang=fr&ids=1&ids=2&open=true&price=9.5&q=tom+%26+jerry+%3D+100%25 HTTP/1.1"
+    ));
+
+    let value: Value = message_to_json_value(msg);
+    assert_eq!(value["messages"][0]["content"]["body"], serde_json::json!({"ok": true}));
+}
+
+fn run_with_policy(url: &str, policy: HttpPolicy) -> Value {
+    let mut context = Context::new(HashMap::new(), HashMap::new(), None, None, "policy_0", "flow");
+    context.http_policy = Some(policy);
+
+    let msg = format_message(
+        Event::new("payload", url, serde_json::json!({})),
+        context,
+        "CSML/basic_test/stdlib/http.csml",
+    );
+
+    message_to_json_value(msg)
+}
+
+fn get_error(value: &Value, index: usize) -> String {
+    assert_eq!(value["messages"][index]["content_type"], "error");
+
+    value["messages"][index]["content"]["error"].as_str().unwrap().to_owned()
+}
+
+#[test]
+fn http_policy_private_ip() {
+    let value = run_with_policy("http://127.0.0.1:1/hello", HttpPolicy::default());
+    assert!(get_error(&value, 0).starts_with("private address '127.0.0.1' is blocked"));
+
+    // names are checked once resolved, redirections included
+    let value = run_with_policy("http://localhost:1/hello", HttpPolicy::default());
+    assert!(get_error(&value, 0).contains("host 'localhost' resolves to the private address"));
+}
+
+#[test]
+fn http_policy_fn_endpoint() {
+    let (url, receiver) = serve_once(
+        "/fn",
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 20\r\n\r\n{\"data\":\"Hello Tom\"}",
+        "}",
+    );
+    let mut context = Context::new(HashMap::new(), HashMap::new(), None, None, "policy_app", "flow");
+    // the fn_endpoint set by the operator is trusted, even on a private address
+    context.api_info = Some(ApiInfo {
+        client: Client::new("bot".to_owned(), "channel".to_owned(), "user".to_owned()),
+        fn_endpoint: url,
+    });
+    context.http_policy = Some(HttpPolicy::default());
+
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        context,
+        "CSML/basic_test/stdlib/http.csml",
+    );
+    let value = message_to_json_value(msg);
+
+    assert_eq!(value["messages"][0]["content"]["text"], "Hello Tom");
+    assert!(receiver.recv().unwrap().contains("\"function_id\":\"hello\""));
+}
+
+#[test]
+fn http_policy_max_requests() {
+    let (url, _receiver) = serve_once(
+        "/hello",
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\n\r\n{}",
+        "\r\n\r\n",
+    );
+    let policy = HttpPolicy {
+        allow_private_ips: true,
+        max_requests: Some(1),
+        ..Default::default()
+    };
+
+    let value = run_with_policy(&url, policy);
+
+    assert_eq!(value["messages"][0]["content"], serde_json::json!({}));
+    assert!(get_error(&value, 1).starts_with("the HTTP policy of this bot allows at most 1 requests per interaction"));
+}
+
+#[test]
+fn http_policy_max_response_bytes() {
+    let (url, _receiver) = serve_once(
+        "/hello",
+        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 15\r\n\r\n{\"hello\":\"bot\"}",
+        "\r\n\r\n",
+    );
+    let policy = HttpPolicy {
+        allow_private_ips: true,
+        max_response_bytes: Some(10),
+        ..Default::default()
+    };
+
+    let value = run_with_policy(&url, policy);
+
+    assert!(get_error(&value, 0).starts_with("the response is larger than the 10 bytes allowed"));
+}
+
+#[test]
+fn http_policy_denied_host() {
+    let policy = HttpPolicy {
+        allow_hosts: Some(vec!["*.csml.dev".to_owned()]),
+        ..Default::default()
+    };
+
+    let value = run_with_policy("https://clevy.io/hello", policy);
+
+    assert!(get_error(&value, 0).starts_with("host 'clevy.io' is not allowed"));
+}
+
+#[test]
+fn http_mock_transport() {
+    let transport = Arc::new(
+        MockTransport::from_json(
+            r#"[
+                {"method": "GET", "url": "https://api.example.com/orders?page=2", "response": {"total": 3}},
+                {"method": "POST", "url": "https://api.example.com/orders", "body": {"item": "pizza"}, "status": 201, "response": {"id": 42}},
+                {"method": "POST", "url": "https://api.example.com/orders", "status": 404, "headers": {"content-type": "text/plain"}, "response": "unknown item"}
+            ]"#,
+        )
+        .unwrap(),
+    );
+    let mut context = Context::new(HashMap::new(), HashMap::new(), None, None, "mock_0", "flow");
+    context.http_transport = Some(transport.clone());
+
+    let msg = format_message(
+        Event::new("payload", "pizza", serde_json::json!({})),
+        context,
+        "CSML/basic_test/stdlib/http.csml",
+    );
+    let value = message_to_json_value(msg);
+
+    assert_eq!(value["messages"][0]["content"]["text"], "3");
+    assert_eq!(value["messages"][1]["content"], serde_json::json!({"id": 42}));
+    assert_eq!(value["messages"][2]["content"]["status"], 404);
+    assert_eq!(value["messages"][2]["content"]["body"], "unknown item");
+    assert!(get_error(&value, 3).starts_with("no HTTP fixture matches the request GET https://api.example.com/users"));
+
+    let requests: Vec<HttpRequest> = transport.get_requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[1].headers["content-type"], "application/json");
+}
@@ -0,0 +1,34 @@
+use csml_interpreter::metrics::{self, Metric};
+
+const TEST_COUNTER: Metric = Metric {
+    name: "csml_test_counter_total",
+    help: "Counter used by the metrics tests",
+};
+
+const TEST_HISTOGRAM: Metric = Metric {
+    name: "csml_test_duration_seconds",
+    help: "Histogram used by the metrics tests",
+};
+
+#[test]
+fn ok_metrics_counter() {
+    metrics::inc_counter(&TEST_COUNTER, &[("status", "success")], 1.0);
+    metrics::inc_counter(&TEST_COUNTER, &[("status", "success")], 2.0);
+
+    let text = metrics::render();
+
+    assert!(text.contains("# TYPE csml_test_counter_total counter"));
+    assert!(text.contains("csml_test_counter_total{status=\"success\"} 3"));
+}
+
+#[test]
+fn ok_metrics_histogram() {
+    metrics::observe(&TEST_HISTOGRAM, &[("route", "/\"run\"")], 0.2);
+
+    let text = metrics::render();
+
+    assert!(text.contains("# TYPE csml_test_duration_seconds histogram"));
+    assert!(text.contains("csml_test_duration_seconds_bucket{route=\"/\\\"run\\\"\",le=\"0.1\"} 0"));
+    assert!(text.contains("csml_test_duration_seconds_bucket{route=\"/\\\"run\\\"\",le=\"0.25\"} 1"));
+    assert!(text.contains("csml_test_duration_seconds_count{route=\"/\\\"run\\\"\"} 1"));
+}
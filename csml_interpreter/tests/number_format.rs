@@ -0,0 +1,137 @@
+mod support;
+
+use csml_interpreter::data::context::Context;
+use csml_interpreter::data::event::Event;
+use std::collections::HashMap;
+
+use crate::support::tools::format_message;
+use crate::support::tools::message_to_json_value;
+
+use serde_json::Value;
+
+#[test]
+fn ok_format_number() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "text", "content": {"text": "1,234,567.891"}},
+            {"content_type": "text", "content": {"text": "1\u202f234\u202f567,89"}},
+            {"content_type": "text", "content": {"text": "1.234"}},
+            {"content_type": "text", "content": {"text": "1234"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "format_number", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_format_currency() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "text", "content": {"text": "$1,234.50"}},
+            {"content_type": "text", "content": {"text": "1\u202f234,50\u00a0\u20ac"}},
+            {"content_type": "text", "content": {"text": "\uffe51,234"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "format_currency", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_format_percent() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "text", "content": {"text": "26%"}},
+            {"content_type": "text", "content": {"text": "25,6\u202f%"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "format_percent", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_plural() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "text", "content": {"text": "one"}},
+            {"content_type": "text", "content": {"text": "one"}},
+            {"content_type": "text", "content": {"text": "few"}},
+            {"content_type": "text", "content": {"text": "many"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "plural", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_format_message() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "text", "content": {"text": "no pizza for Tom"}},
+            {"content_type": "text", "content": {"text": "1 pizza for Tom"}},
+            {"content_type": "text", "content": {"text": "1\u202f200 pizzas for Tom"}},
+            {"content_type": "text", "content": {"text": "elle arrive"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "format_message", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ko_format_error() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type": "error", "content": {"error": "unknown locale 'xx', supported locales are: de, en, es, fr, it, ja, nl, pl, pt, ru at line 58, column 13 at flow [flow]"}},
+            {"content_type": "text", "content": {"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "format_error", "flow"),
+        "CSML/basic_test/stdlib/number_format.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -198,3 +198,142 @@ fn ok_regex_8() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn ok_regex_capture() {
+    let data = r##"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content":{"text": "#A-1234"}},
+            {"content_type":"array", "content":["A", "1234", null]},
+            {"content_type":"text", "content":{"text": "1234"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"##;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "regex_9",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/regex.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_regex_find_all() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content":{"text": "3"}},
+            {"content_type":"text", "content":{"text": "345"}},
+            {"content_type":"array", "content":[]}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "regex_10",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/regex.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_regex_replace_split() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content":{"text": "01/06/2021"}},
+            {"content_type":"text", "content":{"text": "42 Tom"}},
+            {"content_type":"array", "content":["a", "b", "c"]}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "regex_11",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/regex.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ko_regex_invalid() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "parameter must be a valid regex expression at line 71, column 17 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "regex_12",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/regex.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ko_replace_regex_too_long() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "[replace_all] and [replace_regex] can not make a string longer than 100000 bytes at line 75, column 33 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "regex_13",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/regex.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
@@ -277,3 +277,143 @@ fn string_step_11() {
 
     assert_eq!(v1, v2)
 }
+
+#[test]
+fn string_step_12() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"text", "content":{"text": "a+b-c"}},
+            {"content_type":"text", "content":{"text": "a+b+c"}},
+            {"content_type":"text", "content":{"text": "Hello World"}},
+            {"content_type":"text", "content":{"text": "00042"}},
+            {"content_type":"text", "content":{"text": "  7"}},
+            {"content_type":"text", "content":{"text": "abxyzxy"}},
+            {"content_type":"text", "content":{"text": "Hello"}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_12",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_13() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "[pad_start] and [pad_end] take a length of type Int, at most 10000, and an optional non empty String. Usage: string.pad_start(5, \"0\") at line 100, column 14 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_13",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_14() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "[pad_start] and [pad_end] take a length of type Int, at most 10000, and an optional non empty String. Usage: string.pad_start(5, \"0\") at line 104, column 13 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_14",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_15() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "[replace] takes two parameters of type String, the first one not empty. Usage: string.replace(\"old\", \"new\") at line 108, column 15 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_15",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn string_step_16() {
+    let data = r#"{
+        "memories":[],
+        "messages":[
+            {"content_type":"error", "content":{"error": "[replace_all] and [replace_regex] can not make a string longer than 100000 bytes at line 112, column 33 at flow [flow]"}},
+            {"content_type":"text", "content":{"text": null}}
+        ]}"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(
+            HashMap::new(),
+            HashMap::new(),
+            None,
+            None,
+            "step_16",
+            "flow",
+        ),
+        "CSML/basic_test/stdlib/string.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
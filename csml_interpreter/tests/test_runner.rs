@@ -0,0 +1,65 @@
+use csml_interpreter::test_runner::{run_test_file, run_tests, TestFailure, TestFile};
+use std::path::{Path, PathBuf};
+
+fn get_failures(path: &str) -> Vec<Option<TestFailure>> {
+    let test_file = TestFile::read(Path::new(path)).unwrap();
+
+    run_test_file(&test_file)
+        .unwrap()
+        .into_iter()
+        .map(|result| result.failure)
+        .collect()
+}
+
+#[test]
+fn test_runner_ok() {
+    let failures = get_failures("CSML/test_runner/bot.test.csml");
+
+    assert_eq!(failures, vec![None, None, None]);
+}
+
+#[test]
+fn test_runner_failures() {
+    let failures = get_failures("CSML/test_runner/failing.test.csml");
+
+    let failure = failures[0].as_ref().unwrap();
+    assert_eq!((failure.line, failure.message.as_str()), (7, "unexpected message"));
+    assert!(failure
+        .diff
+        .as_ref()
+        .unwrap()
+        .contains("-     \"text\": \"Hello Tom!\"\n+     \"text\": \"Hi Tom! Do you want to order a pizza?\""));
+
+    let failure = failures[1].as_ref().unwrap();
+    assert_eq!(failure.message, "expected step 'end', found 'start'");
+
+    // every message must be checked
+    let failure = failures[2].as_ref().unwrap();
+    assert_eq!((failure.line, failure.message.as_str()), (15, "unexpected message"));
+}
+
+#[test]
+fn test_runner_summary() {
+    let mut output = vec![];
+    let summary = run_tests(&[PathBuf::from("CSML/test_runner")], &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert_eq!((summary.passed, summary.failed, summary.errors), (3, 3, 0));
+    assert_eq!(summary.exit_code(), 1);
+    assert!(output.contains("  ok      orders a pizza\n"));
+    assert!(output.contains("  FAILED  expects another step (line 9)\n      line 12: expected step 'end', found 'start'\n"));
+}
+
+#[test]
+fn test_runner_invalid_file() {
+    let err = TestFile::parse(
+        Path::new("CSML/test_runner/invalid.test.csml"),
+        "@flow \"default.csml\"\n\nscenario \"waits\":\n\twait 10\n",
+    )
+    .unwrap_err();
+    assert_eq!(err, "CSML/test_runner/invalid.test.csml:4: unknown instruction 'wait'");
+
+    let mut output = vec![];
+    let summary = run_tests(&[PathBuf::from("CSML/test_runner/missing.test.csml")], &mut output).unwrap();
+    assert_eq!(summary.exit_code(), 2);
+}
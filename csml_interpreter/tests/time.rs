@@ -77,3 +77,204 @@ fn ok_time_parse_2_args() {
     assert_eq!(v1, v2)
 }
 
+#[test]
+fn ok_time_timezone() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "2021-03-27T11:30:00.000+01:00"},"content_type":"text"},
+            {"content":{"text": "11:30 CET"},"content_type":"text"},
+            {"content":{"text": "2021-03-28T11:30:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-03-28T12:30:00.000+02:00"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "timezone", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_time_arithmetic() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "2021-02-28T12:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2020-02-14T12:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-01-31T13:30:30.005Z"},"content_type":"text"},
+            {"content":{"text": "28"},"content_type":"text"},
+            {"content":{"text": "-13"},"content_type":"text"},
+            {"content":{"text": "-36"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "arithmetic", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_time_calendar() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "5"},"content_type":"text"},
+            {"content":{"text": "53"},"content_type":"text"},
+            {"content":{"text": "2020-12-28T00:00:00.000+01:00"},"content_type":"text"},
+            {"content":{"text": "2021-01-01T00:00:00.000+01:00"},"content_type":"text"},
+            {"content":{"text": "2021-01-01T13:00:00.000+01:00"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "calendar", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_time_locale() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "Friday 13 August 2021"},"content_type":"text"},
+            {"content":{"text": "vendredi 13 août 2021"},"content_type":"text"},
+            {"content":{"text": "Fr. Aug."},"content_type":"text"},
+            {"content":{"text": "100% agosto"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "locale", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ko_time_unknown_timezone() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "unknown_timezone", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let value: Value = message_to_json_value(msg);
+
+    assert_eq!(value["messages"][0]["content_type"], "error");
+}
+
+#[test]
+fn ok_time_parse_natural_en() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "2021-08-14T15:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-08-20T00:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-08-13T12:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-08-10T09:30:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-08-14T08:15:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2022-09-03T12:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-09-01T00:00:00.000Z"},"content_type":"text"},
+            {"content":{"text": "2021-08-09T00:00:00.000Z"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "natural_en", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_time_parse_natural_fr() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "2021-08-14T15:00:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-08-20T00:00:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-08-13T13:30:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-08-15T09:30:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-09-01T20:00:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-08-16T00:00:00.000+02:00"},"content_type":"text"},
+            {"content":{"text": "2021-08-06T11:30:00.000+02:00"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "natural_fr", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ok_time_parse_natural_error() {
+    let data =
+        r#"
+        {"messages":[
+            {"content":{"text": "whenever you want"},"content_type":"text"},
+            {"content":{"text": "31/02 is not a valid date"},"content_type":"text"}
+        ],
+        "memories":[]
+        }"#;
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "natural_error", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let v1: Value = message_to_json_value(msg);
+    let v2: Value = serde_json::from_str(data).unwrap();
+
+    assert_eq!(v1, v2)
+}
+
+#[test]
+fn ko_time_parse_natural_unknown_lang() {
+    let msg = format_message(
+        Event::new("payload", "", serde_json::json!({})),
+        Context::new(HashMap::new(), HashMap::new(), None, None, "natural_unknown_lang", "flow"),
+        "CSML/basic_test/built-in/time.csml",
+    );
+
+    let value: Value = message_to_json_value(msg);
+
+    assert_eq!(value["messages"][0]["content_type"], "error");
+}
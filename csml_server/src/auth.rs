@@ -0,0 +1,564 @@
+/**
+ * Authentication and authorization of incoming requests.
+ *
+ * Requests must send an API key, either as `Authorization: Bearer <key>` or as `X-Api-Key: <key>`.
+ * Browsers can not set headers on EventSource and WebSocket connections: the key can also be
+ * sent in the `api_key` query parameter, which is hidden in the access logs.
+ * Keys are configured with the following env vars:
+ * - ENGINE_SERVER_API_KEYS: JSON array of `{"key": String, "scopes": [String], "bot_ids": Option<[String]>}`
+ * - ENGINE_SERVER_API_KEYS_DB: if `true`, keys are also looked up in the engine's database
+ *   (see the `/api_keys` routes)
+ *
+ * If none of these is set, authentication is disabled.
+ *
+ * Each route requires a scope: `run` to send events, `read` to read data and `admin`
+ * for everything else. The `admin` scope grants all the other scopes.
+ * Keys restricted to a list of bot_ids can only access the bots they are restricted to.
+ * The bots of a request are found in its path (/bots/{bot_id}), its query (bot_id)
+ * and its JSON body (bot_id, id, bot.id, client.bot_id, event.client.bot_id).
+ * Only POST, PUT and PATCH bodies are read, up to MAX_BODY_SIZE: streams (GET /run/stream,
+ * GET /ws) must give their bot in the query.
+ */
+use actix_web::dev::{Payload, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{Error, HttpMessage};
+use bytes::BytesMut;
+use futures::future::{ok, LocalBoxFuture, Ready};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::error::ApiError;
+
+const API_KEYS: &str = "ENGINE_SERVER_API_KEYS";
+const API_KEYS_DB: &str = "ENGINE_SERVER_API_KEYS_DB";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+  pub key: String,
+  pub scopes: Vec<String>,
+  pub bot_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+  pub keys: Vec<ApiKey>,
+  pub use_db: bool,
+}
+
+impl AuthConfig {
+  /**
+   * Read the auth configuration from env. Returns None if authentication is disabled.
+   */
+  pub fn from_env() -> Result<Option<Self>, String> {
+    let keys = match std::env::var(API_KEYS) {
+      Ok(val) if !val.trim().is_empty() => match serde_json::from_str(&val) {
+        Ok(keys) => keys,
+        Err(err) => return Err(format!("{} is not a valid list of API keys: {}", API_KEYS, err)),
+      },
+      _ => vec![],
+    };
+
+    let use_db = match std::env::var(API_KEYS_DB) {
+      Ok(val) => val == "true",
+      Err(_) => false,
+    };
+
+    match keys.is_empty() && !use_db {
+      true => Ok(None),
+      false => Ok(Some(AuthConfig { keys, use_db })),
+    }
+  }
+
+  /**
+   * Keys of the env are checked first, then the ones of the database on a blocking thread,
+   * so that the worker keeps serving other requests during the lookup
+   */
+  async fn find_key(&self, key: String) -> Option<ApiKey> {
+    if let Some(api_key) = self.keys.iter().find(|api_key| is_same_key(&api_key.key, &key)) {
+      return Some(api_key.to_owned());
+    }
+
+    if !self.use_db {
+      return None;
+    }
+
+    let owned_key = key.to_owned();
+    match actix_web::web::block(move || csml_engine::get_api_key(&owned_key)).await {
+      Ok(Some(api_key)) => Some(ApiKey {
+        key,
+        scopes: api_key.scopes,
+        bot_ids: api_key.bot_ids,
+      }),
+      Ok(None) => None,
+      Err(err) => {
+        eprintln!("EngineError: {:?}", err);
+        None
+      }
+    }
+  }
+}
+
+/**
+ * Compare every byte whatever the first difference, so that the time taken
+ * does not tell how much of a key was guessed right
+ */
+fn is_same_key(expected: &str, key: &str) -> bool {
+  let (expected, key) = (expected.as_bytes(), key.as_bytes());
+
+  expected.len() == key.len()
+    && expected
+      .iter()
+      .zip(key.iter())
+      .fold(0, |diff, (a, b)| diff | (a ^ b))
+      == 0
+}
+
+/**
+ * Scope required to call a route. Returns None for public routes.
+ */
+pub fn get_required_scope(method: &Method, path: &str) -> Option<&'static str> {
+  match (method, path) {
+    (_, "/") | (_, "/openapi.json") => None,
+    (_, "/healthz") | (_, "/readyz") => None,
+    (_, path) if path.starts_with("/static/") => None,
+    (&Method::OPTIONS, _) => None,
+    (&Method::POST, "/run") | (&Method::POST, "/sns") => Some("run"),
+    (&Method::GET, "/run/stream") | (&Method::GET, "/ws") => Some("run"),
+    (&Method::POST, "/validate") | (&Method::POST, "/conversations/open") => Some("read"),
+    (_, path) if path.starts_with("/api_keys") => Some("admin"),
+    (&Method::GET, _) => Some("read"),
+    _ => Some("admin"),
+  }
+}
+
+fn has_scope(api_key: &ApiKey, scope: &str) -> bool {
+  api_key.scopes.iter().any(|key_scope| key_scope == scope || key_scope == "admin")
+}
+
+fn get_request_key(req: &ServiceRequest) -> Option<String> {
+  let headers = req.headers();
+
+  if let Some(Ok(authorization)) = headers.get("authorization").map(|val| val.to_str()) {
+    let mut parts = authorization.splitn(2, ' ');
+
+    if let (Some(scheme), Some(token)) = (parts.next(), parts.next()) {
+      if scheme.eq_ignore_ascii_case("bearer") {
+        return Some(token.trim().to_owned());
+      }
+    }
+  }
+
+  if let Some(Ok(key)) = headers.get("x-api-key").map(|val| val.to_str()) {
+    return Some(key.trim().to_owned());
+  }
+
+  match actix_web::web::Query::<HashMap<String, String>>::from_query(req.query_string()) {
+    Ok(query) => query.get("api_key").map(|key| key.to_owned()),
+    Err(_) => None,
+  }
+}
+
+/**
+ * Request line for the access logs, with the value of the `api_key` query parameter hidden
+ */
+pub fn get_logged_request_line(req: &ServiceRequest) -> String {
+  let query: Vec<String> = req
+    .query_string()
+    .split('&')
+    .filter(|param| !param.is_empty())
+    .map(|param| match param.split('=').next() {
+      Some("api_key") => "api_key=REDACTED".to_owned(),
+      _ => param.to_owned(),
+    })
+    .collect();
+
+  match query.is_empty() {
+    true => format!("{} {} {:?}", req.method(), req.path(), req.version()),
+    false => format!("{} {}?{} {:?}", req.method(), req.path(), query.join("&"), req.version()),
+  }
+}
+
+fn get_path_bot_id(path: &str) -> Option<String> {
+  let path = path.strip_prefix("/data").unwrap_or(path);
+
+  match path.strip_prefix("/bots/") {
+    Some(rest) => rest.split('/').next().filter(|id| !id.is_empty()).map(|id| id.to_owned()),
+    None => None,
+  }
+}
+
+fn get_query_bot_id(query: &str) -> Option<String> {
+  match actix_web::web::Query::<HashMap<String, String>>::from_query(query) {
+    Ok(query) => query.get("bot_id").map(|bot_id| bot_id.to_owned()),
+    Err(_) => None,
+  }
+}
+
+fn get_body_bot_ids(body: &Value) -> Vec<String> {
+  let pointers = ["/bot_id", "/id", "/bot/id", "/client/bot_id", "/event/client/bot_id"];
+
+  pointers
+    .iter()
+    .filter_map(|pointer| body.pointer(pointer).and_then(|val| val.as_str()))
+    .map(|bot_id| bot_id.to_owned())
+    .collect()
+}
+
+/**
+ * Requests whose body may hold bot_ids. Other bodies are never read, as the body
+ * of a WebSocket connection only ends when the connection is closed.
+ */
+fn has_json_body(method: &Method) -> bool {
+  matches!(*method, Method::POST | Method::PUT | Method::PATCH)
+}
+
+/**
+ * A restricted key must be allowed to access every bot of the request,
+ * and the request must be about at least one bot.
+ */
+fn is_allowed_bots(api_key: &ApiKey, bot_ids: &[String]) -> bool {
+  match &api_key.bot_ids {
+    None => true,
+    Some(allowed) => {
+      !bot_ids.is_empty() && bot_ids.iter().all(|bot_id| allowed.contains(bot_id))
+    }
+  }
+}
+
+/**
+ * The inner service must not stay borrowed while its response is awaited,
+ * as other requests may be handled in the meantime.
+ */
+async fn call_service<S, B>(service: &Rc<RefCell<S>>, req: ServiceRequest) -> Result<ServiceResponse<B>, Error>
+where
+  S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+{
+  let fut = service.borrow_mut().call(req);
+
+  fut.await
+}
+
+pub struct Authentication {
+  config: Option<Arc<AuthConfig>>,
+}
+
+impl Authentication {
+  pub fn new(config: Option<Arc<AuthConfig>>) -> Self {
+    Authentication { config }
+  }
+}
+
+impl<S, B> Transform<S> for Authentication
+where
+  S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Request = ServiceRequest;
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type InitError = ();
+  type Transform = AuthenticationMiddleware<S>;
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ok(AuthenticationMiddleware {
+      service: Rc::new(RefCell::new(service)),
+      config: self.config.clone(),
+    })
+  }
+}
+
+pub struct AuthenticationMiddleware<S> {
+  service: Rc<RefCell<S>>,
+  config: Option<Arc<AuthConfig>>,
+}
+
+impl<S, B> Service for AuthenticationMiddleware<S>
+where
+  S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Request = ServiceRequest;
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.service.borrow_mut().poll_ready(cx)
+  }
+
+  fn call(&mut self, mut req: ServiceRequest) -> Self::Future {
+    let service = self.service.clone();
+    let config = self.config.clone();
+
+    Box::pin(async move {
+      let config = match config {
+        Some(config) => config,
+        None => return call_service(&service, req).await,
+      };
+
+      let scope = match get_required_scope(req.method(), req.path()) {
+        Some(scope) => scope,
+        None => return call_service(&service, req).await,
+      };
+
+      let api_key = match get_request_key(&req) {
+        Some(key) => config.find_key(key).await,
+        None => None,
+      };
+      let api_key = match api_key {
+        Some(api_key) => api_key,
+        None => return Err(ApiError::new(StatusCode::UNAUTHORIZED, "unauthorized", "missing or invalid API key").into()),
+      };
+
+      if !has_scope(&api_key, scope) {
+        return Err(ApiError::new(
+          StatusCode::FORBIDDEN,
+          "forbidden",
+          &format!("this API key does not have the '{}' scope", scope),
+        ).into());
+      }
+
+      if api_key.bot_ids.is_some() {
+        let mut bot_ids: Vec<String> = vec![];
+        bot_ids.extend(get_path_bot_id(req.path()));
+        bot_ids.extend(get_query_bot_id(req.query_string()));
+
+        // the body is read to find its bot_ids, then given back to the handler
+        if has_json_body(req.method()) {
+          let mut body = BytesMut::new();
+          let mut payload = req.take_payload();
+          while let Some(chunk) = payload.next().await {
+            let chunk = chunk?;
+            if body.len() + chunk.len() > crate::MAX_BODY_SIZE {
+              return Err(ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "payload_too_large",
+                &format!("the body can not be larger than {} bytes", crate::MAX_BODY_SIZE),
+              ).into());
+            }
+            body.extend_from_slice(&chunk);
+          }
+
+          if let Ok(json) = serde_json::from_slice::<Value>(&body) {
+            bot_ids.extend(get_body_bot_ids(&json));
+          }
+
+          let body = body.freeze();
+          req.set_payload(Payload::Stream(Box::pin(futures::stream::once(async move {
+            Ok(body)
+          }))));
+        }
+
+        if !is_allowed_bots(&api_key, &bot_ids) {
+          return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "forbidden",
+            "this API key is not allowed to access this bot",
+          ).into());
+        }
+      }
+
+      call_service(&service, req).await
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::{get, post, test, web, App, HttpResponse};
+
+    #[get("/bots/{bot_id}")]
+    async fn get_bot() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[post("/run")]
+    async fn run(body: web::Json<Value>) -> HttpResponse {
+        HttpResponse::Ok().json(body.into_inner())
+    }
+
+    // rejected requests are returned as errors by the middleware
+    fn get_status(res: Result<ServiceResponse, Error>) -> StatusCode {
+        match res {
+            Ok(resp) => resp.status(),
+            Err(err) => err.as_response_error().status_code(),
+        }
+    }
+
+    fn init_config() -> Option<Arc<AuthConfig>> {
+        Some(Arc::new(AuthConfig {
+            keys: vec![
+                ApiKey {
+                    key: "admin-key".to_owned(),
+                    scopes: vec!["admin".to_owned()],
+                    bot_ids: None,
+                },
+                ApiKey {
+                    key: "run-key".to_owned(),
+                    scopes: vec!["run".to_owned()],
+                    bot_ids: Some(vec!["botid".to_owned()]),
+                },
+            ],
+            use_db: false,
+        }))
+    }
+
+    #[actix_rt::test]
+    async fn test_auth_missing_key() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(Authentication::new(init_config()))
+                    .service(get_bot)
+        ).await;
+
+        let req = test::TestRequest::get()
+                    .uri("/bots/botid")
+                    .to_request();
+
+        assert_eq!(get_status(app.call(req).await), StatusCode::UNAUTHORIZED);
+    }
+
+    #[actix_rt::test]
+    async fn test_auth_scopes() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(Authentication::new(init_config()))
+                    .service(get_bot)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid")
+                    .header("Authorization", "Bearer admin-key")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = test::TestRequest::get()
+                    .uri("/bots/botid")
+                    .header("X-Api-Key", "run-key")
+                    .to_request();
+
+        assert_eq!(get_status(app.call(req).await), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn test_auth_bot_ids() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(Authentication::new(init_config()))
+                    .service(run)
+        ).await;
+
+        let body = serde_json::json!({"event": {"client": {"bot_id": "botid"}}});
+        let resp = test::TestRequest::post()
+                    .uri("/run")
+                    .header("Authorization", "Bearer run-key")
+                    .set_json(&body)
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        let res: Value = test::read_body_json(resp).await;
+        assert_eq!(res, body);
+
+        let req = test::TestRequest::post()
+                    .uri("/run")
+                    .header("Authorization", "Bearer run-key")
+                    .set_json(&serde_json::json!({"bot_id": "botid", "event": {"client": {"bot_id": "other"}}}))
+                    .to_request();
+
+        assert_eq!(get_status(app.call(req).await), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn test_auth_body_too_large() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(Authentication::new(init_config()))
+                    .service(run)
+        ).await;
+
+        let req = test::TestRequest::post()
+                    .uri("/run")
+                    .header("Authorization", "Bearer run-key")
+                    .set_payload(vec![b' '; crate::MAX_BODY_SIZE + 1])
+                    .to_request();
+
+        assert_eq!(get_status(app.call(req).await), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[actix_rt::test]
+    async fn test_auth_ws_handshake() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(Authentication::new(init_config()))
+                    .app_data(web::Data::new(crate::rate_limit::RateLimiter::default()))
+                    .service(crate::routes::stream::run_ws)
+        ).await;
+
+        let handshake = |uri: &str| {
+            let req = test::TestRequest::get()
+                    .uri(uri)
+                    .header("Authorization", "Bearer run-key")
+                    .header("Upgrade", "websocket")
+                    .header("Connection", "Upgrade")
+                    .header("Sec-WebSocket-Version", "13")
+                    .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+                    .to_request();
+
+            // the body of a connection only ends once it is closed
+            let stream: actix_web::dev::PayloadStream = Box::pin(futures::stream::pending());
+            req.replace_payload(Payload::Stream(stream)).0
+        };
+
+        let res = actix_rt::time::timeout(
+            std::time::Duration::from_secs(5),
+            app.call(handshake("/ws?bot_id=botid")),
+        ).await;
+        assert_eq!(get_status(res.expect("the handshake did not complete")), StatusCode::SWITCHING_PROTOCOLS);
+
+        let res = actix_rt::time::timeout(
+            std::time::Duration::from_secs(5),
+            app.call(handshake("/ws?bot_id=other")),
+        ).await;
+        assert_eq!(get_status(res.expect("the handshake did not complete")), StatusCode::FORBIDDEN);
+    }
+
+    #[test]
+    fn test_logged_request_line() {
+        let req = test::TestRequest::get()
+                    .uri("/run/stream?bot_id=botid&api_key=secret")
+                    .to_srv_request();
+        assert_eq!(get_logged_request_line(&req), "GET /run/stream?bot_id=botid&api_key=REDACTED HTTP/1.1");
+
+        let req = test::TestRequest::get().uri("/bots/botid").to_srv_request();
+        assert_eq!(get_logged_request_line(&req), "GET /bots/botid HTTP/1.1");
+    }
+
+    #[test]
+    fn test_same_key() {
+        assert!(is_same_key("admin-key", "admin-key"));
+        assert!(!is_same_key("admin-key", "admin-kez"));
+        assert!(!is_same_key("admin-key", "admin-key2"));
+        assert!(!is_same_key("admin-key", ""));
+    }
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(get_required_scope(&Method::GET, "/"), None);
+        assert_eq!(get_required_scope(&Method::POST, "/run"), Some("run"));
+        assert_eq!(get_required_scope(&Method::GET, "/memories"), Some("read"));
+        assert_eq!(get_required_scope(&Method::DELETE, "/memories"), Some("admin"));
+        assert_eq!(get_required_scope(&Method::GET, "/api_keys"), Some("admin"));
+    }
+}
@@ -0,0 +1,151 @@
+/**
+ * Every error returned by the server has the same JSON body:
+ *
+ * {
+ *  "code": String,
+ *  "message": String,
+ *  "details": Option<Value>
+ * }
+ */
+use actix_web::{http::{header, StatusCode}, HttpResponse, ResponseError};
+use csml_engine::data::EngineError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBody {
+  pub code: String,
+  pub message: String,
+  pub details: Option<Value>,
+}
+
+#[derive(Debug)]
+pub struct ApiError {
+  status: StatusCode,
+  body: ErrorBody,
+  retry_after: Option<u64>,
+}
+
+impl ApiError {
+  pub fn new(status: StatusCode, code: &str, message: &str) -> Self {
+    Self {
+      status,
+      body: ErrorBody {
+        code: code.to_owned(),
+        message: message.to_owned(),
+        details: None,
+      },
+      retry_after: None,
+    }
+  }
+
+  pub fn with_details(mut self, details: Value) -> Self {
+    self.body.details = Some(details);
+    self
+  }
+
+  /**
+   * Number of seconds the client should wait before retrying, sent as a Retry-After header
+   */
+  pub fn with_retry_after(mut self, seconds: u64) -> Self {
+    self.retry_after = Some(seconds);
+    self
+  }
+
+  pub fn body(&self) -> &ErrorBody {
+    &self.body
+  }
+
+  pub fn bad_request(message: &str) -> Self {
+    Self::new(StatusCode::BAD_REQUEST, "bad_request", message)
+  }
+
+  pub fn not_found(message: &str) -> Self {
+    Self::new(StatusCode::NOT_FOUND, "not_found", message)
+  }
+}
+
+impl fmt::Display for ApiError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}: {}", self.body.code, self.body.message)
+  }
+}
+
+impl ResponseError for ApiError {
+  fn status_code(&self) -> StatusCode {
+    self.status
+  }
+
+  fn error_response(&self) -> HttpResponse {
+    let mut res = HttpResponse::build(self.status);
+
+    if let Some(seconds) = self.retry_after {
+      res.header(header::RETRY_AFTER, seconds.to_string());
+    }
+
+    res.json(&self.body)
+  }
+}
+
+/**
+ * - Format: the request is invalid (400)
+ * - Interpreter: the bot or the event can not be interpreted (422)
+ * - any other error is an internal error of the engine (500)
+ */
+impl From<EngineError> for ApiError {
+  fn from(err: EngineError) -> Self {
+    match err {
+      EngineError::Format(message) => {
+        Self::new(StatusCode::BAD_REQUEST, "format_error", &message)
+      }
+      EngineError::Interpreter(message) => {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, "interpreter_error", &message)
+      }
+      EngineError::Manager(message) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "manager_error", &message)
+      }
+      EngineError::Serde(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "serialization_error", &err.to_string())
+          .with_details(json!({"line": err.line(), "column": err.column()}))
+      }
+      EngineError::Io(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "io_error", &err.to_string())
+      }
+      EngineError::Utf8(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "encoding_error", &err.to_string())
+      }
+      EngineError::Base64(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "encoding_error", &err.to_string())
+      }
+      EngineError::Time(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "time_error", &err.to_string())
+      }
+      EngineError::Openssl(err) => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "encryption_error", &err.to_string())
+      }
+      // the other errors depend on the database the engine is built with
+      #[allow(unreachable_patterns)]
+      err => {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "database_error", &format!("{:?}", err))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_error_body() {
+        let err = ApiError::from(EngineError::Format("Invalid bot_opt format".to_owned()));
+        assert_eq!(err.status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.body.code, "format_error");
+        assert_eq!(err.body.message, "Invalid bot_opt format");
+
+        let err = ApiError::from(EngineError::Interpreter("Flow 'x' does not exist".to_owned()));
+        assert_eq!(err.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.body.code, "interpreter_error");
+    }
+}
@@ -1,15 +1,23 @@
 use actix_cors::Cors;
 use actix_web::{middleware, web, App, HttpServer, http::header};
 use actix_files as fs;
+use std::sync::Arc;
 
+mod auth;
+mod error;
+mod metrics;
+mod openapi;
+mod rate_limit;
 mod routes;
+mod telemetry;
 
-const MAX_BODY_SIZE: usize = 8_388_608; // 8MB
+pub const MAX_BODY_SIZE: usize = 8_388_608; // 8MB
 
 #[actix_rt::main]
 async fn main() -> std::io::Result<()> {
-  std::env::set_var("RUST_LOG", "actix_web=info");
-  env_logger::init();
+  if let Err(err) = telemetry::init() {
+    return Err(std::io::Error::new(std::io::ErrorKind::Other, err));
+  }
 
   let server_port: String = match std::env::var("ENGINE_SERVER_PORT") {
     Ok(val) => val,
@@ -17,8 +25,24 @@ async fn main() -> std::io::Result<()> {
   };
   println!("CSML Server listening on port {}", server_port);
 
-  HttpServer::new(|| {
+  let auth_config = match auth::AuthConfig::from_env() {
+    Ok(Some(config)) => Some(Arc::new(config)),
+    Ok(None) => {
+      println!("Warning: no API keys are configured, authentication is disabled");
+      None
+    }
+    Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, err)),
+  };
+
+  let rate_limiter = match rate_limit::RateLimiter::from_env() {
+    Ok(rate_limiter) => web::Data::new(rate_limiter),
+    Err(err) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, err)),
+  };
+
+  HttpServer::new(move || {
     App::new()
+      .wrap(auth::Authentication::new(auth_config.clone()))
+      .wrap(metrics::RequestMetrics)
       .wrap(
         Cors::default()
           .send_wildcard()
@@ -26,19 +50,38 @@ async fn main() -> std::io::Result<()> {
           .allowed_headers(vec![
             header::AUTHORIZATION,
             header::ACCEPT,
-            header::CONTENT_TYPE
+            header::CONTENT_TYPE,
+            header::HeaderName::from_static("x-api-key"),
+            header::HeaderName::from_static("x-bot-env"),
           ])
           .max_age(86_400) //24h
       )
-      .wrap(middleware::Logger::default())
-      .data(web::JsonConfig::default().limit(MAX_BODY_SIZE))
+      // the default format, without the API keys sent in the query
+      .wrap(
+        middleware::Logger::new(r#"%a "%{REQUEST_LINE}xi" %s %b "%{Referer}i" "%{User-Agent}i" %T"#)
+          .custom_request_replace("REQUEST_LINE", auth::get_logged_request_line)
+      )
+      .data(web::JsonConfig::default().limit(MAX_BODY_SIZE).error_handler(|err, _req| {
+        error::ApiError::bad_request(&err.to_string()).into()
+      }))
+      .data(web::PayloadConfig::new(MAX_BODY_SIZE))
+      .app_data(rate_limiter.clone())
+      .data(web::QueryConfig::default().error_handler(|err, _req| {
+        error::ApiError::bad_request(&err.to_string()).into()
+      }))
 
       .service(fs::Files::new("/static", "./static").use_last_modified(true))
 
       .service(routes::index::home)
+      .service(routes::openapi::get_openapi)
+      .service(routes::health::healthz)
+      .service(routes::health::readyz)
+      .service(routes::health::metrics)
       .service(routes::validate::handler)
 
       .service(routes::run::handler)
+      .service(routes::stream::run_sse)
+      .service(routes::stream::run_ws)
 
       .service(routes::sns::handler)
 
@@ -49,6 +92,16 @@ async fn main() -> std::io::Result<()> {
       .service(routes::bot_versions::delete_bot_version)
       .service(routes::bot_versions::delete_bot_versions)
 
+      .service(routes::bot_packages::export_bot_version)
+      .service(routes::bot_packages::import_bot_package)
+
+      .service(routes::bot_labels::get_bot_labels)
+      .service(routes::bot_labels::get_bot_label)
+      .service(routes::bot_labels::promote_bot_version)
+      .service(routes::bot_labels::rollback_bot_label)
+      .service(routes::bot_labels::delete_bot_label)
+      .service(routes::bot_labels::diff_bot_versions)
+
       .service(routes::conversations::get_open)
       .service(routes::conversations::close_user_conversations)
       .service(routes::conversations::get_client_conversations)
@@ -63,12 +116,36 @@ async fn main() -> std::io::Result<()> {
 
       .service(routes::state::get_client_current_state)
 
+      .service(routes::handoff::get_handoff)
+      .service(routes::handoff::start_handoff)
+      .service(routes::handoff::end_handoff)
+
+      .service(routes::analytics::get_bot_analytics)
+
+      .service(routes::admin::get_bot_open_conversations)
+      .service(routes::admin::get_conversation)
+      .service(routes::admin::close_conversation)
+      .service(routes::admin::search_bot_clients)
+
+      .service(routes::bot_limits::set_bot_limits)
+      .service(routes::bot_limits::get_bot_limits)
+      .service(routes::bot_limits::delete_bot_limits)
+      .service(routes::http_policies::set_http_policy)
+      .service(routes::http_policies::get_http_policy)
+      .service(routes::http_policies::delete_http_policy)
+
       .service(routes::data::delete_bot)
       .service(routes::data::delete_client)
 
+      .service(routes::api_keys::create_api_key)
+      .service(routes::api_keys::delete_api_key)
+
   })
   .bind(format!("0.0.0.0:{}", server_port))?
   .run()
-  .await
+  .await?;
+
+  telemetry::shutdown();
+  Ok(())
 }
 
@@ -0,0 +1,90 @@
+/**
+ * Count the requests handled by the server and measure their latency, per route.
+ * Routes are identified by their pattern (e.g. /bots/{bot_id}) to keep the number
+ * of label values bounded.
+ */
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use csml_interpreter::metrics::{self, Metric};
+use futures::future::{ok, LocalBoxFuture, Ready};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+pub const HTTP_REQUESTS: Metric = Metric {
+  name: "csml_server_requests_total",
+  help: "Number of requests handled by the server",
+};
+
+pub const HTTP_REQUEST_DURATION: Metric = Metric {
+  name: "csml_server_request_duration_seconds",
+  help: "Duration of the requests handled by the server",
+};
+
+pub struct RequestMetrics;
+
+impl<S, B> Transform<S> for RequestMetrics
+where
+  S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Request = ServiceRequest;
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type InitError = ();
+  type Transform = RequestMetricsMiddleware<S>;
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ok(RequestMetricsMiddleware {
+      service: Rc::new(RefCell::new(service)),
+    })
+  }
+}
+
+pub struct RequestMetricsMiddleware<S> {
+  service: Rc<RefCell<S>>,
+}
+
+impl<S, B> Service for RequestMetricsMiddleware<S>
+where
+  S: Service<Request = ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Request = ServiceRequest;
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+    self.service.borrow_mut().poll_ready(cx)
+  }
+
+  fn call(&mut self, req: ServiceRequest) -> Self::Future {
+    let start = Instant::now();
+    let method = req.method().to_string();
+    let route = req.match_pattern().unwrap_or_else(|| "unknown".to_owned());
+    let fut = self.service.borrow_mut().call(req);
+
+    Box::pin(async move {
+      let res = fut.await;
+
+      let status = match &res {
+        Ok(res) => res.status(),
+        Err(err) => err.as_response_error().status_code(),
+      };
+
+      metrics::inc_counter(
+        &HTTP_REQUESTS,
+        &[("route", &route), ("method", &method), ("status", status.as_str())],
+        1.0,
+      );
+      metrics::observe_since(&HTTP_REQUEST_DURATION, &[("route", &route), ("method", &method)], start);
+
+      res
+    })
+  }
+}
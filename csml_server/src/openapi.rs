@@ -0,0 +1,441 @@
+/**
+ * OpenAPI 3 description of the routes of the server, served at /openapi.json.
+ * Each route is described once in `ROUTES`; the document is generated from this list.
+ */
+use serde_json::{json, Map, Value};
+
+const CLIENT_QUERY: &[Param] = &[
+  Param::query("bot_id", true),
+  Param::query("channel_id", true),
+  Param::query("user_id", true),
+];
+
+const PAGINATED_CLIENT_QUERY: &[Param] = &[
+  Param::query("bot_id", true),
+  Param::query("channel_id", true),
+  Param::query("user_id", true),
+  Param::query("limit", false),
+  Param::query("pagination_key", false),
+];
+
+struct Param {
+  name: &'static str,
+  location: &'static str,
+  required: bool,
+}
+
+impl Param {
+  const fn path(name: &'static str) -> Self {
+    Param { name, location: "path", required: true }
+  }
+
+  const fn query(name: &'static str, required: bool) -> Self {
+    Param { name, location: "query", required }
+  }
+
+  const fn header(name: &'static str, required: bool) -> Self {
+    Param { name, location: "header", required }
+  }
+}
+
+struct Route {
+  method: &'static str,
+  path: &'static str,
+  tag: &'static str,
+  summary: &'static str,
+  scope: Option<&'static str>,
+  params: &'static [Param],
+  body: Option<&'static str>,
+  status: &'static str,
+  response: Option<&'static str>,
+}
+
+const ROUTES: &[Route] = &[
+  Route { method: "get", path: "/", tag: "server", summary: "Home page of the server", scope: None, params: &[], body: None, status: "200", response: None },
+  Route { method: "get", path: "/openapi.json", tag: "server", summary: "OpenAPI description of the server", scope: None, params: &[], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/healthz", tag: "server", summary: "Liveness check", scope: None, params: &[], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/readyz", tag: "server", summary: "Readiness check: the database can be reached", scope: None, params: &[], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/metrics", tag: "server", summary: "Metrics in the Prometheus text format", scope: Some("read"), params: &[], body: None, status: "200", response: None },
+  Route { method: "post", path: "/validate", tag: "bots", summary: "Validate a bot", scope: Some("read"), params: &[], body: Some("CsmlBot"), status: "200", response: Some("Validation") },
+  Route { method: "post", path: "/run", tag: "run", summary: "Send an event to a bot", scope: Some("run"), params: &[], body: Some("RunRequest"), status: "200", response: Some("Messages") },
+  Route { method: "get", path: "/run/stream", tag: "run", summary: "Send an event to a bot and receive its messages as Server-Sent Events", scope: Some("run"), params: &[Param::query("bot_id", true), Param::query("version_id", false), Param::query("label", false), Param::query("fn_endpoint", false), Param::query("channel_id", true), Param::query("user_id", true), Param::query("request_id", true), Param::query("payload", true), Param::query("metadata", false)], body: None, status: "200", response: None },
+  Route { method: "get", path: "/ws", tag: "run", summary: "Send events to bots and receive their messages over a WebSocket", scope: Some("run"), params: &[Param::query("bot_id", false)], body: None, status: "101", response: None },
+  Route { method: "post", path: "/sns", tag: "run", summary: "Send an event to a bot as an AWS SNS notification", scope: Some("run"), params: &[], body: Some("Object"), status: "200", response: Some("Messages") },
+  Route { method: "post", path: "/bots", tag: "bots", summary: "Create a new version of a bot", scope: Some("admin"), params: &[], body: Some("CsmlBot"), status: "201", response: Some("BotVersionCreated") },
+  Route { method: "get", path: "/bots/{bot_id}", tag: "bots", summary: "Get the latest version of a bot", scope: Some("read"), params: &[Param::path("bot_id")], body: None, status: "200", response: Some("BotVersion") },
+  Route { method: "delete", path: "/bots/{bot_id}", tag: "bots", summary: "Delete all the versions of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: None, status: "204", response: None },
+  Route { method: "get", path: "/bots/{bot_id}/versions", tag: "bots", summary: "List the versions of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::query("limit", false), Param::query("pagination_key", false)], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/bots/{bot_id}/versions/{version_id}", tag: "bots", summary: "Get a version of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::path("version_id")], body: None, status: "200", response: Some("BotVersion") },
+  Route { method: "get", path: "/bots/{bot_id}/versions/{version_id}/export", tag: "bots", summary: "Export a version of a bot as a package", scope: Some("read"), params: &[Param::path("bot_id"), Param::path("version_id")], body: None, status: "200", response: Some("BotPackage") },
+  Route { method: "post", path: "/bots/{bot_id}/import", tag: "bots", summary: "Import a package as a new version of a bot", scope: Some("admin"), params: &[Param::path("bot_id"), Param::header("X-Bot-Env", false)], body: Some("BotPackage"), status: "201", response: Some("BotVersionCreated") },
+  Route { method: "delete", path: "/bots/{bot_id}/versions/{version_id}", tag: "bots", summary: "Delete a version of a bot", scope: Some("admin"), params: &[Param::path("bot_id"), Param::path("version_id")], body: None, status: "204", response: None },
+  Route { method: "get", path: "/bots/{bot_id}/labels", tag: "bots", summary: "List the labels of a bot", scope: Some("read"), params: &[Param::path("bot_id")], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/bots/{bot_id}/labels/{label}", tag: "bots", summary: "Get a label of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::path("label")], body: None, status: "200", response: Some("BotLabel") },
+  Route { method: "delete", path: "/bots/{bot_id}/labels/{label}", tag: "bots", summary: "Delete a label of a bot", scope: Some("admin"), params: &[Param::path("bot_id"), Param::path("label")], body: None, status: "204", response: None },
+  Route { method: "post", path: "/bots/{bot_id}/labels/{label}/promote", tag: "bots", summary: "Make a label point to a version of a bot", scope: Some("admin"), params: &[Param::path("bot_id"), Param::path("label")], body: Some("BotVersionId"), status: "200", response: Some("BotLabel") },
+  Route { method: "post", path: "/bots/{bot_id}/labels/{label}/rollback", tag: "bots", summary: "Make a label point back to its previous version, or to a given version", scope: Some("admin"), params: &[Param::path("bot_id"), Param::path("label")], body: Some("Object"), status: "200", response: Some("BotLabel") },
+  Route { method: "get", path: "/bots/{bot_id}/diff", tag: "bots", summary: "Compare the flows of two versions of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::query("from", true), Param::query("to", true)], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/bots/{bot_id}/analytics", tag: "bots", summary: "Get the analytics of a bot over at most 90 days", scope: Some("read"), params: &[Param::path("bot_id"), Param::query("from", false), Param::query("to", false)], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/bots/{bot_id}/conversations/open", tag: "bots", summary: "List the open conversations of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::query("limit", false), Param::query("pagination_key", false)], body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/bots/{bot_id}/conversations/{conversation_id}", tag: "bots", summary: "Get a conversation with its nodes, interactions and messages", scope: Some("read"), params: &[Param::path("bot_id"), Param::path("conversation_id"), Param::query("channel_id", true), Param::query("user_id", true)], body: None, status: "200", response: Some("Object") },
+  Route { method: "post", path: "/bots/{bot_id}/conversations/{conversation_id}/close", tag: "bots", summary: "Close a conversation of a client", scope: Some("admin"), params: &[Param::path("bot_id"), Param::path("conversation_id")], body: Some("BotClient"), status: "200", response: None },
+  Route { method: "get", path: "/bots/{bot_id}/clients", tag: "bots", summary: "Search the clients of a bot", scope: Some("read"), params: &[Param::path("bot_id"), Param::query("channel_id", false), Param::query("user_id", false), Param::query("limit", false), Param::query("pagination_key", false)], body: None, status: "200", response: Some("Object") },
+  Route { method: "post", path: "/bots/{bot_id}/limits", tag: "bots", summary: "Set the rate limits and daily quota of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: Some("BotLimits"), status: "200", response: None },
+  Route { method: "get", path: "/bots/{bot_id}/limits", tag: "bots", summary: "Get the rate limits and daily quota of a bot", scope: Some("read"), params: &[Param::path("bot_id")], body: None, status: "200", response: Some("BotLimits") },
+  Route { method: "delete", path: "/bots/{bot_id}/limits", tag: "bots", summary: "Remove the rate limits and daily quota of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: None, status: "204", response: None },
+  Route { method: "post", path: "/bots/{bot_id}/http_policy", tag: "bots", summary: "Set the outbound HTTP policy of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: Some("HttpPolicy"), status: "200", response: None },
+  Route { method: "get", path: "/bots/{bot_id}/http_policy", tag: "bots", summary: "Get the outbound HTTP policy of a bot", scope: Some("read"), params: &[Param::path("bot_id")], body: None, status: "200", response: Some("HttpPolicy") },
+  Route { method: "delete", path: "/bots/{bot_id}/http_policy", tag: "bots", summary: "Remove the outbound HTTP policy of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: None, status: "204", response: None },
+  Route { method: "post", path: "/conversations/open", tag: "conversations", summary: "Get the open conversation of a client", scope: Some("read"), params: &[], body: Some("Client"), status: "200", response: Some("Object") },
+  Route { method: "post", path: "/conversations/close", tag: "conversations", summary: "Close all the conversations of a client", scope: Some("admin"), params: &[], body: Some("Client"), status: "200", response: None },
+  Route { method: "get", path: "/conversations", tag: "conversations", summary: "List the conversations of a client", scope: Some("read"), params: PAGINATED_CLIENT_QUERY, body: None, status: "200", response: Some("Object") },
+  Route { method: "post", path: "/memories", tag: "memories", summary: "Create a memory for a client", scope: Some("admin"), params: CLIENT_QUERY, body: Some("Memory"), status: "201", response: None },
+  Route { method: "get", path: "/memories", tag: "memories", summary: "Get all the memories of a client", scope: Some("read"), params: CLIENT_QUERY, body: None, status: "200", response: Some("Object") },
+  Route { method: "delete", path: "/memories", tag: "memories", summary: "Delete all the memories of a client", scope: Some("admin"), params: CLIENT_QUERY, body: None, status: "204", response: None },
+  Route { method: "get", path: "/memories/{key}", tag: "memories", summary: "Get a memory of a client", scope: Some("read"), params: &[Param::path("key"), Param::query("bot_id", true), Param::query("channel_id", true), Param::query("user_id", true)], body: None, status: "200", response: Some("Object") },
+  Route { method: "delete", path: "/memories/{key}", tag: "memories", summary: "Delete a memory of a client", scope: Some("admin"), params: &[Param::path("key"), Param::query("bot_id", true), Param::query("channel_id", true), Param::query("user_id", true)], body: None, status: "204", response: None },
+  Route { method: "get", path: "/messages", tag: "messages", summary: "List the messages of a client", scope: Some("read"), params: PAGINATED_CLIENT_QUERY, body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/state", tag: "state", summary: "Get the current state of a client", scope: Some("read"), params: CLIENT_QUERY, body: None, status: "200", response: Some("Object") },
+  Route { method: "get", path: "/handoff", tag: "handoff", summary: "Get the current handoff of a client", scope: Some("read"), params: CLIENT_QUERY, body: None, status: "200", response: Some("Object") },
+  Route { method: "post", path: "/handoff/start", tag: "handoff", summary: "Hand the open conversation of a client off to a human agent", scope: Some("admin"), params: &[], body: Some("StartHandoff"), status: "200", response: None },
+  Route { method: "post", path: "/handoff/end", tag: "handoff", summary: "Give the conversation of a client back to the bot", scope: Some("admin"), params: &[], body: Some("EndHandoff"), status: "200", response: None },
+  Route { method: "delete", path: "/data/bots/{bot_id}", tag: "data", summary: "Delete all the data of a bot", scope: Some("admin"), params: &[Param::path("bot_id")], body: None, status: "204", response: None },
+  Route { method: "delete", path: "/data/clients", tag: "data", summary: "Delete all the data of a client", scope: Some("admin"), params: CLIENT_QUERY, body: None, status: "204", response: None },
+  Route { method: "post", path: "/api_keys", tag: "api_keys", summary: "Create an API key", scope: Some("admin"), params: &[], body: Some("CreateApiKey"), status: "201", response: Some("ApiKeyCreated") },
+  Route { method: "delete", path: "/api_keys/{id}", tag: "api_keys", summary: "Revoke an API key", scope: Some("admin"), params: &[Param::path("id")], body: None, status: "200", response: None },
+];
+
+fn schema_ref(name: &str) -> Value {
+  json!({"$ref": format!("#/components/schemas/{}", name)})
+}
+
+// bot packages are .tar.gz archives, everything else is JSON
+fn get_content(schema: &str) -> Value {
+  match schema {
+    "BotPackage" => json!({"application/gzip": {"schema": schema_ref(schema)}}),
+    _ => json!({"application/json": {"schema": schema_ref(schema)}}),
+  }
+}
+
+fn get_operation(route: &Route) -> Value {
+  let parameters: Vec<Value> = route
+    .params
+    .iter()
+    .map(|param| {
+      json!({
+        "name": param.name,
+        "in": param.location,
+        "required": param.required,
+        "schema": {"type": if param.name == "limit" { "integer" } else { "string" }},
+      })
+    })
+    .collect();
+
+  let mut success = json!({"description": "Success"});
+  if let Some(response) = route.response {
+    success["content"] = get_content(response);
+  }
+
+  let error = json!({
+    "description": "Error",
+    "content": {"application/json": {"schema": schema_ref("Error")}},
+  });
+
+  let mut responses = Map::new();
+  responses.insert(route.status.to_owned(), success);
+  responses.insert("default".to_owned(), error);
+
+  let mut operation = json!({
+    "tags": [route.tag],
+    "summary": route.summary,
+    "parameters": parameters,
+    "responses": responses,
+  });
+
+  if let Some(body) = route.body {
+    operation["requestBody"] = json!({
+      "required": true,
+      "content": get_content(body),
+    });
+  }
+
+  match route.scope {
+    Some(scope) => {
+      operation["security"] = json!([{"bearer": [scope]}, {"api_key": [scope]}]);
+      operation["x-scope"] = json!(scope);
+    }
+    None => operation["security"] = json!([]),
+  }
+
+  operation
+}
+
+fn get_schemas() -> Value {
+  json!({
+    "Object": {"type": "object"},
+    "Error": {
+      "type": "object",
+      "required": ["code", "message"],
+      "properties": {
+        "code": {"type": "string"},
+        "message": {"type": "string"},
+        "details": {"nullable": true},
+      },
+    },
+    "Client": {
+      "type": "object",
+      "required": ["bot_id", "channel_id", "user_id"],
+      "properties": {
+        "bot_id": {"type": "string"},
+        "channel_id": {"type": "string"},
+        "user_id": {"type": "string"},
+      },
+    },
+    "BotClient": {
+      "type": "object",
+      "required": ["channel_id", "user_id"],
+      "properties": {
+        "channel_id": {"type": "string"},
+        "user_id": {"type": "string"},
+      },
+    },
+    "CsmlFlow": {
+      "type": "object",
+      "required": ["id", "name", "content", "commands"],
+      "properties": {
+        "id": {"type": "string"},
+        "name": {"type": "string"},
+        "content": {"type": "string"},
+        "commands": {"type": "array", "items": {"type": "string"}},
+      },
+    },
+    "CsmlBot": {
+      "type": "object",
+      "required": ["id", "name", "flows", "default_flow"],
+      "properties": {
+        "id": {"type": "string"},
+        "name": {"type": "string"},
+        "fn_endpoint": {"type": "string", "nullable": true},
+        "flows": {"type": "array", "items": schema_ref("CsmlFlow")},
+        "native_components": {"type": "object", "nullable": true},
+        "custom_components": {"nullable": true},
+        "default_flow": {"type": "string"},
+        "env": {"nullable": true},
+      },
+    },
+    "BotVersion": {
+      "allOf": [
+        schema_ref("CsmlBot"),
+        {
+          "type": "object",
+          "properties": {
+            "version_id": {"type": "string"},
+            "engine_version": {"type": "string"},
+          },
+        },
+      ],
+    },
+    "RateLimit": {
+      "type": "object",
+      "required": ["burst", "per_second"],
+      "properties": {
+        "burst": {"type": "number"},
+        "per_second": {"type": "number"},
+      },
+    },
+    "BotLimits": {
+      "type": "object",
+      "properties": {
+        "bot": {"allOf": [schema_ref("RateLimit")], "nullable": true},
+        "channel": {"allOf": [schema_ref("RateLimit")], "nullable": true},
+        "user": {"allOf": [schema_ref("RateLimit")], "nullable": true},
+        "daily_interactions": {"type": "integer", "nullable": true},
+      },
+    },
+    "HttpPolicy": {
+      "type": "object",
+      "properties": {
+        "allow_hosts": {"type": "array", "items": {"type": "string"}, "nullable": true},
+        "deny_hosts": {"type": "array", "items": {"type": "string"}},
+        "allow_private_ips": {"type": "boolean"},
+        "max_response_bytes": {"type": "integer", "nullable": true},
+        "max_requests": {"type": "integer", "nullable": true},
+      },
+    },
+    "BotLabel": {
+      "type": "object",
+      "properties": {
+        "label": {"type": "string"},
+        "version_id": {"type": "string"},
+        "history": {"type": "array", "items": {"type": "string"}},
+        "updated_at": {"type": "string"},
+      },
+    },
+    "BotVersionId": {
+      "type": "object",
+      "required": ["version_id"],
+      "properties": {
+        "version_id": {"type": "string"},
+      },
+    },
+    "VersionWeight": {
+      "type": "object",
+      "required": ["version_id", "weight"],
+      "properties": {
+        "version_id": {"type": "string"},
+        "weight": {"type": "integer", "minimum": 0},
+      },
+    },
+    "BotPackage": {
+      "type": "string",
+      "format": "binary",
+      "description": "A .tar.gz archive with manifest.json, flows/*.csml, components/*.json and env.schema.json",
+    },
+    "BotVersionCreated": {
+      "type": "object",
+      "properties": {
+        "version_id": {"type": "string"},
+        "engine_version": {"type": "string"},
+      },
+    },
+    "Validation": {
+      "type": "object",
+      "properties": {
+        "valid": {"type": "boolean"},
+        "errors": {"type": "array", "items": {"type": "object"}},
+      },
+    },
+    "CsmlRequest": {
+      "type": "object",
+      "required": ["request_id", "client", "payload"],
+      "properties": {
+        "request_id": {"type": "string"},
+        "client": schema_ref("Client"),
+        "callback_url": {"type": "string", "nullable": true},
+        "payload": {"type": "object"},
+        "metadata": {"type": "object"},
+      },
+    },
+    "RunRequest": {
+      "type": "object",
+      "required": ["event"],
+      "description": "Either a bot, or a bot_id (and optionally a version_id, a label or a split) must be given",
+      "properties": {
+        "bot": schema_ref("CsmlBot"),
+        "bot_id": {"type": "string"},
+        "version_id": {"type": "string"},
+        "label": {"type": "string"},
+        "split": {"type": "array", "items": schema_ref("VersionWeight")},
+        "fn_endpoint": {"type": "string"},
+        "event": schema_ref("CsmlRequest"),
+      },
+    },
+    "Messages": {
+      "type": "object",
+      "properties": {
+        "messages": {"type": "array", "items": {"type": "object"}},
+        "conversation_end": {"type": "boolean"},
+        "request_id": {"type": "string"},
+        "interaction_id": {"type": "string"},
+        "received_at": {"type": "string"},
+        "client": schema_ref("Client"),
+      },
+    },
+    "Memory": {
+      "type": "object",
+      "required": ["key", "value"],
+      "properties": {
+        "key": {"type": "string"},
+        "value": {},
+      },
+    },
+    "StartHandoff": {
+      "type": "object",
+      "required": ["client"],
+      "properties": {
+        "client": schema_ref("Client"),
+        "reason": {},
+      },
+    },
+    "EndHandoff": {
+      "type": "object",
+      "required": ["client"],
+      "properties": {
+        "client": schema_ref("Client"),
+        "flow": {"type": "string"},
+        "step": {"type": "string"},
+      },
+    },
+    "CreateApiKey": {
+      "type": "object",
+      "required": ["scopes"],
+      "properties": {
+        "scopes": {"type": "array", "items": {"type": "string", "enum": ["run", "read", "admin"]}},
+        "bot_ids": {"type": "array", "items": {"type": "string"}},
+      },
+    },
+    "ApiKeyCreated": {
+      "type": "object",
+      "properties": {
+        "id": {"type": "string"},
+        "key": {"type": "string"},
+        "scopes": {"type": "array", "items": {"type": "string"}},
+        "bot_ids": {"type": "array", "items": {"type": "string"}, "nullable": true},
+      },
+    },
+  })
+}
+
+pub fn get_openapi_spec() -> Value {
+  let mut paths = Map::new();
+
+  for route in ROUTES.iter() {
+    let path = paths.entry(route.path).or_insert_with(|| json!({}));
+    path[route.method] = get_operation(route);
+  }
+
+  json!({
+    "openapi": "3.0.3",
+    "info": {
+      "title": "CSML Server",
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "paths": paths,
+    "components": {
+      "schemas": get_schemas(),
+      "securitySchemes": {
+        "bearer": {"type": "http", "scheme": "bearer"},
+        "api_key": {"type": "apiKey", "in": "header", "name": "X-Api-Key"},
+      },
+    },
+  })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::get_required_scope;
+    use actix_web::http::Method;
+
+    #[test]
+    fn test_routes_scopes() {
+        for route in ROUTES.iter() {
+            let method = Method::from_bytes(route.method.to_uppercase().as_bytes()).unwrap();
+            let path = route.path.replace("{", "").replace("}", "");
+
+            assert_eq!(get_required_scope(&method, &path), route.scope, "{} {}", route.method, route.path);
+        }
+    }
+
+    #[test]
+    fn test_openapi_spec() {
+        let spec = get_openapi_spec();
+
+        assert_eq!(spec["paths"]["/run"]["post"]["x-scope"], "run");
+        assert_eq!(
+            spec["paths"]["/bots/{bot_id}"]["delete"]["responses"]["default"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Error"
+        );
+        assert_eq!(spec["paths"]["/bots/{bot_id}/import"]["post"]["parameters"][1]["in"], "header");
+    }
+}
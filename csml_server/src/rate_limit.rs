@@ -0,0 +1,317 @@
+/**
+ * The requests sent to the bots (POST /run, GET /run/stream, /ws and POST /sns) are
+ * rate limited with token buckets: one for each bot, one for each channel of a bot and
+ * one for each user of a channel. Rejected requests get a 429 response with a Retry-After header.
+ *
+ * A bot can also have a daily quota of interactions. The number of interactions of each bot
+ * is counted in the database, so that the quota is shared by every server instance.
+ *
+ * The default limits of every bot are given by ENGINE_SERVER_RATE_LIMITS (JSON), e.g.
+ * {"bot": {"burst": 100, "per_second": 10}, "user": {"burst": 5, "per_second": 1}, "daily_interactions": 10000}
+ * With ENGINE_SERVER_RATE_LIMITS_DB=true, the limits set for a bot with POST /bots/{bot_id}/limits
+ * replace the default limits of this bot.
+ */
+use crate::error::ApiError;
+use actix_web::{http::StatusCode, web};
+use csml_engine::data::{BotLimits, EngineError, RateLimit};
+use csml_interpreter::data::Client;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+// how long the limits of a bot read from the database are used before being read again
+const BOT_LIMITS_TTL: Duration = Duration::from_secs(60);
+// full buckets are dropped once there are too many of them
+const MAX_BUCKETS: usize = 100_000;
+
+type BucketKey = (&'static str, String, String, String);
+
+struct Bucket {
+  limit: RateLimit,
+  tokens: f64,
+  updated_at: Instant,
+}
+
+impl Bucket {
+  fn new(limit: &RateLimit, now: Instant) -> Self {
+    Self {
+      limit: limit.to_owned(),
+      tokens: limit.burst,
+      updated_at: now,
+    }
+  }
+
+  fn refill(&mut self, now: Instant) {
+    let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+
+    self.tokens = (self.tokens + elapsed * self.limit.per_second).min(self.limit.burst);
+    self.updated_at = now;
+  }
+}
+
+#[derive(Default)]
+pub struct RateLimiter {
+  defaults: BotLimits,
+  use_db: bool,
+  buckets: Mutex<HashMap<BucketKey, Bucket>>,
+  bot_limits: Mutex<HashMap<String, (Instant, Option<BotLimits>)>>,
+}
+
+impl RateLimiter {
+  pub fn new(defaults: BotLimits, use_db: bool) -> Self {
+    Self {
+      defaults,
+      use_db,
+      ..Default::default()
+    }
+  }
+
+  /**
+   * Default limits given as JSON, checked like the limits set with POST /bots/{bot_id}/limits
+   */
+  fn parse_defaults(limits: &str) -> Result<BotLimits, String> {
+    let limits = serde_json::from_str::<BotLimits>(limits).map_err(|err| err.to_string())?;
+
+    match csml_engine::validate_bot_limits(&limits) {
+      Ok(()) => Ok(limits),
+      Err(EngineError::Format(message)) => Err(message),
+      Err(err) => Err(format!("{:?}", err)),
+    }
+  }
+
+  pub fn from_env() -> Result<Self, String> {
+    let defaults = match std::env::var("ENGINE_SERVER_RATE_LIMITS") {
+      Ok(limits) if !limits.is_empty() => match Self::parse_defaults(&limits) {
+        Ok(limits) => limits,
+        Err(err) => return Err(format!("ENGINE_SERVER_RATE_LIMITS is not valid: {}", err)),
+      },
+      _ => BotLimits::default(),
+    };
+
+    let use_db = matches!(std::env::var("ENGINE_SERVER_RATE_LIMITS_DB"), Ok(var) if var == "true");
+
+    Ok(Self::new(defaults, use_db))
+  }
+
+  /**
+   * Limits of a bot, read from the database at most once every BOT_LIMITS_TTL
+   */
+  async fn get_limits(&self, bot_id: &str) -> BotLimits {
+    if !self.use_db {
+      return self.defaults.to_owned();
+    }
+
+    let cached = match self.bot_limits.lock().unwrap().get(bot_id) {
+      Some((read_at, limits)) if read_at.elapsed() < BOT_LIMITS_TTL => {
+        return limits.to_owned().unwrap_or_else(|| self.defaults.to_owned());
+      }
+      Some((_, limits)) => limits.to_owned(),
+      None => None,
+    };
+
+    let owned_bot_id = bot_id.to_owned();
+    let res = web::block(move || csml_engine::get_bot_limits(&owned_bot_id)).await;
+
+    let limits = match res {
+      Ok(limits) => limits,
+      Err(err) => {
+        eprintln!("EngineError: {:?}", err);
+        cached
+      }
+    };
+
+    self.bot_limits.lock().unwrap().insert(bot_id.to_owned(), (Instant::now(), limits.to_owned()));
+
+    limits.unwrap_or_else(|| self.defaults.to_owned())
+  }
+
+  /**
+   * The limits of this bot have changed: read them again on its next request
+   */
+  pub fn forget_bot(&self, bot_id: &str) {
+    self.bot_limits.lock().unwrap().remove(bot_id);
+  }
+
+  /**
+   * Take a token from each bucket of the client, or none at all if one of them is empty
+   */
+  fn take_tokens(&self, client: &Client, limits: &BotLimits) -> Result<(), ApiError> {
+    let keys = [
+      (&limits.bot, ("bot", client.bot_id.to_owned(), String::new(), String::new())),
+      (&limits.channel, ("channel", client.bot_id.to_owned(), client.channel_id.to_owned(), String::new())),
+      (&limits.user, ("user", client.bot_id.to_owned(), client.channel_id.to_owned(), client.user_id.to_owned())),
+    ];
+
+    let now = Instant::now();
+    let mut buckets = self.buckets.lock().unwrap();
+
+    if buckets.len() > MAX_BUCKETS {
+      buckets.retain(|_, bucket| {
+        bucket.refill(now);
+        bucket.tokens < bucket.limit.burst
+      });
+    }
+
+    let mut exceeded: Option<(&str, f64)> = None;
+    for (limit, key) in keys.iter() {
+      let limit = match limit {
+        Some(limit) => limit,
+        None => continue,
+      };
+
+      let bucket = buckets.entry(key.to_owned()).or_insert_with(|| Bucket::new(limit, now));
+      bucket.limit = limit.to_owned();
+      bucket.refill(now);
+
+      if bucket.tokens < 1.0 {
+        let wait = (1.0 - bucket.tokens) / limit.per_second;
+
+        match exceeded {
+          Some((_, max_wait)) if max_wait >= wait => {}
+          _ => exceeded = Some((key.0, wait)),
+        }
+      }
+    }
+
+    if let Some((scope, wait)) = exceeded {
+      return Err(
+        ApiError::new(
+          StatusCode::TOO_MANY_REQUESTS,
+          "rate_limited",
+          &format!("too many requests for this {}", scope),
+        )
+        .with_details(json!({"scope": scope}))
+        .with_retry_after(wait.ceil() as u64)
+      );
+    }
+
+    for (limit, key) in keys.iter() {
+      if limit.is_some() {
+        if let Some(bucket) = buckets.get_mut(key) {
+          bucket.tokens -= 1.0;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  /**
+   * Count the interaction in the daily quota of the bot. Rejected interactions are not counted.
+   * If the database can not be reached, the request is let through.
+   */
+  async fn count_interaction(&self, bot_id: &str, limits: &BotLimits) -> Result<(), ApiError> {
+    let max = match limits.daily_interactions {
+      Some(max) => max,
+      None => return Ok(()),
+    };
+
+    let owned_bot_id = bot_id.to_owned();
+    let res = web::block(move || csml_engine::increment_daily_interactions(&owned_bot_id, max)).await;
+
+    match res {
+      Ok(None) => {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let next_day = 86_400 - now % 86_400;
+
+        Err(
+          ApiError::new(
+            StatusCode::TOO_MANY_REQUESTS,
+            "quota_exceeded",
+            "the daily quota of interactions of this bot is exceeded",
+          )
+          .with_details(json!({"daily_interactions": max}))
+          .with_retry_after(next_day)
+        )
+      }
+      Ok(Some(_)) => Ok(()),
+      Err(err) => {
+        eprintln!("EngineError: {:?}", err);
+        Ok(())
+      }
+    }
+  }
+
+  pub async fn check(&self, client: &Client) -> Result<(), ApiError> {
+    let limits = self.get_limits(&client.bot_id).await;
+
+    self.take_tokens(client, &limits)?;
+    self.count_interaction(&client.bot_id, &limits).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::ResponseError;
+
+    fn client(user_id: &str) -> Client {
+        Client {
+            bot_id: "bot_id".to_owned(),
+            channel_id: "channel_id".to_owned(),
+            user_id: user_id.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let limits = RateLimiter::parse_defaults(r#"{"user": {"burst": 5, "per_second": 1}, "daily_interactions": 100}"#).unwrap();
+        assert_eq!(limits.daily_interactions, Some(100));
+
+        assert!(RateLimiter::parse_defaults(r#"{"bot": {"burst": 10, "per_second": 0}}"#).is_err());
+        assert!(RateLimiter::parse_defaults(r#"{"user": {"burst": -1, "per_second": 1}}"#).is_err());
+        assert!(RateLimiter::parse_defaults("not json").is_err());
+    }
+
+    #[actix_rt::test]
+    async fn test_no_limits() {
+        let limiter = RateLimiter::default();
+
+        for _ in 0..100 {
+            assert!(limiter.check(&client("user_id")).await.is_ok());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn test_user_limit() {
+        let limiter = RateLimiter::new(
+            BotLimits {
+                user: Some(RateLimit { burst: 2.0, per_second: 0.5 }),
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert!(limiter.check(&client("user_id")).await.is_ok());
+        assert!(limiter.check(&client("user_id")).await.is_ok());
+
+        let err = limiter.check(&client("user_id")).await.unwrap_err();
+        assert_eq!(err.status_code(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(err.body().code, "rate_limited");
+        assert_eq!(err.body().details, Some(json!({"scope": "user"})));
+
+        let res = err.error_response();
+        assert_eq!(res.headers().get("retry-after").unwrap(), "2");
+
+        // other users have their own bucket
+        assert!(limiter.check(&client("other_user_id")).await.is_ok());
+    }
+
+    #[actix_rt::test]
+    async fn test_bot_limit() {
+        let limiter = RateLimiter::new(
+            BotLimits {
+                bot: Some(RateLimit { burst: 1.0, per_second: 1.0 }),
+                user: Some(RateLimit { burst: 10.0, per_second: 1.0 }),
+                ..Default::default()
+            },
+            false,
+        );
+
+        assert!(limiter.check(&client("user_id")).await.is_ok());
+
+        let err = limiter.check(&client("other_user_id")).await.unwrap_err();
+        assert_eq!(err.body().details, Some(json!({"scope": "bot"})));
+    }
+}
@@ -9,3 +9,14 @@ pub mod messages;
 pub mod state;
 
 pub mod bot_versions;
+pub mod bot_labels;
+pub mod bot_limits;
+pub mod bot_packages;
+pub mod http_policies;
+pub mod analytics;
+pub mod admin;
+pub mod handoff;
+pub mod api_keys;
+pub mod openapi;
+pub mod stream;
+pub mod health;
@@ -0,0 +1,250 @@
+use actix_web::{get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use csml_engine::Client;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConversationPath {
+  bot_id: String,
+  conversation_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginationQuery {
+  limit: Option<i64>,
+  pagination_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ConversationClientQuery {
+  channel_id: String,
+  user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchClientsQuery {
+  channel_id: Option<String>,
+  user_id: Option<String>,
+  limit: Option<i64>,
+  pagination_key: Option<String>,
+}
+
+fn get_pagination_key(pagination_key: &Option<String>) -> Option<String> {
+  match pagination_key {
+    Some(pagination_key) if pagination_key.is_empty() => None,
+    Some(pagination_key) => Some(pagination_key.to_owned()),
+    None => None,
+  }
+}
+
+/**
+ * List the conversations that are still open for any client of a bot
+ *
+ * {"statusCode": 200,"body": {"conversations": [Conversation], "pagination_key": Option<String>}}
+ */
+#[get("/bots/{bot_id}/conversations/open")]
+pub async fn get_bot_open_conversations(
+  path: web::Path<BotIdPath>,
+  query: web::Query<PaginationQuery>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let limit = query.limit.to_owned();
+  let pagination_key = get_pagination_key(&query.pagination_key);
+
+  let res = thread::spawn(move || {
+    csml_engine::get_bot_open_conversations(&bot_id, limit, pagination_key)
+  }).join().unwrap();
+
+  match res {
+    Ok(data) => HttpResponse::Ok().json(data),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Get a conversation of a client with all its nodes, interactions and messages
+ *
+ * {"statusCode": 200,"body": {"conversation": Conversation, "interactions": [Interaction], "nodes": [Node], "messages": [Message]}}
+ */
+#[get("/bots/{bot_id}/conversations/{conversation_id}")]
+pub async fn get_conversation(
+  path: web::Path<ConversationPath>,
+  query: web::Query<ConversationClientQuery>,
+) -> HttpResponse {
+  let client = Client {
+    bot_id: path.bot_id.to_owned(),
+    channel_id: query.channel_id.to_owned(),
+    user_id: query.user_id.to_owned(),
+  };
+  let conversation_id = path.conversation_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_conversation_details(&client, &conversation_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(data)) => HttpResponse::Ok().json(data),
+    Ok(None) => ApiError::not_found("Conversation not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Close a given conversation of a client
+ *
+ * {"channel_id": String, "user_id": String}
+ */
+#[post("/bots/{bot_id}/conversations/{conversation_id}/close")]
+pub async fn close_conversation(
+  path: web::Path<ConversationPath>,
+  body: web::Json<ConversationClientQuery>,
+) -> HttpResponse {
+  let client = Client {
+    bot_id: path.bot_id.to_owned(),
+    channel_id: body.channel_id.to_owned(),
+    user_id: body.user_id.to_owned(),
+  };
+  let conversation_id = path.conversation_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::close_client_conversation(&client, &conversation_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(true) => HttpResponse::Ok().finish(),
+    Ok(false) => ApiError::not_found("Conversation not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Search the clients that had a conversation with a bot,
+ * optionally in a given channel and with a user id starting with `user_id`
+ *
+ * {"statusCode": 200,"body": {"clients": [Client], "pagination_key": Option<String>}}
+ */
+#[get("/bots/{bot_id}/clients")]
+pub async fn search_bot_clients(
+  path: web::Path<BotIdPath>,
+  query: web::Query<SearchClientsQuery>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let channel_id = query.channel_id.to_owned();
+  let user_id = query.user_id.to_owned();
+  let limit = query.limit.to_owned();
+  let pagination_key = get_pagination_key(&query.pagination_key);
+
+  let res = thread::spawn(move || {
+    csml_engine::search_bot_clients(
+      &bot_id,
+      channel_id.as_deref(),
+      user_id.as_deref(),
+      limit,
+      pagination_key,
+    )
+  }).join().unwrap();
+
+  match res {
+    Ok(data) => HttpResponse::Ok().json(data),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_get_bot_open_conversations() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_bot_open_conversations)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/conversations/open?limit=5")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_get_unknown_conversation() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_conversation)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/conversations/unknown?channel_id=admin-channel&user_id=test")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_get_conversation_without_client() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_conversation)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/conversations/unknown")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_close_unknown_conversation() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(close_conversation)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/conversations/unknown/close")
+                    .set_json(&serde_json::json!({
+                      "channel_id": "admin-channel",
+                      "user_id": "test"
+                    }))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_search_bot_clients() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(search_bot_clients)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/clients?channel_id=admin-channel&user_id=te")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
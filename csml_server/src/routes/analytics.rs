@@ -0,0 +1,94 @@
+use actix_web::{get, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnalyticsQuery {
+  from: Option<String>,
+  to: Option<String>,
+}
+
+/**
+ * Get the analytics of a given bot: most visited steps, drop-off points,
+ * flow completion rates, average conversation length and error rate.
+ * `from` and `to` are optional RFC 3339 dates, the time range defaults to the last 90 days
+ * and can not be longer than 90 days.
+ *
+ * {"statusCode": 200,"body": Analytics}
+ */
+#[get("/bots/{bot_id}/analytics")]
+pub async fn get_bot_analytics(
+  path: web::Path<BotIdPath>,
+  query: web::Query<AnalyticsQuery>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let from = query.from.to_owned();
+  let to = query.to.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_bot_analytics(&bot_id, from.as_deref(), to.as_deref())
+  }).join().unwrap();
+
+  match res {
+    Ok(data) => HttpResponse::Ok().json(data),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_bot_analytics() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_bot_analytics)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/analytics?from=2021-01-01T00:00:00Z&to=2021-02-01T00:00:00Z")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_bot_analytics_too_long_range() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_bot_analytics)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/analytics?from=2021-01-01T00:00:00Z&to=2021-06-01T00:00:00Z")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_bot_analytics_invalid_date() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_bot_analytics)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/analytics?from=yesterday")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
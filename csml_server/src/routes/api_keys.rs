@@ -0,0 +1,79 @@
+use actix_web::{post, delete, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyBody {
+  scopes: Vec<String>,
+  bot_ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyIdPath {
+  id: String
+}
+
+/**
+ * Create an API key. The key is only returned once: only its hash is stored.
+ *
+ * {"statusCode": 201,"body": {"id": String, "key": String, "scopes": [String], "bot_ids": Option<[String]>}}
+ */
+#[post("/api_keys")]
+pub async fn create_api_key(body: web::Json<CreateApiKeyBody>) -> HttpResponse {
+  let body = body.into_inner();
+
+  let res = thread::spawn(move || {
+    csml_engine::create_api_key(body.scopes, body.bot_ids)
+  }).join().unwrap();
+
+  match res {
+    Ok(data) => HttpResponse::Created().json(data),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Revoke an API key
+ */
+#[delete("/api_keys/{id}")]
+pub async fn delete_api_key(path: web::Path<ApiKeyIdPath>) -> HttpResponse {
+  let id = path.id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::delete_api_key(&id)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::Ok().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_create_api_key_bad_scope() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(create_api_key)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/api_keys")
+                    .set_json(&serde_json::json!({"scopes": ["superuser"]}))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
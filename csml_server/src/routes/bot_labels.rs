@@ -0,0 +1,251 @@
+use actix_web::{delete, get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotLabelPath {
+  bot_id: String,
+  label: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PromoteBody {
+  version_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RollbackBody {
+  version_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffQuery {
+  from: String,
+  to: String,
+}
+
+/**
+ * List the labels of a bot
+ *
+ * {"statusCode": 200,"body": {"labels": [BotLabel]}}
+ *
+ * BotLabel = {
+ *  "label": String,
+ *  "version_id": String,
+ *  "history": [String],
+ *  "updated_at": String
+ * }
+ */
+#[get("/bots/{bot_id}/labels")]
+pub async fn get_bot_labels(path: web::Path<BotIdPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_bot_labels(&bot_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(labels) => HttpResponse::Ok().json(serde_json::json!({ "labels": labels })),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Get a label of a bot
+ *
+ * {"statusCode": 200,"body": BotLabel}
+ */
+#[get("/bots/{bot_id}/labels/{label}")]
+pub async fn get_bot_label(path: web::Path<BotLabelPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let label = path.label.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_bot_label(&bot_id, &label)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(label)) => HttpResponse::Ok().json(label),
+    Ok(None) => ApiError::not_found("Bot label not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Make a label point to a given version of a bot, creating the label if needed
+ *
+ * {"version_id": String}
+ */
+#[post("/bots/{bot_id}/labels/{label}/promote")]
+pub async fn promote_bot_version(
+  path: web::Path<BotLabelPath>,
+  body: web::Json<PromoteBody>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let label = path.label.to_owned();
+  let version_id = body.version_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::promote_bot_version(&bot_id, &label, &version_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(label) => HttpResponse::Ok().json(label),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Make a label point back to the version it pointed to before,
+ * or to the given version
+ *
+ * Option<{"version_id": Option<String>}>
+ */
+#[post("/bots/{bot_id}/labels/{label}/rollback")]
+pub async fn rollback_bot_label(
+  path: web::Path<BotLabelPath>,
+  body: Option<web::Json<RollbackBody>>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let label = path.label.to_owned();
+  let version_id = body.and_then(|body| body.version_id.to_owned());
+
+  let res = thread::spawn(move || {
+    csml_engine::rollback_bot_label(&bot_id, &label, version_id.as_deref())
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(label)) => HttpResponse::Ok().json(label),
+    Ok(None) => ApiError::not_found("Bot label not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Delete a label of a bot. The version it points to is not deleted.
+ */
+#[delete("/bots/{bot_id}/labels/{label}")]
+pub async fn delete_bot_label(path: web::Path<BotLabelPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let label = path.label.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::delete_bot_label(&bot_id, &label)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::NoContent().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Compare the flows of two versions of a bot, given by their version_id
+ *
+ * {"statusCode": 200,"body": {
+ *  "from_version_id": String,
+ *  "to_version_id": String,
+ *  "default_flow": Option<{"from": String, "to": String}>,
+ *  "flows": [{
+ *    "name": String,
+ *    "status": "added" | "removed" | "modified",
+ *    "steps": {"added": [String], "removed": [String]},
+ *    "commands": {"added": [String], "removed": [String]},
+ *    "diff": String
+ *  }],
+ *  "unchanged_flows": [String]
+ * }}
+ */
+#[get("/bots/{bot_id}/diff")]
+pub async fn diff_bot_versions(
+  path: web::Path<BotIdPath>,
+  query: web::Query<DiffQuery>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let from = query.from.to_owned();
+  let to = query.to.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::diff_bot_versions(&bot_id, &from, &to)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(diff)) => HttpResponse::Ok().json(diff),
+    Ok(None) => ApiError::not_found("Bot version not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_get_bot_labels() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_bot_labels)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/labels")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_promote_invalid_label() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(promote_bot_version)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/labels/not%20a%20label/promote")
+                    .set_json(&serde_json::json!({"version_id": "version"}))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_diff_without_versions() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(diff_bot_versions)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/diff?from=version")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
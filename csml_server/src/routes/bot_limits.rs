@@ -0,0 +1,119 @@
+use actix_web::{delete, get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use crate::rate_limit::RateLimiter;
+use csml_engine::data::BotLimits;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String,
+}
+
+/**
+ * Set the rate limits and daily quota of a bot, replacing the previous ones.
+ * They are only used by servers started with ENGINE_SERVER_RATE_LIMITS_DB=true.
+ *
+ * {
+ *  "bot": Option<{"burst": f64, "per_second": f64}>,
+ *  "channel": Option<{"burst": f64, "per_second": f64}>,
+ *  "user": Option<{"burst": f64, "per_second": f64}>,
+ *  "daily_interactions": Option<u64>
+ * }
+ */
+#[post("/bots/{bot_id}/limits")]
+pub async fn set_bot_limits(
+  path: web::Path<BotIdPath>,
+  body: web::Json<BotLimits>,
+  limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let limits = body.into_inner();
+
+  let res = thread::spawn(move || {
+    csml_engine::set_bot_limits(&bot_id, limits)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => {
+      limiter.forget_bot(&path.bot_id);
+      HttpResponse::Ok().finish()
+    }
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Get the rate limits and daily quota set for a bot
+ */
+#[get("/bots/{bot_id}/limits")]
+pub async fn get_bot_limits(path: web::Path<BotIdPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_bot_limits(&bot_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(limits)) => HttpResponse::Ok().json(limits),
+    Ok(None) => ApiError::not_found("no limits are set for this bot").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Remove the rate limits and daily quota of a bot: the default limits apply again
+ */
+#[delete("/bots/{bot_id}/limits")]
+pub async fn delete_bot_limits(
+  path: web::Path<BotIdPath>,
+  limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::delete_bot_limits(&bot_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => {
+      limiter.forget_bot(&path.bot_id);
+      HttpResponse::NoContent().finish()
+    }
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_set_bot_limits_bad_limits() {
+        let mut app = test::init_service(
+            App::new()
+                    .app_data(web::Data::new(RateLimiter::default()))
+                    .service(set_bot_limits)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/limits")
+                    .set_json(&serde_json::json!({
+                        "user": {"burst": 0, "per_second": 1}
+                    }))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
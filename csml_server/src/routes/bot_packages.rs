@@ -0,0 +1,133 @@
+use actix_web::{get, post, web, HttpRequest, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotVersionPath {
+  bot_id: String,
+  version_id: String,
+}
+
+/**
+ * Export a version of a bot as a package (.tar.gz) with a manifest, a .csml file for each flow,
+ * a JSON file for each custom component and the JSON schema of the bot's env.
+ * The values of the env are not exported.
+ */
+#[get("/bots/{bot_id}/versions/{version_id}/export")]
+pub async fn export_bot_version(path: web::Path<BotVersionPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let version_id = path.version_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::export_bot_version(&bot_id, &version_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(package)) => HttpResponse::Ok()
+      .content_type("application/gzip")
+      .header(
+        "Content-Disposition",
+        format!("attachment; filename=\"{}.tar.gz\"", path.version_id),
+      )
+      .body(package),
+    Ok(None) => ApiError::not_found("Bot version not found").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Import a package (.tar.gz) as a new version of a bot. The body is the package.
+ * If the package has an env schema, the bot's env must be given as JSON in the X-Bot-Env header,
+ * so that its secret values are not written in the access logs with the query.
+ *
+ * {"statusCode": 201,"body": {"version_id": String, "engine_version": String}}
+ */
+#[post("/bots/{bot_id}/import")]
+pub async fn import_bot_package(
+  req: HttpRequest,
+  path: web::Path<BotIdPath>,
+  body: web::Bytes,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let env = match req.headers().get("x-bot-env").map(|env| env.as_bytes()) {
+    Some(env) => match serde_json::from_slice::<Value>(env) {
+      Ok(env) => Some(env),
+      Err(_) => return ApiError::bad_request("env is not valid JSON").error_response(),
+    },
+    None => None,
+  };
+
+  let res = thread::spawn(move || {
+    csml_engine::import_bot_package(&bot_id, &body, env)
+  }).join().unwrap();
+
+  match res {
+    Ok(data) => HttpResponse::Created().json(data),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_export_bot_version() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(export_bot_version)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/bots/botid/versions/unknown/export")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn test_import_invalid_package() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(import_bot_package)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/import")
+                    .set_payload("not a package")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_import_invalid_env() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(import_bot_package)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/import")
+                    .header("X-Bot-Env", "{not json")
+                    .set_payload("not a package")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,4 +1,5 @@
-use actix_web::{post, get, delete, web, HttpResponse};
+use actix_web::{post, get, delete, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_engine::{
   create_bot_version, get_bot_by_version_id, get_bot_versions, get_last_bot_version,
   delete_all_bot_versions, delete_bot_version_id
@@ -25,7 +26,7 @@ pub async fn add_bot_version(body: web::Json<CsmlBot>) -> HttpResponse {
     Ok(data) => HttpResponse::Created().json(serde_json::json!(data)),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -66,10 +67,10 @@ pub async fn get_bot_latest_version(path: web::Path<BotIdPath>) -> HttpResponse
 
   match res {
     Ok(Some(bot_version)) => HttpResponse::Ok().json(bot_version.flatten()),
-    Ok(None) => HttpResponse::NotFound().finish(),
+    Ok(None) => ApiError::not_found("Bot version not found").error_response(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -93,7 +94,7 @@ pub async fn delete_bot_versions(
     Ok(_) => HttpResponse::NoContent().finish(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -132,7 +133,7 @@ pub async fn get_bot_latest_versions(path: web::Path<BotIdPath>, query: web::Que
     Ok(data) => HttpResponse::Ok().json(data),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -171,10 +172,10 @@ pub async fn get_bot_version(
 
   match res {
     Ok(Some(bot_version)) => HttpResponse::Ok().json(bot_version.flatten()),
-    Ok(None) => HttpResponse::NotFound().finish(),
+    Ok(None) => ApiError::not_found("Bot version not found").error_response(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -199,7 +200,7 @@ pub async fn delete_bot_version(
     Ok(_) => HttpResponse::NoContent().finish(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
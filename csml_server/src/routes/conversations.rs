@@ -1,4 +1,5 @@
-use actix_web::{get, post, web, HttpResponse};
+use actix_web::{get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_engine::{user_close_all_conversations, get_open_conversation, Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -19,7 +20,7 @@ pub async fn get_open(body: web::Json<Client>) -> HttpResponse {
     Ok(None) => HttpResponse::Ok().finish(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 
@@ -39,7 +40,7 @@ pub async fn close_user_conversations(body: web::Json<Client>) -> HttpResponse {
     Ok(()) => HttpResponse::Ok().finish(),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -80,7 +81,7 @@ pub async fn get_client_conversations(query: web::Query<GetClientInfoQuery>) ->
     Ok(data) => HttpResponse::Ok().json(data),
     Err(err) => {
     eprintln!("EngineError: {:?}", err);
-    HttpResponse::InternalServerError().finish()
+    ApiError::from(err).error_response()
     }
   }
 }
@@ -1,4 +1,5 @@
-use actix_web::{delete, web, HttpResponse};
+use actix_web::{delete, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_interpreter::data::{Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -21,7 +22,7 @@ pub struct BotIdPath {
 * {"statusCode": 204}
 *
 */
-#[delete("/data/clients)")]
+#[delete("/data/clients")]
 pub async fn delete_client(query: web::Query<ClientQuery>) -> HttpResponse {
     let client = Client {
         user_id: query.user_id.clone(),
@@ -37,7 +38,7 @@ pub async fn delete_client(query: web::Query<ClientQuery>) -> HttpResponse {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -59,7 +60,7 @@ pub async fn delete_bot(path: web::Path<BotIdPath>,) -> HttpResponse {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
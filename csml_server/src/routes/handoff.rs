@@ -0,0 +1,134 @@
+use actix_web::{get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use csml_engine::Client;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClientQuery {
+  pub bot_id: String,
+  pub channel_id: String,
+  pub user_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartHandoffBody {
+  client: Client,
+  reason: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EndHandoffBody {
+  client: Client,
+  flow: Option<String>,
+  step: Option<String>,
+}
+
+/**
+ * Get the current handoff of a client, if any
+ */
+#[get("/handoff")]
+pub async fn get_handoff(query: web::Query<ClientQuery>) -> HttpResponse {
+  let client = Client {
+    bot_id: query.bot_id.to_owned(),
+    channel_id: query.channel_id.to_owned(),
+    user_id: query.user_id.to_owned()
+  };
+
+  let res = thread::spawn(move || {
+    csml_engine::get_client_handoff(&client)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(handoff)) => HttpResponse::Ok().json(handoff),
+    Ok(None) => HttpResponse::Ok().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Pause the bot and hand the open conversation of a client off to a human agent
+ */
+#[post("/handoff/start")]
+pub async fn start_handoff(body: web::Json<StartHandoffBody>) -> HttpResponse {
+  let reason = body.reason.to_owned().unwrap_or(serde_json::Value::Null);
+
+  let res = thread::spawn(move || {
+    csml_engine::start_client_handoff(&body.client, reason)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::Ok().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * End the handoff of a client, optionally resuming the conversation at a given flow/step
+ */
+#[post("/handoff/end")]
+pub async fn end_handoff(body: web::Json<EndHandoffBody>) -> HttpResponse {
+  let res = thread::spawn(move || {
+    csml_engine::end_client_handoff(&body.client, body.flow.to_owned(), body.step.to_owned())
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::Ok().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_get_handoff() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_handoff)
+        ).await;
+
+        let (user_id, channel_id, bot_id) = ("test", "handoff-channel", "botid");
+
+        let resp = test::TestRequest::get()
+                    .uri(&format!("/handoff?user_id={}&channel_id={}&bot_id={}", user_id, channel_id, bot_id))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_end_handoff() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(end_handoff)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/handoff/end")
+                    .set_json(&serde_json::json!({
+                      "client": {
+                        "user_id": "test",
+                        "channel_id": "handoff-channel",
+                        "bot_id": "botid"
+                      },
+                      "flow": "Default",
+                      "step": "start"
+                    }))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,87 @@
+use actix_web::{get, HttpResponse};
+use crate::error::ApiError;
+use serde_json::json;
+use std::thread;
+
+/**
+ * The server is up
+ */
+#[get("/healthz")]
+pub async fn healthz() -> HttpResponse {
+  HttpResponse::Ok().json(json!({"status": "ok"}))
+}
+
+/**
+ * The server is ready to handle requests: the database can be reached
+ */
+#[get("/readyz")]
+pub async fn readyz() -> HttpResponse {
+  let res = thread::spawn(move || {
+    csml_engine::check_db()
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::Ok().json(json!({"status": "ok"})),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      let err = ApiError::from(err);
+
+      HttpResponse::ServiceUnavailable().json(err.body())
+    }
+  }
+}
+
+/**
+ * Metrics of the server and of the engine, in the Prometheus text format
+ */
+#[get("/metrics")]
+pub async fn metrics() -> HttpResponse {
+  HttpResponse::Ok()
+    .content_type("text/plain; version=0.0.4")
+    .body(csml_interpreter::metrics::render())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_healthz() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(healthz)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/healthz")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn test_metrics() {
+        let mut app = test::init_service(
+            App::new()
+                    .wrap(crate::metrics::RequestMetrics)
+                    .service(healthz)
+                    .service(metrics)
+        ).await;
+
+        test::TestRequest::get()
+                    .uri("/healthz")
+                    .send_request(&mut app).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/metrics")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = test::read_body(resp).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("csml_server_requests_total{route=\"/healthz\",method=\"GET\",status=\"200\"}"));
+    }
+}
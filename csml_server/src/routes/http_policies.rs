@@ -0,0 +1,108 @@
+use actix_web::{delete, get, post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use csml_engine::HttpPolicy;
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotIdPath {
+  bot_id: String,
+}
+
+/**
+ * Set the outbound HTTP policy of a bot, replacing the previous one.
+ * It is only used by engines started with ENGINE_HTTP_POLICY_DB=true.
+ *
+ * {
+ *  "allow_hosts": Option<[String]>, // "api.example.com" or "*.example.com"
+ *  "deny_hosts": [String],
+ *  "allow_private_ips": bool,
+ *  "max_response_bytes": Option<u64>,
+ *  "max_requests": Option<u64> // per interaction
+ * }
+ */
+#[post("/bots/{bot_id}/http_policy")]
+pub async fn set_http_policy(
+  path: web::Path<BotIdPath>,
+  body: web::Json<HttpPolicy>,
+) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+  let policy = body.into_inner();
+
+  let res = thread::spawn(move || {
+    csml_engine::set_http_policy(&bot_id, policy)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::Ok().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Get the outbound HTTP policy set for a bot
+ */
+#[get("/bots/{bot_id}/http_policy")]
+pub async fn get_http_policy(path: web::Path<BotIdPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::get_http_policy(&bot_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(Some(policy)) => HttpResponse::Ok().json(policy),
+    Ok(None) => ApiError::not_found("no HTTP policy is set for this bot").error_response(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+/**
+ * Remove the outbound HTTP policy of a bot: the default policy applies again
+ */
+#[delete("/bots/{bot_id}/http_policy")]
+pub async fn delete_http_policy(path: web::Path<BotIdPath>) -> HttpResponse {
+  let bot_id = path.bot_id.to_owned();
+
+  let res = thread::spawn(move || {
+    csml_engine::delete_http_policy(&bot_id)
+  }).join().unwrap();
+
+  match res {
+    Ok(()) => HttpResponse::NoContent().finish(),
+    Err(err) => {
+      eprintln!("EngineError: {:?}", err);
+      ApiError::from(err).error_response()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_set_http_policy_bad_host() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(set_http_policy)
+        ).await;
+
+        let resp = test::TestRequest::post()
+                    .uri("/bots/botid/http_policy")
+                    .set_json(&serde_json::json!({
+                        "allow_hosts": ["https://api.example.com/"]
+                    }))
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
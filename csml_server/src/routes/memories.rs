@@ -1,4 +1,5 @@
-use actix_web::{post, delete, get, web, HttpResponse};
+use actix_web::{post, delete, get, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_interpreter::data::{Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -44,7 +45,7 @@ pub async fn create_client_memory(query: web::Query<ClientQuery>, body: web::Jso
         Ok(_) => HttpResponse::Created().finish(),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -73,7 +74,7 @@ pub async fn delete_memory(path: web::Path<MemoryKeyPath>, query: web::Query<Cli
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -100,7 +101,7 @@ pub async fn delete_memories( query: web::Query<ClientQuery>) -> HttpResponse {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -127,7 +128,7 @@ pub async fn get_memory(path: web::Path<MemoryKeyPath>, query: web::Query<Client
         Ok(memory) => HttpResponse::Ok().json(memory),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -152,7 +153,7 @@ pub async fn get_memories(query: web::Query<ClientQuery>) -> HttpResponse {
     Ok(memory) => HttpResponse::Ok().json(memory),
         Err(err) => {
             eprintln!("EngineError: {:?}", err);
-            HttpResponse::InternalServerError().finish()
+            ApiError::from(err).error_response()
         }
     }
 }
@@ -1,4 +1,5 @@
-use actix_web::{get, web, HttpResponse};
+use actix_web::{get, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_interpreter::data::{Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -44,7 +45,7 @@ pub async fn get_client_messages(query: web::Query<GetClientInfoQuery>) -> HttpR
         Ok(data) => HttpResponse::Ok().json(data),
         Err(err) => {
         eprintln!("EngineError: {:?}", err);
-        HttpResponse::InternalServerError().finish()
+        ApiError::from(err).error_response()
         }
    }
 }
@@ -0,0 +1,31 @@
+use actix_web::{get, HttpResponse};
+use crate::openapi::get_openapi_spec;
+
+/**
+ * OpenAPI 3 description of every route of the server
+ */
+#[get("/openapi.json")]
+pub async fn get_openapi() -> HttpResponse {
+  HttpResponse::Ok().json(get_openapi_spec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_get_openapi() {
+        let mut app = test::init_service(
+            App::new()
+                    .service(get_openapi)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/openapi.json")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
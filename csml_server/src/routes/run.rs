@@ -1,21 +1,27 @@
-use actix_web::{post, web, HttpResponse};
+use actix_web::{post, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
+use crate::rate_limit::RateLimiter;
 use csml_engine::{start_conversation};
 use csml_engine::data::{RunRequest};
 use serde_json::{Value, json};
 use std::thread;
 
 #[post("/run")]
-pub async fn handler(body: web::Json<RunRequest>) -> HttpResponse {
+pub async fn handler(body: web::Json<RunRequest>, limiter: web::Data<RateLimiter>) -> HttpResponse {
   let mut request = body.event.to_owned();
 
   let bot_opt = match body.get_bot_opt() {
     Ok(bot_opt) => bot_opt,
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      return HttpResponse::BadRequest().finish()
+      return ApiError::from(err).error_response()
     }
   };
 
+  if let Err(err) = limiter.check(&request.client).await {
+    return err.error_response();
+  }
+
   // request metadata should be an empty object by default
   request.metadata = match request.metadata {
     Value::Null => json!({}),
@@ -30,7 +36,7 @@ pub async fn handler(body: web::Json<RunRequest>) -> HttpResponse {
     Ok(data) => HttpResponse::Ok().json(data),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 }
@@ -45,6 +51,7 @@ mod tests {
     async fn test_run() {
         let mut app = test::init_service(
             App::new()
+                    .app_data(web::Data::new(RateLimiter::default()))
                     .service(handler)
         ).await;
 
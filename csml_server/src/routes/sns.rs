@@ -1,4 +1,7 @@
-use actix_web::{post, web, HttpResponse, HttpRequest, client};
+use actix_web::{post, web, HttpResponse, ResponseError, HttpRequest, client};
+use actix_web::http::StatusCode;
+use crate::error::ApiError;
+use crate::rate_limit::RateLimiter;
 use csml_engine::{start_conversation};
 use csml_engine::data::{RunRequest};
 use serde::{Deserialize, Serialize};
@@ -15,7 +18,7 @@ async fn confirm_subscription(body: &str) -> HttpResponse {
 
   let val: SnsConfirmationRequest = match serde_json::from_str(body) {
     Ok(res) => res,
-    Err(_) => return HttpResponse::BadRequest().body("Request body can not be properly parsed"),
+    Err(_) => return ApiError::bad_request("Request body can not be properly parsed").error_response(),
   };
 
   println!("SNS SubscribeURL: {}", val.subscribe_url);
@@ -26,7 +29,7 @@ async fn confirm_subscription(body: &str) -> HttpResponse {
     .send()
     .await {
       Ok(_) => HttpResponse::Ok().finish(),
-      Err(_) => HttpResponse::BadGateway().body("Impossible to reach SubscribeURL"),
+      Err(_) => ApiError::new(StatusCode::BAD_GATEWAY, "bad_gateway", "Impossible to reach SubscribeURL").error_response(),
   }
 
 }
@@ -37,7 +40,7 @@ struct SnsMessage {
   message: String,
 }
 
-async fn handle_notification(body: &str) -> HttpResponse {
+async fn handle_notification(body: &str, limiter: &RateLimiter) -> HttpResponse {
 
   // All requests with an invalid should return a 200 code,
   // as we don't want the SNS event to be retried (same result).
@@ -69,6 +72,11 @@ async fn handle_notification(body: &str) -> HttpResponse {
   };
   let mut event = csml_request.event.to_owned();
 
+  // rejected messages are retried by SNS according to its delivery policy
+  if let Err(err) = limiter.check(&event.client).await {
+    return err.error_response();
+  }
+
   // event metadata should be an empty object by default
   event.metadata = match event.metadata {
     Value::Null => json!({}),
@@ -83,7 +91,7 @@ async fn handle_notification(body: &str) -> HttpResponse {
     Ok(data) => HttpResponse::Ok().json(data),
     Err(err) => {
       eprintln!("EngineError: {:?}", err);
-      HttpResponse::InternalServerError().finish()
+      ApiError::from(err).error_response()
     }
   }
 
@@ -97,11 +105,11 @@ async fn handle_notification(body: &str) -> HttpResponse {
  * has been properly confirmed.
  */
 #[post("/sns")]
-pub async fn handler(req: HttpRequest, body: web::Bytes) -> HttpResponse {
+pub async fn handler(req: HttpRequest, body: web::Bytes, limiter: web::Data<RateLimiter>) -> HttpResponse {
 
   let body_string = match std::str::from_utf8(&body) {
     Ok(res) => res,
-    Err(_) => return HttpResponse::BadRequest().body("Request body can not be properly parsed"),
+    Err(_) => return ApiError::bad_request("Request body can not be properly parsed").error_response(),
   };
 
   // See AWS SNS docs for specification of how this endpoint is called for http/https notification event types:
@@ -113,11 +121,11 @@ pub async fn handler(req: HttpRequest, body: web::Bytes) -> HttpResponse {
       return confirm_subscription(&body_string).await;
     }
     if val == "Notification" {
-      return handle_notification(&body_string).await;
+      return handle_notification(&body_string, &limiter).await;
     }
   };
 
   // other scenarios inclure unsubscribe requests and invalid/non-SNS requests
-  return HttpResponse::BadRequest().finish();
+  return ApiError::bad_request("Not a valid SNS request").error_response();
 
 }
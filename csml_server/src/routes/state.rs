@@ -1,4 +1,5 @@
-use actix_web::{get, web, HttpResponse};
+use actix_web::{get, web, HttpResponse, ResponseError};
+use crate::error::ApiError;
 use csml_engine::{Client};
 use serde::{Deserialize, Serialize};
 use std::thread;
@@ -28,7 +29,7 @@ pub async fn get_client_current_state(query: web::Query<ClientQuery>) -> HttpRes
     Ok(data) => HttpResponse::Ok().json(data),
     Err(err) => {
         eprintln!("EngineError: {:?}", err);
-        HttpResponse::InternalServerError().finish()
+        ApiError::from(err).error_response()
     }
   }
 }
@@ -0,0 +1,361 @@
+use actix_codec::{Decoder, Encoder};
+use actix_http::ws;
+use actix_web::{get, http::StatusCode, web, Error, HttpRequest, HttpResponse, ResponseError};
+use bytes::{Bytes, BytesMut};
+use crate::error::ApiError;
+use crate::rate_limit::RateLimiter;
+use csml_engine::data::{CsmlRequest, RunRequest};
+use csml_interpreter::data::Client;
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{sync::mpsc, thread};
+
+/**
+ * Run a request and send a frame for each message as soon as it is processed:
+ *
+ * {"type": "message", "request_id": String, "message": Message}
+ *
+ * followed by a last frame once the request is fully processed:
+ *
+ * {"type": "end", "request_id": String, "conversation_end": bool, "interaction_id": String}
+ * or
+ * {"type": "error", "request_id": String, "error": {"code": String, "message": String, "details": Value}}
+ */
+fn run_stream(run_request: RunRequest, frames: UnboundedSender<Value>) {
+  thread::spawn(move || {
+    let request_id = run_request.event.request_id.to_owned();
+    let send_error = |err: ApiError| {
+      frames.unbounded_send(json!({
+        "type": "error",
+        "request_id": request_id,
+        "error": err.body(),
+      })).ok();
+    };
+
+    let bot_opt = match run_request.get_bot_opt() {
+      Ok(bot_opt) => bot_opt,
+      Err(err) => return send_error(ApiError::from(err)),
+    };
+
+    let mut request = run_request.event;
+    // request metadata should be an empty object by default
+    request.metadata = match request.metadata {
+      Value::Null => json!({}),
+      val => val,
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let engine = thread::spawn(move || {
+      csml_engine::start_conversation_stream(request, bot_opt, sender)
+    });
+
+    let send_message = |message: &Value| {
+      frames.unbounded_send(json!({
+        "type": "message",
+        "request_id": request_id,
+        "message": message,
+      })).ok();
+    };
+
+    // the channel is closed by the engine once the request is processed
+    let mut streamed = 0;
+    for data in receiver {
+      for message in data["messages"].as_array().into_iter().flatten() {
+        send_message(message);
+        streamed += 1;
+      }
+    }
+
+    match engine.join() {
+      Ok(Ok(data)) => {
+        // cached or handed off requests are not streamed
+        let messages = data.get("messages").and_then(|messages| messages.as_array());
+        for message in messages.into_iter().flatten().skip(streamed) {
+          send_message(message);
+        }
+
+        frames.unbounded_send(json!({
+          "type": "end",
+          "request_id": request_id,
+          "conversation_end": data.get("conversation_end"),
+          "interaction_id": data.get("interaction_id"),
+        })).ok();
+      }
+      Ok(Err(err)) => {
+        eprintln!("EngineError: {:?}", err);
+        send_error(ApiError::from(err))
+      }
+      // the engine panicked: end the stream instead of the server thread
+      Err(_) => send_error(ApiError::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "engine_error",
+        "the request could not be processed by the engine",
+      )),
+    }
+  });
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StreamQuery {
+  bot_id: String,
+  version_id: Option<String>,
+  label: Option<String>,
+  fn_endpoint: Option<String>,
+  channel_id: String,
+  user_id: String,
+  request_id: String,
+  payload: String,
+  metadata: Option<String>,
+}
+
+/**
+ * Same as POST /run, as Server-Sent Events.
+ * The bot is given by bot_id (and optionally version_id or label), the event by its client,
+ * request_id and JSON-encoded payload and metadata.
+ *
+ * event: message
+ * data: {"type": "message", "request_id": String, "message": Message}
+ *
+ * event: end
+ * data: {"type": "end", "request_id": String, "conversation_end": bool, "interaction_id": String}
+ */
+#[get("/run/stream")]
+pub async fn run_sse(query: web::Query<StreamQuery>, limiter: web::Data<RateLimiter>) -> HttpResponse {
+  let query = query.into_inner();
+
+  let parse = |name: &str, value: &str| match serde_json::from_str::<Value>(value) {
+    Ok(value) => Ok(value),
+    Err(_) => Err(ApiError::bad_request(&format!("{} is not valid JSON", name)).error_response()),
+  };
+
+  let payload = match parse("payload", &query.payload) {
+    Ok(payload) => payload,
+    Err(res) => return res,
+  };
+  let metadata = match query.metadata.as_deref().map(|metadata| parse("metadata", metadata)) {
+    Some(Ok(metadata)) => metadata,
+    Some(Err(res)) => return res,
+    None => Value::Null,
+  };
+
+  let run_request = RunRequest {
+    bot: None,
+    bot_id: Some(query.bot_id.to_owned()),
+    version_id: query.version_id,
+    label: query.label,
+    split: None,
+    fn_endpoint: query.fn_endpoint,
+    event: CsmlRequest {
+      request_id: query.request_id,
+      client: Client {
+        bot_id: query.bot_id,
+        channel_id: query.channel_id,
+        user_id: query.user_id,
+      },
+      callback_url: None,
+      payload,
+      metadata,
+    },
+  };
+
+  if let Err(err) = limiter.check(&run_request.event.client).await {
+    return err.error_response();
+  }
+
+  let (frames, receiver) = unbounded();
+  run_stream(run_request, frames);
+
+  let events = receiver.map(|frame| {
+    let event = format!("event: {}\ndata: {}\n\n", frame["type"].as_str().unwrap_or_default(), frame);
+
+    Ok::<Bytes, Error>(Bytes::from(event))
+  });
+
+  HttpResponse::Ok()
+    .content_type("text/event-stream")
+    .header("Cache-Control", "no-cache")
+    .streaming(events)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WsQuery {
+  bot_id: Option<String>,
+}
+
+/**
+ * A connection is restricted to the bot given at the handshake, if any
+ */
+fn is_same_bot(run_request: &RunRequest, bot_id: &Option<String>) -> bool {
+  let bot_id = match bot_id {
+    Some(bot_id) => bot_id,
+    None => return true,
+  };
+
+  let request_bot_id = match (&run_request.bot, &run_request.bot_id) {
+    (Some(bot), _) => &bot.id,
+    (None, Some(request_bot_id)) => request_bot_id,
+    (None, None) => return false,
+  };
+
+  request_bot_id == bot_id && &run_request.event.client.bot_id == bot_id
+}
+
+/**
+ * Read the frames sent by the client. Each text frame is a POST /run body,
+ * and the frames of each request are sent back as text frames (see `run_stream`).
+ */
+async fn read_ws_frames(
+  mut payload: web::Payload,
+  bot_id: Option<String>,
+  limiter: web::Data<RateLimiter>,
+  messages: UnboundedSender<ws::Message>,
+) {
+  let mut codec = ws::Codec::new();
+  let mut buffer = BytesMut::new();
+
+  let (frames, receiver) = unbounded::<Value>();
+  let text_messages = messages.clone();
+  actix_rt::spawn(async move {
+    let mut receiver = receiver;
+    while let Some(frame) = receiver.next().await {
+      text_messages.unbounded_send(ws::Message::Text(frame.to_string())).ok();
+    }
+  });
+
+  while let Some(chunk) = payload.next().await {
+    match chunk {
+      Ok(chunk) => buffer.extend_from_slice(&chunk),
+      Err(_) => return,
+    };
+
+    loop {
+      let frame = match codec.decode(&mut buffer) {
+        Ok(Some(frame)) => frame,
+        Ok(None) => break,
+        Err(_) => {
+          messages.unbounded_send(ws::Message::Close(Some(ws::CloseCode::Protocol.into()))).ok();
+          return;
+        }
+      };
+
+      match frame {
+        ws::Frame::Text(text) => match serde_json::from_slice::<RunRequest>(&text) {
+          Ok(run_request) if is_same_bot(&run_request, &bot_id) => match limiter.check(&run_request.event.client).await {
+            Ok(()) => run_stream(run_request, frames.clone()),
+            Err(err) => {
+              frames.unbounded_send(json!({
+                "type": "error",
+                "request_id": run_request.event.request_id,
+                "error": err.body(),
+              })).ok();
+            }
+          },
+          Ok(run_request) => {
+            let err = ApiError::new(
+              actix_web::http::StatusCode::FORBIDDEN,
+              "forbidden",
+              "this connection is not allowed to access this bot",
+            );
+            frames.unbounded_send(json!({
+              "type": "error",
+              "request_id": run_request.event.request_id,
+              "error": err.body(),
+            })).ok();
+          }
+          Err(err) => {
+            let err = ApiError::bad_request(&err.to_string());
+            frames.unbounded_send(json!({"type": "error", "request_id": null, "error": err.body()})).ok();
+          }
+        },
+        ws::Frame::Ping(ping) => {
+          messages.unbounded_send(ws::Message::Pong(ping)).ok();
+        }
+        ws::Frame::Close(reason) => {
+          messages.unbounded_send(ws::Message::Close(reason)).ok();
+          return;
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+/**
+ * Same as POST /run, over a WebSocket. Each text frame sent by the client must be a
+ * POST /run body; see `run_stream` for the frames sent back by the server.
+ * If a bot_id is given at the handshake, the connection is restricted to this bot.
+ */
+#[get("/ws")]
+pub async fn run_ws(
+  req: HttpRequest,
+  query: web::Query<WsQuery>,
+  payload: web::Payload,
+  limiter: web::Data<RateLimiter>,
+) -> HttpResponse {
+  let mut res = match ws::handshake(req.head()) {
+    Ok(res) => res,
+    Err(err) => return err.error_response(),
+  };
+
+  let (messages, receiver) = unbounded::<ws::Message>();
+  actix_rt::spawn(read_ws_frames(payload, query.into_inner().bot_id, limiter, messages));
+
+  let mut codec = ws::Codec::new();
+  let frames = receiver.map(move |message| {
+    let mut buffer = BytesMut::new();
+    codec.encode(message, &mut buffer)?;
+
+    Ok::<Bytes, Error>(buffer.freeze())
+  });
+
+  res.streaming(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use actix_web::http::{StatusCode};
+
+    #[actix_rt::test]
+    async fn test_run_sse_bad_payload() {
+        let mut app = test::init_service(
+            App::new()
+                    .app_data(web::Data::new(RateLimiter::default()))
+                    .service(run_sse)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/run/stream?bot_id=botid&channel_id=channel_id&user_id=user_id&request_id=request_id&payload=toto")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn test_run_ws_handshake() {
+        let mut app = test::init_service(
+            App::new()
+                    .app_data(web::Data::new(RateLimiter::default()))
+                    .service(run_ws)
+        ).await;
+
+        let resp = test::TestRequest::get()
+                    .uri("/ws")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = test::TestRequest::get()
+                    .uri("/ws")
+                    .header("Upgrade", "websocket")
+                    .header("Connection", "Upgrade")
+                    .header("Sec-WebSocket-Version", "13")
+                    .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+                    .send_request(&mut app).await;
+
+        assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+    }
+}
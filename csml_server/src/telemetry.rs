@@ -0,0 +1,93 @@
+/**
+ * The spans and events of the server, the engine and the interpreter
+ * (request → interaction → step → builtin / database call) are written to stdout.
+ * The filter is given by RUST_LOG; by default, every crate logs at the info level,
+ * or at the debug level (with the duration of each span) if DEBUG=true.
+ *
+ * When built with the `otlp` feature, the spans are also exported with OTLP (gRPC)
+ * if OTEL_EXPORTER_OTLP_ENDPOINT is set.
+ */
+use tracing::Subscriber;
+use tracing_subscriber::{
+  fmt::format::FmtSpan, layer::SubscriberExt, registry::LookupSpan, util::SubscriberInitExt,
+  EnvFilter, Layer, Registry,
+};
+
+fn is_debug() -> bool {
+  matches!(std::env::var("DEBUG"), Ok(var) if var == "true")
+}
+
+fn get_filter() -> EnvFilter {
+  if let Ok(filter) = EnvFilter::try_from_default_env() {
+    return filter;
+  }
+
+  let level = if is_debug() { "debug" } else { "info" };
+  EnvFilter::new(format!(
+    "actix_web=info,csml_server={0},csml_engine={0},csml_interpreter={0}",
+    level
+  ))
+}
+
+fn get_fmt_layer<S>() -> impl Layer<S>
+where
+  S: Subscriber + for<'span> LookupSpan<'span>,
+{
+  let span_events = if is_debug() { FmtSpan::CLOSE } else { FmtSpan::NONE };
+
+  tracing_subscriber::fmt::layer().with_span_events(span_events)
+}
+
+#[cfg(feature = "otlp")]
+fn get_otlp_layer() -> Result<Option<tracing_opentelemetry::OpenTelemetryLayer<Registry, opentelemetry::sdk::trace::Tracer>>, String> {
+  use opentelemetry::{sdk::{trace, Resource}, KeyValue};
+
+  if std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT").is_err() {
+    return Ok(None);
+  }
+
+  let service_name = std::env::var("OTEL_SERVICE_NAME").unwrap_or_else(|_| "csml_server".to_owned());
+
+  // the batch exporter runs on its own runtime, next to the actix one,
+  // which must live as long as the server
+  let runtime = tokio::runtime::Runtime::new().map_err(|err| err.to_string())?;
+  let runtime: &'static tokio::runtime::Runtime = Box::leak(Box::new(runtime));
+  let _guard = runtime.enter();
+
+  let tracer = opentelemetry_otlp::new_pipeline()
+    .with_env()
+    .with_trace_config(
+      trace::config().with_resource(Resource::new(vec![KeyValue::new("service.name", service_name)]))
+    )
+    .with_tonic()
+    .install_batch(opentelemetry::runtime::Tokio)
+    .map_err(|err| err.to_string())?;
+
+  Ok(Some(tracing_opentelemetry::layer().with_tracer(tracer)))
+}
+
+pub fn init() -> Result<(), String> {
+  #[cfg(feature = "otlp")]
+  if let Some(otlp_layer) = get_otlp_layer()? {
+    return Registry::default()
+      .with(otlp_layer)
+      .with(get_filter())
+      .with(get_fmt_layer())
+      .try_init()
+      .map_err(|err| err.to_string());
+  }
+
+  Registry::default()
+    .with(get_filter())
+    .with(get_fmt_layer())
+    .try_init()
+    .map_err(|err| err.to_string())
+}
+
+/**
+ * Export the spans that have not been sent yet
+ */
+pub fn shutdown() {
+  #[cfg(feature = "otlp")]
+  opentelemetry::global::shutdown_tracer_provider();
+}